@@ -0,0 +1,144 @@
+// SPDX-License-Identifier: EUPL-1.2
+
+use crate::{OrExit, OutputFormat, print_structured};
+use dialoguer::console::style;
+use rust_i18n::t;
+use serde::Serialize;
+use std::thread;
+use std::time::{Duration, Instant};
+use tiny4linux::{Camera, OffsetLabels, RawSample, RawWatcher, parse_hex};
+
+/// Bytes per line of the hex dumps.
+const BYTES_PER_LINE: usize = 16;
+
+/// Parses a byte written in hex, with or without `0x`, e.g. a selector.
+pub fn parse_hex_byte(text: &str) -> Result<u8, String> {
+    parse_hex(text).ok_or_else(|| t!("cli.debug.invalid_hex", value = text).to_string())
+}
+
+/// Parses an offset written in hex, with or without `0x`.
+pub fn parse_hex_offset(text: &str) -> Result<usize, String> {
+    parse_hex(text).ok_or_else(|| t!("cli.debug.invalid_hex", value = text).to_string())
+}
+
+/// Prints the raw data of the selectors 0x06 and 0x02 whenever a byte of them changes,
+/// highlighting the changed bytes, until the process is stopped.
+pub fn watch_raw_status(camera: Camera, interval: Duration) {
+    let labels = OffsetLabels::load().or_exit();
+    let mut watcher = RawWatcher::new(interval);
+    let started = Instant::now();
+
+    println!("{}", t!("cli.debug.watching"));
+    loop {
+        let samples = watcher.wait(&camera).or_exit();
+
+        println!();
+        println!("+{:.1}s", started.elapsed().as_secs_f64());
+        for sample in &samples {
+            print_sample(sample, &labels);
+        }
+
+        thread::sleep(interval);
+    }
+}
+
+fn print_sample(sample: &RawSample, labels: &OffsetLabels) {
+    for (line, bytes) in sample.bytes.chunks(BYTES_PER_LINE).enumerate() {
+        let offset = line * BYTES_PER_LINE;
+        let hex: Vec<String> = bytes
+            .iter()
+            .enumerate()
+            .map(|(index, byte)| {
+                let byte = format!("{:02x}", byte);
+                if sample.is_changed(offset + index) {
+                    style(byte).reverse().bold().to_string()
+                } else {
+                    byte
+                }
+            })
+            .collect();
+
+        let selector = if line == 0 {
+            format!("{:#04x}", sample.selector)
+        } else {
+            String::new()
+        };
+        println!("{:<6}{:02x}: {}", selector, offset, hex.join(" "));
+    }
+
+    for change in &sample.changes {
+        println!(
+            "      {:#04x}  {:02x} → {:02x}  {}",
+            change.offset,
+            change.old,
+            change.new,
+            labels
+                .describe(sample.selector, change.offset)
+                .unwrap_or_default()
+        );
+    }
+}
+
+/// A label of an offset, for the structured output of `debug labels`.
+#[derive(Serialize)]
+struct Label<'a> {
+    selector: u8,
+    offset: usize,
+    label: &'a str,
+}
+
+/// Labels an offset in the raw data of a selector, or removes its label if none is given.
+pub fn edit_label(selector: u8, offset: usize, label: Option<&str>) {
+    let mut labels = OffsetLabels::load().or_exit();
+
+    match label {
+        Some(label) => {
+            labels.insert(selector, offset, label);
+            labels.save().or_exit();
+            println!(
+                "{}",
+                t!(
+                    "cli.debug.labeled",
+                    selector = format!("{:#04x}", selector),
+                    offset = format!("{:#04x}", offset),
+                    label = label
+                )
+            );
+        }
+        None => {
+            labels.remove(selector, offset);
+            labels.save().or_exit();
+            println!(
+                "{}",
+                t!(
+                    "cli.debug.unlabeled",
+                    selector = format!("{:#04x}", selector),
+                    offset = format!("{:#04x}", offset)
+                )
+            );
+        }
+    }
+}
+
+pub fn list_labels(output: OutputFormat) {
+    let labels = OffsetLabels::load().or_exit();
+
+    if output != OutputFormat::Plain {
+        let labels: Vec<Label> = labels
+            .iter()
+            .map(|(selector, offset, label)| Label {
+                selector,
+                offset,
+                label,
+            })
+            .collect();
+        return print_structured(output, &labels);
+    }
+
+    if labels.iter().next().is_none() {
+        return println!("{}", t!("cli.debug.no_labels"));
+    }
+    for (selector, offset, label) in labels.iter() {
+        println!("{:#04x} {:#04x}  {}", selector, offset, label);
+    }
+}
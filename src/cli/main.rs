@@ -1,13 +1,38 @@
 // SPDX-License-Identifier: EUPL-1.2
 
-use clap::{Parser, Subcommand};
+mod debug;
+mod raw;
+mod tui;
+
+use crate::tui::run_tui;
+use clap::builder::{PossibleValue, PossibleValuesParser, TypedValueParser};
+use clap::{Parser, Subcommand, ValueEnum};
 use clap_complete::generate;
-use dialoguer::{FuzzySelect, Select};
+use dialoguer::{FuzzySelect, Input, Select};
 use rust_i18n::{i18n, set_locale, t};
-use tiny4linux::{AIMode, Camera, SleepMode, Tiny2Camera, get_language};
+use serde::Serialize;
+use std::fmt::Display;
+use std::fs;
+use std::io;
+use std::io::Read;
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+use tiny4linux::{
+    AIMode, AutoSleep, CAMERA_HINT, Camera, CameraRequest, CameraState, CameraStatus,
+    CronExpression, ExposureMode, LocalTime, PanTilt, Profile, Profiles, Recording, SYSTEMD_UNIT,
+    Schedule, ScheduleAction, ScheduleRule, Scheduler, Script, SettingChange, SleepMode, T4lError,
+    Tiny2Camera, TrackingSpeed, config_dir, get_language, systemd_units,
+};
 
 i18n!("src/locales", fallback = "en");
 
+/// How often `auto-sleep` checks whether an application is streaming from the camera.
+const AUTO_SLEEP_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How often `schedule run` checks for due rules, which has to be more than once a minute.
+const SCHEDULE_INTERVAL: Duration = Duration::from_secs(15);
+
 #[derive(Parser)]
 #[command(name = "t4l", bin_name = "t4l", version, about, long_about = None, disable_version_flag = true)]
 struct Args {
@@ -18,6 +43,19 @@ struct Args {
     // lang is being evaluated manually (without clap)
     #[arg(long, help = t!("cli.help.args.lang"), global = true)]
     lang: Option<String>,
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Plain, help = t!("cli.help.args.output"), global = true)]
+    output: OutputFormat,
+    #[arg(short, long, help = t!("cli.help.args.device"), global = true)]
+    device: Option<String>,
+    #[arg(long, value_name = "FILE", help = t!("cli.help.args.record"), global = true)]
+    record: Option<PathBuf>,
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq)]
+enum OutputFormat {
+    Plain,
+    Json,
+    Yaml,
 }
 
 #[derive(Subcommand)]
@@ -31,18 +69,28 @@ enum Command {
         #[command(subcommand)]
         action: Option<OnOffArg>,
     },
-    #[command(alias = "track", subcommand_required = false, about = t!("cli.help.tracking"))]
+    #[command(alias = "track", about = t!("cli.help.tracking"))]
     Tracking {
-        #[command(subcommand)]
-        tracking_mode: Option<TrackingArg>,
+        #[arg(value_parser = tracking_mode_parser())]
+        tracking_mode: Option<AIMode>,
     },
     #[command(alias = "tracking-speed", subcommand_required = false, about = t!("cli.help.speed"))]
     Speed {
         #[command(subcommand)]
         speed: Option<TrackingSpeedArg>,
     },
-    #[command(alias = "position", subcommand_required = false, about = t!("cli.help.preset"))]
-    Preset { position_id: Option<i8> },
+    #[command(
+        alias = "position",
+        subcommand_required = false,
+        args_conflicts_with_subcommands = true,
+        about = t!("cli.help.preset")
+    )]
+    Preset {
+        #[cfg(feature = "experimental")]
+        #[command(subcommand)]
+        action: Option<PresetArg>,
+        position_id: Option<i8>,
+    },
     #[command(about = t!("cli.help.hdr"))]
     Hdr {
         #[command(subcommand)]
@@ -53,8 +101,61 @@ enum Command {
         #[command(subcommand)]
         exposure_mode: Option<ExposureArg>,
     },
+    #[command(alias = "pan-tilt", subcommand_required = false, about = t!("cli.help.move"))]
+    Move {
+        #[command(subcommand)]
+        movement: Option<MoveArg>,
+    },
+    #[command(about = t!("cli.help.zoom"))]
+    Zoom { value: Option<i32> },
+    #[command(about = t!("cli.help.profile.command"))]
+    Profile {
+        #[command(subcommand)]
+        action: ProfileArg,
+    },
+    #[command(about = t!("cli.help.state.command"))]
+    State {
+        #[command(subcommand)]
+        action: StateArg,
+    },
+    #[command(about = t!("cli.help.auto_sleep.command"))]
+    AutoSleep {
+        #[arg(long, value_name = "SECONDS", default_value_t = 300, help = t!("cli.help.auto_sleep.idle_timeout"))]
+        idle_timeout: u64,
+    },
+    #[command(about = t!("cli.help.schedule.command"))]
+    Schedule {
+        #[command(subcommand)]
+        action: ScheduleArg,
+    },
+    #[command(about = t!("cli.help.run.command"))]
+    Run {
+        #[arg(help = t!("cli.help.run.file"))]
+        file: String,
+        #[arg(long, help = t!("cli.help.run.dry_run"))]
+        dry_run: bool,
+    },
+    #[command(about = t!("cli.help.recording.command"))]
+    Recording {
+        #[command(subcommand)]
+        action: RecordingArg,
+    },
+    #[command(about = t!("cli.help.debug.command"))]
+    Debug {
+        #[command(subcommand)]
+        action: DebugArg,
+    },
+    #[command(about = t!("cli.help.raw.command"))]
+    Raw {
+        #[command(subcommand)]
+        action: RawArg,
+    },
     #[command(about = t!("cli.help.info"))]
     Info,
+    #[command(alias = "dashboard", about = t!("cli.help.tui"))]
+    Tui,
+    #[command(alias = "devices", about = t!("cli.help.list"))]
+    List,
     #[command(about = t!("cli.help.version"))]
     Version,
     #[command(about = t!("cli.help.completions"))]
@@ -67,24 +168,6 @@ enum OnOffArg {
     Off,
 }
 
-#[derive(Subcommand, Clone)]
-enum TrackingArg {
-    #[command(aliases = ["none", "off"])]
-    Static,
-    #[command(aliases = ["standard", "on"])]
-    Normal,
-    #[command(alias = "close")]
-    CloseUp,
-    UpperBody,
-    Headless,
-    LowerBody,
-    Desk,
-    Whiteboard,
-    #[command(alias = "point")]
-    Hand,
-    Group,
-}
-
 #[derive(Subcommand, Clone)]
 enum TrackingSpeedArg {
     #[command(aliases = ["normal", "default", "slow", "low"])]
@@ -93,6 +176,179 @@ enum TrackingSpeedArg {
     Fast,
 }
 
+#[cfg(feature = "experimental")]
+#[derive(Subcommand, Clone)]
+enum PresetArg {
+    #[command(alias = "store", about = t!("cli.help.preset_save"))]
+    Save { position_id: Option<i8> },
+}
+
+#[derive(Subcommand, Clone)]
+enum MoveArg {
+    #[command(about = t!("cli.help.move_to"), allow_negative_numbers = true)]
+    To { pan: f64, tilt: f64 },
+    #[command(alias = "home")]
+    Center,
+    Left {
+        #[arg(default_value_t = 1)]
+        steps: i32,
+    },
+    Right {
+        #[arg(default_value_t = 1)]
+        steps: i32,
+    },
+    Up {
+        #[arg(default_value_t = 1)]
+        steps: i32,
+    },
+    Down {
+        #[arg(default_value_t = 1)]
+        steps: i32,
+    },
+}
+
+#[derive(Subcommand, Clone)]
+enum ProfileArg {
+    #[command(about = t!("cli.help.profile.apply"))]
+    Apply { name: String },
+    #[command(about = t!("cli.help.profile.save"))]
+    Save {
+        name: String,
+        #[arg(long, help = t!("cli.help.profile.preset"))]
+        preset: Option<i8>,
+    },
+    #[command(alias = "ls", about = t!("cli.help.profile.list"))]
+    List,
+    #[command(alias = "remove", about = t!("cli.help.profile.delete"))]
+    Delete { name: String },
+}
+
+#[derive(Subcommand, Clone)]
+enum StateArg {
+    #[command(about = t!("cli.help.state.save"))]
+    Save { file: String },
+    #[command(about = t!("cli.help.state.restore"))]
+    Restore { file: String },
+}
+
+#[derive(Subcommand, Clone)]
+enum RecordingArg {
+    #[command(about = t!("cli.help.recording.replay"))]
+    Replay {
+        #[arg(help = t!("cli.help.recording.file"))]
+        file: String,
+        #[arg(long, help = t!("cli.help.recording.no_wait"))]
+        no_wait: bool,
+    },
+    #[command(about = t!("cli.help.recording.import"))]
+    Import {
+        #[arg(help = t!("cli.help.recording.capture"))]
+        capture: String,
+        #[arg(help = t!("cli.help.recording.output"))]
+        file: String,
+    },
+}
+
+#[derive(Subcommand, Clone)]
+enum DebugArg {
+    #[command(about = t!("cli.help.debug.watch_status"))]
+    WatchStatus {
+        #[arg(long, value_name = "MILLISECONDS", default_value_t = 500, help = t!("cli.help.debug.interval"))]
+        interval: u64,
+    },
+    #[command(about = t!("cli.help.debug.label"))]
+    Label {
+        #[arg(value_parser = debug::parse_hex_byte, help = t!("cli.help.debug.selector"))]
+        selector: u8,
+        #[arg(value_parser = debug::parse_hex_offset, help = t!("cli.help.debug.offset"))]
+        offset: usize,
+        #[arg(help = t!("cli.help.debug.label_text"))]
+        label: Option<String>,
+    },
+    #[command(about = t!("cli.help.debug.labels"))]
+    Labels,
+}
+
+#[derive(Subcommand, Clone)]
+enum RawArg {
+    #[command(about = t!("cli.help.raw.get"))]
+    Get(RawTarget),
+    #[command(about = t!("cli.help.raw.set"))]
+    Set {
+        #[command(flatten)]
+        target: RawTarget,
+        #[arg(value_parser = raw::parse_hex_data, help = t!("cli.help.raw.data"))]
+        data: raw::HexData,
+    },
+    #[command(about = t!("cli.help.raw.len"))]
+    Len(RawTarget),
+    #[command(about = t!("cli.help.raw.info"))]
+    Info(RawTarget),
+    #[command(about = t!("cli.help.raw.min"))]
+    Min(RawTarget),
+    #[command(about = t!("cli.help.raw.max"))]
+    Max(RawTarget),
+    #[command(about = t!("cli.help.raw.def"))]
+    Def(RawTarget),
+    #[command(about = t!("cli.help.raw.res"))]
+    Res(RawTarget),
+}
+
+/// The unit and selector a raw request is sent to, and how its answer is printed.
+#[derive(clap::Args, Clone)]
+struct RawTarget {
+    #[arg(long, value_parser = debug::parse_hex_byte, help = t!("cli.help.raw.unit"))]
+    unit: u8,
+    #[arg(long, value_parser = debug::parse_hex_byte, help = t!("cli.help.raw.selector"))]
+    selector: u8,
+    #[arg(long, help = t!("cli.help.raw.hex"))]
+    hex: bool,
+}
+
+#[derive(Subcommand, Clone)]
+enum ScheduleArg {
+    #[command(alias = "ls", about = t!("cli.help.schedule.list"))]
+    List,
+    #[command(about = t!("cli.help.schedule.add"))]
+    Add {
+        when: String,
+        #[command(subcommand)]
+        action: ScheduleActionArg,
+    },
+    #[command(alias = "delete", about = t!("cli.help.schedule.remove"))]
+    Remove { number: usize },
+    #[command(about = t!("cli.help.schedule.run"))]
+    Run {
+        #[arg(long, help = t!("cli.help.schedule.due"))]
+        due: bool,
+    },
+    #[command(about = t!("cli.help.schedule.systemd"))]
+    Systemd {
+        #[arg(long, help = t!("cli.help.schedule.dir"))]
+        dir: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand, Clone)]
+enum ScheduleActionArg {
+    #[command(about = t!("cli.help.sleep"))]
+    Sleep,
+    #[command(about = t!("cli.help.wake"))]
+    Wake,
+    #[command(alias = "track", about = t!("cli.help.tracking"))]
+    Tracking {
+        #[arg(value_parser = tracking_mode_parser())]
+        tracking_mode: AIMode,
+    },
+    #[command(alias = "position", about = t!("cli.help.preset"))]
+    Preset {
+        #[arg(value_parser = clap::value_parser!(i8).range(1..))]
+        position_id: i8,
+    },
+    #[command(about = t!("cli.help.profile.apply"))]
+    Profile { name: String },
+}
+
 #[derive(Subcommand, Clone)]
 enum ExposureArg {
     Manual,
@@ -106,46 +362,119 @@ fn main() {
 
     let args = Args::parse();
 
-    let mut camera = Camera::new("OBSBOT Tiny 2").ok();
+    let output = args.output;
 
-    if camera.is_none() {
-        println!("{}", t!("shared.errors.no_camera"));
-        return;
+    match &args.subcommand {
+        Command::List => return list_cameras(output),
+        Command::Profile {
+            action: ProfileArg::List,
+        } => return list_profiles(output),
+        Command::Profile {
+            action: ProfileArg::Delete { name },
+        } => return delete_profile(name, output),
+        Command::Schedule { action } if !matches!(action, ScheduleArg::Run { .. }) => {
+            return edit_schedule(action.clone(), args.device.as_deref(), output);
+        }
+        Command::Run {
+            file,
+            dry_run: true,
+        } => return dry_run_script(file, output),
+        Command::Recording {
+            action: RecordingArg::Import { capture, file },
+        } => return import_usbmon(capture, file, output),
+        Command::Debug {
+            action:
+                DebugArg::Label {
+                    selector,
+                    offset,
+                    label,
+                },
+        } => return debug::edit_label(*selector, *offset, label.as_deref()),
+        Command::Debug {
+            action: DebugArg::Labels,
+        } => return debug::list_labels(output),
+        _ => {}
     }
 
-    if args.verbose {
-        camera.as_mut().unwrap().set_debugging(true);
+    let mut camera = match &args.device {
+        Some(selector) => Camera::select(selector),
+        None => Camera::new(CAMERA_HINT),
     }
+    .or_exit();
 
-    let camera = camera.unwrap();
+    if args.verbose {
+        camera.set_debugging(true);
+    }
+    if let Some(file) = &args.record {
+        camera.start_recording(file).or_exit();
+    }
 
     match &args.subcommand {
-        Command::Turn { action } => evaluate_sleep_arg(action.clone(), camera),
-        Command::Sleep => evaluate_sleep_arg(Option::from(OnOffArg::Off), camera),
-        Command::Wake => evaluate_sleep_arg(Option::from(OnOffArg::On), camera),
-        Command::Tracking { tracking_mode } => evaluate_tracking_arg(tracking_mode.clone(), camera),
-        Command::Speed { speed } => evaluate_speed_arg(speed.clone(), camera),
-        Command::Preset { position_id } => evaluate_preset_arg(*position_id, camera),
-        Command::Hdr { hdr_mode } => evaluate_hdr_arg(hdr_mode.clone(), camera),
-        Command::Exposure { exposure_mode } => evaluate_exposure_arg(exposure_mode.clone(), camera),
+        Command::Turn { action } => evaluate_sleep_arg(action.clone(), camera, output),
+        Command::Sleep => evaluate_sleep_arg(Option::from(OnOffArg::Off), camera, output),
+        Command::Wake => evaluate_sleep_arg(Option::from(OnOffArg::On), camera, output),
+        Command::Tracking { tracking_mode } => {
+            evaluate_tracking_arg(*tracking_mode, camera, output)
+        }
+        Command::Speed { speed } => evaluate_speed_arg(speed.clone(), camera, output),
+        #[cfg(feature = "experimental")]
+        Command::Preset {
+            action: Some(PresetArg::Save { position_id }),
+            ..
+        } => evaluate_preset_save_arg(*position_id, camera, output),
+        Command::Preset { position_id, .. } => evaluate_preset_arg(*position_id, camera, output),
+        Command::Hdr { hdr_mode } => evaluate_hdr_arg(hdr_mode.clone(), camera, output),
+        Command::Exposure { exposure_mode } => {
+            evaluate_exposure_arg(exposure_mode.clone(), camera, output)
+        }
+        Command::Move { movement } => evaluate_move_arg(movement.clone(), camera, output),
+        Command::Zoom { value } => evaluate_zoom_arg(*value, camera, output),
+        Command::Profile { action } => evaluate_profile_arg(action.clone(), camera, output),
+        Command::State { action } => evaluate_state_arg(action.clone(), camera, output),
+        Command::AutoSleep { idle_timeout } => {
+            run_auto_sleep(args.device.as_deref(), *idle_timeout, camera)
+        }
+        Command::Schedule {
+            action: ScheduleArg::Run { due },
+        } => run_schedule(*due, camera),
+        // edited before opening a camera
+        Command::Schedule { .. } => {}
+        Command::Run { file, .. } => run_script(file, camera, output),
+        Command::Recording {
+            action: RecordingArg::Replay { file, no_wait },
+        } => replay_recording(file, !no_wait, camera, output),
+        // imported without a camera
+        Command::Recording { .. } => {}
+        Command::Debug {
+            action: DebugArg::WatchStatus { interval },
+        } => debug::watch_raw_status(camera, Duration::from_millis(*interval)),
+        // labeled without a camera
+        Command::Debug { .. } => {}
+        Command::Raw { action } => raw::evaluate_raw_arg(action.clone(), camera, output),
         Command::Info => {
-            let info = camera.get_status();
+            let info = camera.get_status().or_exit();
 
-            if info.is_err() {
-                println!("{}", t!("cli.errors.info_error"),);
-                return;
+            if output != OutputFormat::Plain {
+                print_structured(output, &info);
             } else {
-                let info = info.unwrap();
-
                 println!("{}:", t!("shared.info.camera_status"));
-                println!("  💤  {}: {}", t!("shared.info.sleep_mode"), info.awake);
-                println!("  🤖  {}: {}", t!("shared.info.ai_mode"), info.ai_mode);
-                println!("  🏃  {}: {}", t!("shared.info.tracking_speed"), info.speed);
-                println!("  💐  {}: {}", t!("shared.info.hdr"), info.hdr_on);
+                for line in status_lines(&info) {
+                    println!("{}", line);
+                }
             }
         }
+        Command::Tui => run_tui(camera),
+        // listed before opening a camera
+        Command::List => {}
         Command::Version => {
-            println!("t4l version: {}", env!("CARGO_PKG_VERSION"));
+            if output != OutputFormat::Plain {
+                print_structured(
+                    output,
+                    &serde_json::json!({ "version": env!("CARGO_PKG_VERSION") }),
+                );
+            } else {
+                println!("t4l version: {}", env!("CARGO_PKG_VERSION"));
+            }
         }
         Command::Completions { shell } => {
             use clap::CommandFactory;
@@ -155,21 +484,614 @@ fn main() {
     }
 }
 
+/// The fields of the status as shown by `info` and the dashboard of `tui`.
+fn status_lines(info: &CameraStatus) -> Vec<String> {
+    vec![
+        format!("  💤  {}: {}", t!("shared.info.sleep_mode"), info.awake),
+        format!("  🤖  {}: {}", t!("shared.info.ai_mode"), info.ai_mode),
+        format!("  🏃  {}: {}", t!("shared.info.tracking_speed"), info.speed),
+        format!("  💐  {}: {}", t!("shared.info.hdr"), info.hdr_on),
+    ]
+}
+
 struct SelectionOption<'a, T> {
     result: T,
     option: &'a str,
 }
 
-fn evaluate_sleep_arg(state: Option<OnOffArg>, camera: Camera) {
-    match state {
-        Some(OnOffArg::Off) => {
-            println!("{}", t!("cli.sleep.response_to_sleep"));
-            camera.set_sleep_mode(SleepMode::Sleep).unwrap();
+fn print_structured<T: Serialize>(output: OutputFormat, value: &T) {
+    match output {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(value).unwrap()),
+        OutputFormat::Yaml => print!("{}", serde_yaml::to_string(value).unwrap()),
+        OutputFormat::Plain => {}
+    }
+}
+
+/// A profile with its name, for the structured output of the profile commands.
+#[derive(Serialize)]
+struct NamedProfile<'a> {
+    name: &'a str,
+    profile: &'a Profile,
+}
+
+fn list_profiles(output: OutputFormat) {
+    let profiles = Profiles::load().or_exit();
+
+    if output != OutputFormat::Plain {
+        return print_structured(output, &profiles);
+    }
+
+    if profiles.names().is_empty() {
+        println!(
+            "{}",
+            t!(
+                "cli.profile.none",
+                path = Profiles::path().display().to_string()
+            )
+        );
+        return;
+    }
+
+    println!("{}:", t!("cli.profile.profiles"));
+    for (name, profile) in profiles.iter() {
+        println!("  {}", name);
+        if let Ok(serde_json::Value::Object(settings)) = serde_json::to_value(profile) {
+            for (setting, value) in settings {
+                println!(
+                    "      {}: {}",
+                    setting,
+                    value.as_str().unwrap_or(&value.to_string())
+                );
+            }
+        }
+    }
+}
+
+fn delete_profile(name: &str, output: OutputFormat) {
+    let mut profiles = Profiles::load().or_exit();
+    let profile = profiles.remove(name).or_exit();
+    profiles.save().or_exit();
+
+    if output != OutputFormat::Plain {
+        print_structured(
+            output,
+            &NamedProfile {
+                name,
+                profile: &profile,
+            },
+        );
+    } else {
+        println!("{}", t!("cli.profile.deleted", name = name));
+    }
+}
+
+fn evaluate_profile_arg(action: ProfileArg, camera: Camera, output: OutputFormat) {
+    let mut profiles = Profiles::load().or_exit();
+
+    let (name, profile, message) = match &action {
+        ProfileArg::Apply { name } => {
+            let profile = profiles.get(name).or_exit().clone();
+            profile.apply(&camera).or_exit();
+            (name, profile, t!("cli.profile.applied", name = name))
+        }
+        ProfileArg::Save { name, preset } => {
+            let profile = Profile {
+                preset: *preset,
+                ..Profile::current(&camera).or_exit()
+            };
+            profiles.insert(name, profile.clone());
+            profiles.save().or_exit();
+            (name, profile, t!("cli.profile.saved", name = name))
+        }
+        // handled before opening a camera
+        ProfileArg::List | ProfileArg::Delete { .. } => return,
+    };
+
+    if output != OutputFormat::Plain {
+        print_structured(
+            output,
+            &NamedProfile {
+                name,
+                profile: &profile,
+            },
+        );
+    } else {
+        println!("{}", message);
+    }
+}
+
+/// Saves or restores the state of the camera as json, where the file `-` is stdout or stdin.
+fn evaluate_state_arg(action: StateArg, camera: Camera, output: OutputFormat) {
+    match action {
+        StateArg::Save { file } => {
+            let state = camera.snapshot().or_exit();
+            let json = serde_json::to_string_pretty(&state).unwrap();
+
+            if file == "-" {
+                return println!("{}", json);
+            }
+            fs::write(&file, json + "\n")
+                .map_err(T4lError::from)
+                .or_exit();
+
+            if output != OutputFormat::Plain {
+                print_structured(output, &state);
+            } else {
+                println!("{}", t!("cli.state.saved", file = file));
+            }
+        }
+        StateArg::Restore { file } => {
+            let json = read_input(&file).or_exit();
+
+            let state: CameraState = serde_json::from_str(&json)
+                .map_err(|error| T4lError::InvalidConfig(file.clone(), error.to_string()))
+                .or_exit();
+            let changed = camera.restore(&state).or_exit();
+
+            if output != OutputFormat::Plain {
+                print_structured(output, &state);
+            } else {
+                println!("{}", t!("cli.state.restored", file = file, count = changed));
+            }
+        }
+    }
+}
+
+/// Reads the file, where `-` is stdin.
+fn read_input(file: &str) -> Result<String, T4lError> {
+    if file == "-" {
+        let mut text = String::new();
+        io::stdin().read_to_string(&mut text).map(|_| text)
+    } else {
+        fs::read_to_string(file)
+    }
+    .map_err(T4lError::from)
+}
+
+/// Reads a binary file, where the file `-` is stdin.
+fn read_input_bytes(file: &str) -> Result<Vec<u8>, T4lError> {
+    if file == "-" {
+        let mut bytes = Vec::new();
+        io::stdin().read_to_end(&mut bytes).map(|_| bytes)
+    } else {
+        fs::read(file)
+    }
+    .map_err(T4lError::from)
+}
+
+/// A command of a script with its line number, for the structured output of `run`.
+#[derive(Serialize)]
+struct ScriptStep {
+    line: usize,
+    command: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    requests: Option<Vec<CameraRequest>>,
+}
+
+/// Runs the commands of the script against the camera, stopping at the first that fails.
+fn run_script(file: &str, camera: Camera, output: OutputFormat) {
+    let script = Script::parse(&read_input(file).or_exit()).or_exit();
+    let mut steps = Vec::new();
+
+    script
+        .run(&camera, |line, command| {
+            if output == OutputFormat::Plain {
+                println!("{:>3}  {}", line, command);
+            }
+            steps.push(ScriptStep {
+                line,
+                command: command.to_string(),
+                requests: None,
+            });
+        })
+        .or_exit();
+
+    print_structured(output, &steps);
+}
+
+/// Prints the requests the commands of the script would send, without opening a camera.
+fn dry_run_script(file: &str, output: OutputFormat) {
+    let script = Script::parse(&read_input(file).or_exit()).or_exit();
+    let steps: Vec<ScriptStep> = script
+        .commands()
+        .iter()
+        .map(|(line, command)| ScriptStep {
+            line: *line,
+            command: command.to_string(),
+            requests: Some(
+                command
+                    .requests()
+                    .map_err(|error| T4lError::ScriptFailed(*line, Box::new(error)))
+                    .or_exit(),
+            ),
+        })
+        .collect();
+
+    if output != OutputFormat::Plain {
+        return print_structured(output, &steps);
+    }
+
+    for step in steps {
+        println!("{:>3}  {}", step.line, step.command);
+        for request in step.requests.unwrap_or_default() {
+            println!("       {}", request);
+        }
+    }
+}
+
+/// Sends the requests of a recording to the camera again, stopping at the first that fails.
+fn replay_recording(file: &str, paced: bool, camera: Camera, output: OutputFormat) {
+    let recording = Recording::parse(&read_input(file).or_exit()).or_exit();
+    let mut replayed = Vec::new();
+
+    recording
+        .replay(&camera, paced, |line, request| {
+            if output == OutputFormat::Plain {
+                println!("{:>4}  {}", line, request);
+            }
+            replayed.push(ScriptStep {
+                line,
+                command: request.to_string(),
+                requests: None,
+            });
+        })
+        .or_exit();
+
+    print_structured(output, &replayed);
+}
+
+/// Converts a usbmon capture, as pcap or text, into a recording, where the file `-` is stdin or stdout.
+fn import_usbmon(capture: &str, file: &str, output: OutputFormat) {
+    let (recording, truncated) =
+        Recording::from_capture(&read_input_bytes(capture).or_exit()).or_exit();
+    let count = recording.requests().len();
+
+    if truncated > 0 {
+        eprintln!("{}", t!("cli.recording.truncated", count = truncated));
+    }
+    if file == "-" {
+        return print!("{}", recording);
+    }
+    fs::write(file, recording.to_string())
+        .map_err(T4lError::from)
+        .or_exit();
+
+    if output != OutputFormat::Plain {
+        print_structured(
+            output,
+            &serde_json::json!({ "file": file, "requests": count, "truncated": truncated }),
+        );
+    } else {
+        println!(
+            "{}",
+            t!("cli.recording.imported", count = count, file = file)
+        );
+    }
+}
+
+/// Puts the camera to sleep while no application streams from it, until the process is stopped.
+fn run_auto_sleep(selector: Option<&str>, idle_timeout: u64, camera: Camera) {
+    let device = match selector {
+        Some(selector) => tiny4linux::find_camera(CAMERA_HINT, selector),
+        None => tiny4linux::list_cameras(CAMERA_HINT)
+            .into_iter()
+            .next()
+            .ok_or(T4lError::NoCameraFound),
+    }
+    .or_exit();
+
+    println!(
+        "{}",
+        t!(
+            "cli.auto_sleep.watching",
+            path = device.path,
+            seconds = idle_timeout
+        )
+    );
+
+    let mut auto_sleep = AutoSleep::new(&device.path, Duration::from_secs(idle_timeout));
+    loop {
+        // a failed check, e.g. while the camera is reconnecting, is retried with the next one
+        match auto_sleep.poll(&camera) {
+            Ok(Some(SleepMode::Awake)) => println!("{}", t!("cli.auto_sleep.woken_up")),
+            Ok(Some(_)) => println!("{}", t!("cli.auto_sleep.put_to_sleep")),
+            Ok(None) => {}
+            Err(error) => eprintln!("{}", error),
+        }
+        thread::sleep(AUTO_SLEEP_INTERVAL);
+    }
+}
+
+/// A rule with its number, for the structured output of the schedule commands.
+#[derive(Serialize)]
+struct NumberedRule<'a> {
+    number: usize,
+    #[serde(flatten)]
+    rule: &'a ScheduleRule,
+}
+
+/// Lists, adds or removes rules of the schedule, or generates the systemd units running it.
+fn edit_schedule(action: ScheduleArg, selector: Option<&str>, output: OutputFormat) {
+    let mut schedule = Schedule::load().or_exit();
+
+    let (number, rule, message) = match action {
+        ScheduleArg::List => return list_schedule(&schedule, output),
+        ScheduleArg::Systemd { dir } => return generate_systemd_units(&schedule, dir, selector),
+        ScheduleArg::Add { when, action } => {
+            let rule = ScheduleRule {
+                when: when.parse::<CronExpression>().or_exit(),
+                action: schedule_action(action),
+            };
+            let number = schedule.add(rule.clone());
+            (number, rule, t!("cli.schedule.added", number = number))
+        }
+        ScheduleArg::Remove { number } => {
+            let rule = schedule.remove(number).or_exit();
+            (number, rule, t!("cli.schedule.removed", number = number))
         }
-        Some(OnOffArg::On) => {
-            println!("{}", t!("cli.sleep.response_to_awake"));
-            camera.set_sleep_mode(SleepMode::Awake).unwrap();
+        // run after opening a camera
+        ScheduleArg::Run { .. } => return,
+    };
+    schedule.save().or_exit();
+
+    if output != OutputFormat::Plain {
+        print_structured(
+            output,
+            &NumberedRule {
+                number,
+                rule: &rule,
+            },
+        );
+    } else {
+        println!("{}", message);
+    }
+}
+
+fn schedule_action(action: ScheduleActionArg) -> ScheduleAction {
+    match action {
+        ScheduleActionArg::Sleep => ScheduleAction::Sleep,
+        ScheduleActionArg::Wake => ScheduleAction::Wake,
+        ScheduleActionArg::Tracking { tracking_mode } => ScheduleAction::AiMode {
+            ai_mode: tracking_mode,
+        },
+        ScheduleActionArg::Preset { position_id } => ScheduleAction::Preset {
+            preset: position_id,
+        },
+        ScheduleActionArg::Profile { name } => {
+            // fail early on typos, although the profile is only looked up once the rule runs
+            Profiles::load().or_exit().get(&name).or_exit();
+            ScheduleAction::Profile { profile: name }
         }
+    }
+}
+
+fn list_schedule(schedule: &Schedule, output: OutputFormat) {
+    let rules: Vec<NumberedRule> = (1..)
+        .zip(schedule.rules())
+        .map(|(number, rule)| NumberedRule { number, rule })
+        .collect();
+
+    if output != OutputFormat::Plain {
+        return print_structured(output, &rules);
+    }
+
+    if rules.is_empty() {
+        println!(
+            "{}",
+            t!(
+                "cli.schedule.none",
+                path = Schedule::path().display().to_string()
+            )
+        );
+        return;
+    }
+
+    let width = rules
+        .iter()
+        .map(|rule| rule.rule.when.to_string().len())
+        .max()
+        .unwrap_or_default();
+
+    println!("{}:", t!("cli.schedule.rules"));
+    for NumberedRule { number, rule } in rules {
+        println!(
+            "  {:>2}  {:<width$}  {}",
+            number,
+            rule.when.to_string(),
+            rule.action
+        );
+    }
+}
+
+/// Writes a systemd user timer and service running the due rules with this executable.
+fn generate_systemd_units(schedule: &Schedule, dir: Option<PathBuf>, selector: Option<&str>) {
+    let executable = std::env::current_exe().map_err(T4lError::from).or_exit();
+    let mut command = format!("\"{}\" schedule run --due", executable.display());
+    if let Some(selector) = selector {
+        command += &format!(" --device \"{}\"", selector);
+    }
+
+    let units = systemd_units(schedule, &command).or_exit();
+    let dir = dir.unwrap_or_else(|| config_dir().with_file_name("systemd").join("user"));
+    fs::create_dir_all(&dir).map_err(T4lError::from).or_exit();
+
+    for (name, contents) in units {
+        let path = dir.join(name);
+        fs::write(&path, contents).map_err(T4lError::from).or_exit();
+        println!(
+            "{}",
+            t!("cli.schedule.written", path = path.display().to_string())
+        );
+    }
+
+    println!(
+        "{}",
+        t!(
+            "cli.schedule.enable",
+            command = format!(
+                "systemctl --user daemon-reload && systemctl --user enable --now {}.timer",
+                SYSTEMD_UNIT
+            )
+        )
+    );
+}
+
+/// Runs the rules of the schedule once they're due, until the process is stopped,
+/// or only the rules due now.
+fn run_schedule(due: bool, camera: Camera) {
+    if due {
+        let schedule = Schedule::load().or_exit();
+        let mut exit_code = 0;
+
+        for rule in schedule.due(&LocalTime::now()) {
+            let result = rule.action.run(&camera);
+            if let Err(error) = &result {
+                exit_code = error.exit_code();
+            }
+            report_rule(rule, result);
+        }
+        std::process::exit(exit_code);
+    }
+
+    println!(
+        "{}",
+        t!(
+            "cli.schedule.running",
+            path = Schedule::path().display().to_string()
+        )
+    );
+
+    let mut scheduler = Scheduler::new();
+    loop {
+        // a schedule that became invalid is reported, but the next fix is still picked up
+        match scheduler.poll(&camera) {
+            Ok(ran) => ran
+                .into_iter()
+                .for_each(|(rule, result)| report_rule(&rule, result)),
+            Err(error) => eprintln!("{}", error),
+        }
+        thread::sleep(SCHEDULE_INTERVAL);
+    }
+}
+
+fn report_rule(rule: &ScheduleRule, result: Result<(), T4lError>) {
+    match result {
+        Ok(()) => println!(
+            "{}",
+            t!("cli.schedule.ran", when = rule.when, action = rule.action)
+        ),
+        Err(error) => eprintln!(
+            "{}",
+            t!(
+                "cli.schedule.failed",
+                when = rule.when,
+                action = rule.action,
+                error = error
+            )
+        ),
+    }
+}
+
+fn list_cameras(output: OutputFormat) {
+    let cameras = tiny4linux::list_cameras(CAMERA_HINT);
+
+    if output != OutputFormat::Plain {
+        return print_structured(output, &cameras);
+    }
+
+    if cameras.is_empty() {
+        eprintln!("{}", T4lError::NoCameraFound);
+        std::process::exit(T4lError::NoCameraFound.exit_code());
+    }
+
+    println!("{}:", t!("cli.list.cameras"));
+    for (index, camera) in cameras.iter().enumerate() {
+        println!("  [{}] {}", index, camera.card);
+        println!("      {}: {}", t!("cli.list.path"), camera.path);
+        println!("      {}: {}", t!("cli.list.bus"), camera.bus_info);
+        if let Some(serial) = &camera.serial {
+            println!("      {}: {}", t!("cli.list.serial"), serial);
+        }
+    }
+}
+
+/// Ends the process for errors that leave nothing else to do, with the message on stderr.
+trait OrExit<T> {
+    fn or_exit(self) -> T;
+}
+
+impl<T> OrExit<T> for Result<T, T4lError> {
+    fn or_exit(self) -> T {
+        self.unwrap_or_else(|error| {
+            eprintln!("{}", error);
+            std::process::exit(error.exit_code());
+        })
+    }
+}
+
+impl<T> OrExit<T> for Result<T, dialoguer::Error> {
+    fn or_exit(self) -> T {
+        self.map_err(|error| T4lError::from(io::Error::from(error)))
+            .or_exit()
+    }
+}
+
+/// Prints the outcome of a change, either as the given message or structured.
+///
+/// Failed changes end the process with a non-zero exit code, as there is nothing left to do.
+fn report_change<T: Serialize>(
+    output: OutputFormat,
+    change: &SettingChange<T>,
+    message: impl Display,
+) {
+    match (&change.error, output) {
+        (_, OutputFormat::Json | OutputFormat::Yaml) => print_structured(output, change),
+        (Some(error), OutputFormat::Plain) => eprintln!("{}", error.message),
+        (None, OutputFormat::Plain) => println!("{}", message),
+    }
+
+    if let Some(error) = &change.error {
+        std::process::exit(error.exit_code);
+    }
+}
+
+/// Turns off the tracking before the gimbal is moved by hand, as it would move it back otherwise.
+///
+/// In structured output, only a failure is reported, to keep a single document per command.
+fn stop_tracking(camera: &Camera, output: OutputFormat, message: impl Display) {
+    let change = SettingChange::apply(
+        "ai_mode",
+        AIMode::NoTracking,
+        || camera.get_ai_mode(),
+        |mode| camera.set_ai_mode(mode),
+    );
+
+    if output == OutputFormat::Plain || !change.is_ok() {
+        report_change(output, &change, message);
+    }
+}
+
+fn evaluate_sleep_arg(state: Option<OnOffArg>, camera: Camera, output: OutputFormat) {
+    let apply = |mode: SleepMode| {
+        SettingChange::apply(
+            "sleep_mode",
+            mode,
+            || camera.get_sleep_mode(),
+            |mode| camera.set_sleep_mode(mode),
+        )
+    };
+
+    match state {
+        Some(OnOffArg::Off) => report_change(
+            output,
+            &apply(SleepMode::Sleep),
+            t!("cli.sleep.response_to_sleep"),
+        ),
+        Some(OnOffArg::On) => report_change(
+            output,
+            &apply(SleepMode::Awake),
+            t!("cli.sleep.response_to_awake"),
+        ),
         None => {
             let option_on1 = t!("cli.sleep.option_on1");
             let option_off1 = t!("cli.sleep.option_off1");
@@ -209,141 +1131,93 @@ fn evaluate_sleep_arg(state: Option<OnOffArg>, camera: Camera) {
                         .collect::<Vec<&str>>(),
                 )
                 .interact()
-                .unwrap();
+                .or_exit();
 
-            evaluate_sleep_arg(Option::from(options[selection].result.clone()), camera);
+            evaluate_sleep_arg(
+                Option::from(options[selection].result.clone()),
+                camera,
+                output,
+            );
         }
     }
 }
 
-fn evaluate_tracking_arg(tracking_mode: Option<TrackingArg>, camera: Camera) {
-    let response_setting_to = t!("cli.tracking_mode.response_setting_to");
-    let static_mode = t!("cli.tracking_mode.static");
-    let normal = t!("cli.tracking_mode.normal");
-    let close_up = t!("cli.tracking_mode.close_up");
-    let upper_body = t!("cli.tracking_mode.upper_body");
-    let headless = t!("cli.tracking_mode.headless");
-    let lower_body = t!("cli.tracking_mode.lower_body");
-    let desk = t!("cli.tracking_mode.desk");
-    let whiteboard = t!("cli.tracking_mode.whiteboard");
-    let hand = t!("cli.tracking_mode.hand");
-    let group = t!("cli.tracking_mode.group");
-
-    match tracking_mode {
-        Some(TrackingArg::Static) => {
-            println!("{} {}", response_setting_to, static_mode);
-            camera.set_ai_mode(AIMode::NoTracking).unwrap();
-        }
-        Some(TrackingArg::Normal) => {
-            println!("{} {}", response_setting_to, normal);
-            camera.set_ai_mode(AIMode::NormalTracking).unwrap();
-        }
-        Some(TrackingArg::CloseUp) => {
-            println!("{} {}", response_setting_to, close_up);
-            camera.set_ai_mode(AIMode::CloseUp).unwrap();
-        }
-        Some(TrackingArg::UpperBody) => {
-            println!("{} {}", response_setting_to, upper_body);
-            camera.set_ai_mode(AIMode::UpperBody).unwrap();
-        }
-        Some(TrackingArg::Headless) => {
-            println!("{} {}", response_setting_to, headless);
-            camera.set_ai_mode(AIMode::Headless).unwrap();
-        }
-        Some(TrackingArg::LowerBody) => {
-            println!("{} {}", response_setting_to, lower_body);
-            camera.set_ai_mode(AIMode::LowerBody).unwrap();
-        }
-        Some(TrackingArg::Desk) => {
-            println!("{} {}", response_setting_to, desk);
-            camera.set_ai_mode(AIMode::DeskMode).unwrap();
-        }
-        Some(TrackingArg::Whiteboard) => {
-            println!("{} {}", response_setting_to, whiteboard);
-            camera.set_ai_mode(AIMode::Whiteboard).unwrap();
-        }
-        Some(TrackingArg::Hand) => {
-            println!("{} {}", response_setting_to, hand);
-            camera.set_ai_mode(AIMode::Hand).unwrap();
-        }
-        Some(TrackingArg::Group) => {
-            println!("{} {}", response_setting_to, group);
-            camera.set_ai_mode(AIMode::Group).unwrap();
-        }
-        None => {
-            let options = [
-                SelectionOption {
-                    result: TrackingArg::Static,
-                    option: &static_mode,
-                },
-                SelectionOption {
-                    result: TrackingArg::Normal,
-                    option: &normal,
-                },
-                SelectionOption {
-                    result: TrackingArg::CloseUp,
-                    option: &close_up,
-                },
-                SelectionOption {
-                    result: TrackingArg::UpperBody,
-                    option: &upper_body,
-                },
-                SelectionOption {
-                    result: TrackingArg::Headless,
-                    option: &headless,
-                },
-                SelectionOption {
-                    result: TrackingArg::LowerBody,
-                    option: &lower_body,
-                },
-                SelectionOption {
-                    result: TrackingArg::Desk,
-                    option: &desk,
-                },
-                SelectionOption {
-                    result: TrackingArg::Whiteboard,
-                    option: &whiteboard,
-                },
-                SelectionOption {
-                    result: TrackingArg::Hand,
-                    option: &hand,
-                },
-                SelectionOption {
-                    result: TrackingArg::Group,
-                    option: &group,
-                },
-            ];
-            let selection = FuzzySelect::new()
-                .with_prompt(t!("cli.tracking_mode.request_should_be"))
-                .default(0)
-                .items(
-                    &options
-                        .iter()
-                        .map(|option| option.option)
-                        .collect::<Vec<&str>>(),
-                )
-                .interact()
-                .unwrap();
+/// Parses the tracking modes by their names and aliases, which are offered for completion.
+fn tracking_mode_parser() -> impl TypedValueParser<Value = AIMode> {
+    PossibleValuesParser::new(
+        AIMode::ALL.map(|mode| PossibleValue::new(mode.name()).aliases(mode.aliases())),
+    )
+    .map(|name| name.parse().unwrap_or(AIMode::Unknown))
+}
 
-            evaluate_tracking_arg(Option::from(options[selection].result.clone()), camera);
-        }
+fn tracking_mode_label(mode: AIMode) -> String {
+    match mode {
+        AIMode::NoTracking => t!("cli.tracking_mode.static"),
+        AIMode::NormalTracking => t!("cli.tracking_mode.normal"),
+        AIMode::CloseUp => t!("cli.tracking_mode.close_up"),
+        AIMode::UpperBody => t!("cli.tracking_mode.upper_body"),
+        AIMode::Headless => t!("cli.tracking_mode.headless"),
+        AIMode::LowerBody => t!("cli.tracking_mode.lower_body"),
+        AIMode::DeskMode => t!("cli.tracking_mode.desk"),
+        AIMode::Whiteboard => t!("cli.tracking_mode.whiteboard"),
+        AIMode::Hand => t!("cli.tracking_mode.hand"),
+        AIMode::Group => t!("cli.tracking_mode.group"),
+        AIMode::Unknown => return mode.to_string(),
     }
+    .to_string()
+}
+
+fn evaluate_tracking_arg(tracking_mode: Option<AIMode>, camera: Camera, output: OutputFormat) {
+    let Some(mode) = tracking_mode else {
+        let labels: Vec<String> = AIMode::ALL.map(tracking_mode_label).to_vec();
+        let selection = FuzzySelect::new()
+            .with_prompt(t!("cli.tracking_mode.request_should_be"))
+            .default(0)
+            .items(&labels)
+            .interact()
+            .or_exit();
+
+        return evaluate_tracking_arg(Some(AIMode::ALL[selection]), camera, output);
+    };
+
+    let change = SettingChange::apply(
+        "ai_mode",
+        mode,
+        || camera.get_ai_mode(),
+        |mode| camera.set_ai_mode(mode),
+    );
+    report_change(
+        output,
+        &change,
+        format!(
+            "{} {}",
+            t!("cli.tracking_mode.response_setting_to"),
+            tracking_mode_label(mode)
+        ),
+    );
 }
 
-fn evaluate_speed_arg(speed: Option<TrackingSpeedArg>, camera: Camera) {
+fn evaluate_speed_arg(speed: Option<TrackingSpeedArg>, camera: Camera, output: OutputFormat) {
+    let apply = |speed: TrackingSpeed| {
+        SettingChange::apply(
+            "tracking_speed",
+            speed,
+            || camera.get_tracking_speed(),
+            |speed| camera.set_tracking_speed(speed),
+        )
+    };
+
     match speed {
-        Some(TrackingSpeedArg::Standard) => {
-            println!("{}", t!("cli.tracking_speed.response_to_standard"));
-            camera
-                .set_tracking_speed(tiny4linux::TrackingSpeed::Standard)
-                .unwrap();
-        }
-        Some(TrackingSpeedArg::Fast) => {
-            println!("{}", t!("cli.tracking_speed.response_to_fast"));
-            camera
-                .set_tracking_speed(tiny4linux::TrackingSpeed::Sport)
-                .unwrap();
-        }
+        Some(TrackingSpeedArg::Standard) => report_change(
+            output,
+            &apply(TrackingSpeed::Standard),
+            t!("cli.tracking_speed.response_to_standard"),
+        ),
+        Some(TrackingSpeedArg::Fast) => report_change(
+            output,
+            &apply(TrackingSpeed::Sport),
+            t!("cli.tracking_speed.response_to_fast"),
+        ),
         None => {
             let option_standard = t!("cli.tracking_speed.option_standard");
             let option_sport = t!("cli.tracking_speed.option_sport");
@@ -368,14 +1242,18 @@ fn evaluate_speed_arg(speed: Option<TrackingSpeedArg>, camera: Camera) {
                         .collect::<Vec<&str>>(),
                 )
                 .interact()
-                .unwrap();
+                .or_exit();
 
-            evaluate_speed_arg(Option::from(options[selection].result.clone()), camera);
+            evaluate_speed_arg(
+                Option::from(options[selection].result.clone()),
+                camera,
+                output,
+            );
         }
     }
 }
 
-fn evaluate_preset_arg(position_id: Option<i8>, camera: Camera) {
+fn evaluate_preset_arg(position_id: Option<i8>, camera: Camera, output: OutputFormat) {
     if position_id.is_none() {
         let options = [1, 2, 3];
         let selection = Select::new()
@@ -383,35 +1261,77 @@ fn evaluate_preset_arg(position_id: Option<i8>, camera: Camera) {
             .default(0)
             .items(options)
             .interact()
-            .unwrap();
+            .or_exit();
 
-        return evaluate_preset_arg(Option::from(options[selection]), camera);
+        return evaluate_preset_arg(Option::from(options[selection]), camera, output);
     }
 
-    println!("{}", t!("cli.preset_position.stopping_tracking"));
-    camera.set_ai_mode(AIMode::NoTracking).unwrap();
+    stop_tracking(&camera, output, t!("cli.preset_position.stopping_tracking"));
 
-    println!(
-        "{}",
+    let position_id = position_id.unwrap();
+    let change = SettingChange::from_result(
+        "preset_position",
+        None,
+        camera
+            .goto_preset_position(position_id - 1)
+            .map(|_| position_id),
+    );
+    report_change(
+        output,
+        &change,
         t!(
             "cli.preset_position.response_to_position",
-            position_id = position_id.unwrap()
+            position_id = position_id
         ),
     );
-    camera
-        .goto_preset_position(position_id.unwrap() - 1)
-        .unwrap();
 }
 
-fn evaluate_hdr_arg(hdr_mode: Option<OnOffArg>, camera: Camera) {
+#[cfg(feature = "experimental")]
+fn evaluate_preset_save_arg(position_id: Option<i8>, camera: Camera, output: OutputFormat) {
+    if position_id.is_none() {
+        let options = [1, 2, 3];
+        let selection = Select::new()
+            .with_prompt(t!("cli.preset_position.request_save_position_id"))
+            .default(0)
+            .items(options)
+            .interact()
+            .or_exit();
+
+        return evaluate_preset_save_arg(Option::from(options[selection]), camera, output);
+    }
+
+    let position_id = position_id.unwrap();
+    let change = SettingChange::from_result(
+        "saved_preset_position",
+        None,
+        camera
+            .save_preset_position(position_id - 1)
+            .map(|_| position_id),
+    );
+    report_change(
+        output,
+        &change,
+        t!(
+            "cli.preset_position.response_to_save",
+            position_id = position_id
+        ),
+    );
+}
+
+fn evaluate_hdr_arg(hdr_mode: Option<OnOffArg>, camera: Camera, output: OutputFormat) {
+    let apply = |hdr_on: bool| {
+        SettingChange::apply(
+            "hdr",
+            hdr_on,
+            || Ok(camera.get_status()?.hdr_on),
+            |hdr_on| camera.set_hdr_mode(hdr_on),
+        )
+    };
+
     match hdr_mode {
-        Some(OnOffArg::On) => {
-            println!("{}", t!("cli.hdr.response_to_hdr_on"));
-            camera.set_hdr_mode(true).unwrap();
-        }
+        Some(OnOffArg::On) => report_change(output, &apply(true), t!("cli.hdr.response_to_hdr_on")),
         Some(OnOffArg::Off) => {
-            println!("{}", t!("cli.hdr.response_to_hdr_off"));
-            camera.set_hdr_mode(false).unwrap();
+            report_change(output, &apply(false), t!("cli.hdr.response_to_hdr_off"))
         }
         None => {
             let option_on = t!("shared.options.hdr.on");
@@ -437,33 +1357,43 @@ fn evaluate_hdr_arg(hdr_mode: Option<OnOffArg>, camera: Camera) {
                         .collect::<Vec<&str>>(),
                 )
                 .interact()
-                .unwrap();
+                .or_exit();
 
-            evaluate_hdr_arg(Option::from(options[selection].result.clone()), camera);
+            evaluate_hdr_arg(
+                Option::from(options[selection].result.clone()),
+                camera,
+                output,
+            );
         }
     }
 }
 
-fn evaluate_exposure_arg(exposure_mode: Option<ExposureArg>, camera: Camera) {
+fn evaluate_exposure_arg(exposure_mode: Option<ExposureArg>, camera: Camera, output: OutputFormat) {
+    // the camera doesn't report its exposure mode, so the previous one is unknown
+    let apply = |mode: ExposureMode| {
+        SettingChange::from_result(
+            "exposure_mode",
+            None,
+            camera.set_exposure_mode(mode).map(|_| mode),
+        )
+    };
+
     match exposure_mode {
-        Some(ExposureArg::Manual) => {
-            println!("{}", t!("cli.exposure.response_to_manual"));
-            camera
-                .set_exposure_mode(tiny4linux::ExposureMode::Manual)
-                .unwrap();
-        }
-        Some(ExposureArg::Global) => {
-            println!("{}", t!("cli.exposure.response_to_global"));
-            camera
-                .set_exposure_mode(tiny4linux::ExposureMode::Global)
-                .unwrap();
-        }
-        Some(ExposureArg::Face) => {
-            println!("{}", t!("cli.exposure.response_to_face"));
-            camera
-                .set_exposure_mode(tiny4linux::ExposureMode::Face)
-                .unwrap();
-        }
+        Some(ExposureArg::Manual) => report_change(
+            output,
+            &apply(ExposureMode::Manual),
+            t!("cli.exposure.response_to_manual"),
+        ),
+        Some(ExposureArg::Global) => report_change(
+            output,
+            &apply(ExposureMode::Global),
+            t!("cli.exposure.response_to_global"),
+        ),
+        Some(ExposureArg::Face) => report_change(
+            output,
+            &apply(ExposureMode::Face),
+            t!("cli.exposure.response_to_face"),
+        ),
         None => {
             let option_manual = t!("cli.exposure.option_manual");
             let option_global = t!("cli.exposure.option_global");
@@ -493,9 +1423,143 @@ fn evaluate_exposure_arg(exposure_mode: Option<ExposureArg>, camera: Camera) {
                         .collect::<Vec<&str>>(),
                 )
                 .interact()
-                .unwrap();
+                .or_exit();
 
-            evaluate_exposure_arg(Option::from(options[selection].result.clone()), camera);
+            evaluate_exposure_arg(
+                Option::from(options[selection].result.clone()),
+                camera,
+                output,
+            );
         }
     }
 }
+
+fn evaluate_move_arg(movement: Option<MoveArg>, camera: Camera, output: OutputFormat) {
+    let movement = match movement {
+        Some(MoveArg::To { pan, tilt }) => PanTilt::Absolute { pan, tilt },
+        Some(MoveArg::Center) => PanTilt::Absolute {
+            pan: 0.0,
+            tilt: 0.0,
+        },
+        Some(MoveArg::Left { steps }) => PanTilt::Relative {
+            pan: -steps,
+            tilt: 0,
+        },
+        Some(MoveArg::Right { steps }) => PanTilt::Relative {
+            pan: steps,
+            tilt: 0,
+        },
+        Some(MoveArg::Up { steps }) => PanTilt::Relative {
+            pan: 0,
+            tilt: steps,
+        },
+        Some(MoveArg::Down { steps }) => PanTilt::Relative {
+            pan: 0,
+            tilt: -steps,
+        },
+        None => {
+            let option_left = t!("cli.move.option_left");
+            let option_right = t!("cli.move.option_right");
+            let option_up = t!("cli.move.option_up");
+            let option_down = t!("cli.move.option_down");
+            let option_center = t!("cli.move.option_center");
+
+            let options = [
+                SelectionOption {
+                    result: MoveArg::Left { steps: 1 },
+                    option: &option_left,
+                },
+                SelectionOption {
+                    result: MoveArg::Right { steps: 1 },
+                    option: &option_right,
+                },
+                SelectionOption {
+                    result: MoveArg::Up { steps: 1 },
+                    option: &option_up,
+                },
+                SelectionOption {
+                    result: MoveArg::Down { steps: 1 },
+                    option: &option_down,
+                },
+                SelectionOption {
+                    result: MoveArg::Center,
+                    option: &option_center,
+                },
+            ];
+            let selection = FuzzySelect::new()
+                .with_prompt(t!("cli.move.request_direction"))
+                .default(0)
+                .items(
+                    options
+                        .iter()
+                        .map(|option| option.option)
+                        .collect::<Vec<&str>>(),
+                )
+                .interact()
+                .or_exit();
+
+            return evaluate_move_arg(
+                Option::from(options[selection].result.clone()),
+                camera,
+                output,
+            );
+        }
+    };
+
+    stop_tracking(&camera, output, t!("cli.move.stopping_tracking"));
+
+    let old = camera.get_pan_tilt().ok();
+    let change = SettingChange::from_result(
+        "pan_tilt",
+        old,
+        camera
+            .set_pan_tilt(movement)
+            .and_then(|_| camera.get_pan_tilt()),
+    );
+    let (pan, tilt) = change.new.unwrap_or_default();
+    report_change(
+        output,
+        &change,
+        t!("cli.move.response_to_move", pan = pan, tilt = tilt),
+    );
+}
+
+fn evaluate_zoom_arg(zoom: Option<i32>, camera: Camera, output: OutputFormat) {
+    if zoom.is_none() {
+        let range = camera.get_zoom_range().or_exit();
+        let selection = Input::<i32>::new()
+            .with_prompt(t!(
+                "cli.zoom.request_zoom",
+                min = range.minimum,
+                max = range.maximum
+            ))
+            .default(camera.get_zoom().or_exit())
+            .validate_with(|value: &i32| {
+                if range.contains(*value) {
+                    Ok(())
+                } else {
+                    Err(t!(
+                        "cli.zoom.request_zoom",
+                        min = range.minimum,
+                        max = range.maximum
+                    ))
+                }
+            })
+            .interact_text()
+            .or_exit();
+
+        return evaluate_zoom_arg(Option::from(selection), camera, output);
+    }
+
+    let change = SettingChange::apply(
+        "zoom",
+        zoom.unwrap(),
+        || camera.get_zoom(),
+        |zoom| camera.set_zoom(zoom),
+    );
+    report_change(
+        output,
+        &change,
+        t!("cli.zoom.response_to_zoom", zoom = zoom.unwrap()),
+    );
+}
@@ -0,0 +1,95 @@
+// SPDX-License-Identifier: EUPL-1.2
+
+use crate::{OrExit, OutputFormat, RawArg, print_structured};
+use rust_i18n::t;
+use serde::Serialize;
+use tiny4linux::{
+    Camera, UVC_GET_CUR, UVC_GET_DEF, UVC_GET_INFO, UVC_GET_LEN, UVC_GET_MAX, UVC_GET_MIN,
+    UVC_GET_RES, UVC_SET_CUR,
+};
+
+/// Data written in hex, e.g. `0x16020300` or `16 02 03 00`.
+#[derive(Clone)]
+pub struct HexData(pub Vec<u8>);
+
+/// Parses data written in hex, with or without `0x` and spaces between the bytes,
+/// e.g. `0x16 0x02 0x03 0x00`.
+pub fn parse_hex_data(text: &str) -> Result<HexData, String> {
+    let digits: String = text
+        .split_whitespace()
+        .map(|token| token.strip_prefix("0x").unwrap_or(token))
+        .collect();
+
+    hex::decode(digits)
+        .map(HexData)
+        .map_err(|_| t!("cli.raw.invalid_data", value = text).to_string())
+}
+
+/// A raw request and its answer, for the structured output of `raw`.
+#[derive(Serialize)]
+struct RawAnswer {
+    request: &'static str,
+    unit: u8,
+    selector: u8,
+    #[serde(with = "hex::serde")]
+    data: Vec<u8>,
+}
+
+/// Sends the raw request to the camera and prints the data of its answer,
+/// or the data sent for `raw set`, as a hexdump, in hex or structured.
+pub fn evaluate_raw_arg(action: RawArg, camera: Camera, output: OutputFormat) {
+    let (request, query, target, data) = match action {
+        RawArg::Get(target) => ("GET_CUR", UVC_GET_CUR, target, Vec::new()),
+        RawArg::Set { target, data } => ("SET_CUR", UVC_SET_CUR, target, data.0),
+        RawArg::Len(target) => ("GET_LEN", UVC_GET_LEN, target, Vec::new()),
+        RawArg::Info(target) => ("GET_INFO", UVC_GET_INFO, target, Vec::new()),
+        RawArg::Min(target) => ("GET_MIN", UVC_GET_MIN, target, Vec::new()),
+        RawArg::Max(target) => ("GET_MAX", UVC_GET_MAX, target, Vec::new()),
+        RawArg::Def(target) => ("GET_DEF", UVC_GET_DEF, target, Vec::new()),
+        RawArg::Res(target) => ("GET_RES", UVC_GET_RES, target, Vec::new()),
+    };
+
+    let data = camera
+        .request(target.unit, target.selector, query, &data)
+        .or_exit();
+
+    match output {
+        OutputFormat::Plain if target.hex => println!("{}", hex::encode(&data)),
+        OutputFormat::Plain => hexdump::hexdump(&data),
+        OutputFormat::Json | OutputFormat::Yaml => print_structured(
+            output,
+            &RawAnswer {
+                request,
+                unit: target.unit,
+                selector: target.selector,
+                data,
+            },
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod unit {
+        use crate::raw::parse_hex_data;
+        use test_case::test_case;
+
+        #[test_case("0x16020300"; "one prefix")]
+        #[test_case("16 02 03 00"; "bytes")]
+        #[test_case("0x16 0x02 0x03 0x00"; "prefix per byte")]
+        #[test_case("0x1602 0300"; "prefix per group")]
+        fn parses_data(text: &str) {
+            assert_eq!(
+                parse_hex_data(text).unwrap().0,
+                vec![0x16, 0x02, 0x03, 0x00]
+            );
+        }
+
+        #[test_case("0x0x16"; "prefix twice")]
+        #[test_case("0x1"; "odd digits")]
+        #[test_case("zoom"; "not hex")]
+        fn rejects_data(text: &str) {
+            assert!(parse_hex_data(text).is_err());
+        }
+    }
+}
@@ -0,0 +1,268 @@
+// SPDX-License-Identifier: EUPL-1.2
+
+use crate::{OrExit, status_lines};
+use dialoguer::console::{Key, Term};
+use nix::sys::termios::{self, LocalFlags, SetArg, Termios};
+use rust_i18n::t;
+use std::io;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+use tiny4linux::{
+    AIMode, Camera, CameraStatus, ExposureMode, SleepMode, T4lError, Tiny2Camera, TrackingSpeed,
+};
+
+/// How often the status of the camera is read to show changes made elsewhere.
+const STATUS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Switches the terminal to its alternate screen without echoing the keys, until dropped.
+struct Screen {
+    term: Term,
+    original: Termios,
+}
+
+impl Screen {
+    fn enter(term: Term) -> io::Result<Self> {
+        let original = termios::tcgetattr(io::stdin())?;
+        let mut raw = original.clone();
+        // Ctrl+C is read as a key, so that the terminal is restored before quitting
+        raw.local_flags
+            .remove(LocalFlags::ECHO | LocalFlags::ICANON | LocalFlags::ISIG);
+        termios::tcsetattr(io::stdin(), SetArg::TCSANOW, &raw)?;
+
+        term.write_str("\x1b[?1049h")?;
+        term.hide_cursor()?;
+        Ok(Self { term, original })
+    }
+
+    fn draw(&self, lines: &[String]) -> io::Result<()> {
+        self.term.move_cursor_to(0, 0)?;
+        self.term.clear_to_end_of_screen()?;
+        for line in lines {
+            self.term.write_line(line)?;
+        }
+        self.term.flush()
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        let _ = self.term.show_cursor();
+        let _ = self.term.write_str("\x1b[?1049l");
+        let _ = self.term.flush();
+        let _ = termios::tcsetattr(io::stdin(), SetArg::TCSANOW, &self.original);
+    }
+}
+
+/// Shows the status of the camera on the full terminal, changing the settings by keystrokes,
+/// until `q` is pressed.
+pub fn run_tui(camera: Camera) {
+    let term = Term::buffered_stdout();
+    if !term.is_term() || !Term::stdout().is_term() {
+        Err(T4lError::from(io::Error::new(
+            io::ErrorKind::Unsupported,
+            t!("cli.tui.no_terminal").to_string(),
+        )))
+        .or_exit()
+    }
+
+    let screen = Screen::enter(term).map_err(T4lError::from).or_exit();
+    let result = show_dashboard(&screen, &camera);
+    // the terminal has to be restored before an error is printed
+    drop(screen);
+    result.map_err(T4lError::from).or_exit();
+}
+
+fn show_dashboard(screen: &Screen, camera: &Camera) -> io::Result<()> {
+    let (sender, keys) = mpsc::channel();
+    thread::spawn(move || {
+        let term = Term::stdout();
+        while let Ok(key) = term.read_key() {
+            if sender.send(key).is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut message = String::new();
+    // the camera doesn't report its exposure mode, so `e` cycles on from the last one set here
+    let mut exposure_mode = None;
+    loop {
+        let status = camera.get_status();
+        let mut lines = vec![
+            format!("Tiny4Linux {}", env!("CARGO_PKG_VERSION")),
+            String::new(),
+        ];
+        match &status {
+            Ok(status) => {
+                lines.push(format!("{}:", t!("shared.info.camera_status")));
+                lines.extend(status_lines(status));
+            }
+            Err(error) => lines.push(error.to_string()),
+        }
+        lines.push(String::new());
+        lines.extend(key_lines());
+        lines.push(String::new());
+        lines.push(message.clone());
+        screen.draw(&lines)?;
+
+        let key = match keys.recv_timeout(STATUS_INTERVAL) {
+            Ok(key) => key,
+            Err(RecvTimeoutError::Timeout) => continue,
+            Err(RecvTimeoutError::Disconnected) => return Ok(()),
+        };
+        if matches!(key, Key::Char('q') | Key::Escape | Key::CtrlC) {
+            return Ok(());
+        }
+
+        if let Ok(status) = status {
+            message = match apply_key(camera, &key, &status, &mut exposure_mode) {
+                Ok(()) => String::new(),
+                Err(error) => error.to_string(),
+            };
+        }
+    }
+}
+
+/// Changes the setting the key stands for, if any.
+fn apply_key(
+    camera: &Camera,
+    key: &Key,
+    status: &CameraStatus,
+    exposure_mode: &mut Option<ExposureMode>,
+) -> Result<(), T4lError> {
+    match key {
+        Key::Char('s') => camera.set_sleep_mode(if status.awake == SleepMode::Sleep {
+            SleepMode::Awake
+        } else {
+            SleepMode::Sleep
+        }),
+        Key::Char('t') | Key::ArrowRight => camera.set_ai_mode(status.ai_mode.next()),
+        Key::Char('T') | Key::ArrowLeft => camera.set_ai_mode(status.ai_mode.previous()),
+        Key::Char('v') => camera.set_tracking_speed(match status.speed {
+            TrackingSpeed::Standard => TrackingSpeed::Sport,
+            TrackingSpeed::Sport => TrackingSpeed::Standard,
+        }),
+        Key::Char('h') => camera.set_hdr_mode(!status.hdr_on),
+        Key::Char('e') => {
+            let mode = match exposure_mode {
+                None | Some(ExposureMode::Face) => ExposureMode::Manual,
+                Some(ExposureMode::Manual) => ExposureMode::Global,
+                Some(ExposureMode::Global) => ExposureMode::Face,
+            };
+            camera.set_exposure_mode(mode)?;
+            *exposure_mode = Some(mode);
+            Ok(())
+        }
+        // the three preset positions of the GUI, which stops the tracking to move there as well
+        Key::Char(preset @ '1'..='3') => camera
+            .set_ai_mode(AIMode::NoTracking)
+            .and_then(|_| camera.goto_preset_position((*preset as u8 - b'1') as i8)),
+        _ => Ok(()),
+    }
+}
+
+fn key_lines() -> Vec<String> {
+    [
+        ("s", t!("cli.tui.keys.sleep")),
+        ("t / T", t!("cli.tui.keys.tracking")),
+        ("v", t!("cli.tui.keys.speed")),
+        ("h", t!("cli.tui.keys.hdr")),
+        ("e", t!("cli.tui.keys.exposure")),
+        ("1-3", t!("cli.tui.keys.preset")),
+        ("q", t!("cli.tui.keys.quit")),
+    ]
+    .iter()
+    .map(|(key, description)| format!("  {:<6} {}", key, description))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    mod unit {
+        use crate::tui::apply_key;
+        use dialoguer::console::Key;
+        use test_case::test_case;
+        use tiny4linux::{AIMode, Camera, ExposureMode, SleepMode, Tiny2Camera, TrackingSpeed};
+
+        /// Presses the key with the current status of the camera, as the dashboard does.
+        fn press(camera: &Camera, key: Key, exposure_mode: &mut Option<ExposureMode>) {
+            let status = camera.get_status().unwrap();
+            apply_key(camera, &key, &status, exposure_mode).unwrap();
+        }
+
+        #[test_case(Key::Char('t'), AIMode::NormalTracking, AIMode::CloseUp; "next")]
+        #[test_case(Key::ArrowRight, AIMode::Group, AIMode::NoTracking; "next starts over")]
+        #[test_case(Key::Char('T'), AIMode::NormalTracking, AIMode::NoTracking; "previous")]
+        #[test_case(Key::ArrowLeft, AIMode::NoTracking, AIMode::Group; "previous continues at end")]
+        fn cycles_tracking_mode(key: Key, current: AIMode, expected: AIMode) {
+            let camera = Camera::simulated();
+            camera.set_ai_mode(current).unwrap();
+
+            press(&camera, key, &mut None);
+
+            assert_eq!(camera.get_status().unwrap().ai_mode, expected);
+        }
+
+        #[test]
+        fn toggles_sleep_mode() {
+            let camera = Camera::simulated();
+            camera.set_sleep_mode(SleepMode::Awake).unwrap();
+
+            press(&camera, Key::Char('s'), &mut None);
+            assert_eq!(camera.get_status().unwrap().awake, SleepMode::Sleep);
+
+            press(&camera, Key::Char('s'), &mut None);
+            assert_eq!(camera.get_status().unwrap().awake, SleepMode::Awake);
+        }
+
+        #[test]
+        fn toggles_tracking_speed_and_hdr() {
+            let camera = Camera::simulated();
+            camera.set_tracking_speed(TrackingSpeed::Standard).unwrap();
+            camera.set_hdr_mode(false).unwrap();
+
+            press(&camera, Key::Char('v'), &mut None);
+            press(&camera, Key::Char('h'), &mut None);
+
+            let status = camera.get_status().unwrap();
+            assert_eq!(status.speed, TrackingSpeed::Sport);
+            assert!(status.hdr_on);
+        }
+
+        #[test_case(None, ExposureMode::Manual; "first")]
+        #[test_case(Some(ExposureMode::Manual), ExposureMode::Global; "manual")]
+        #[test_case(Some(ExposureMode::Global), ExposureMode::Face; "global")]
+        #[test_case(Some(ExposureMode::Face), ExposureMode::Manual; "starts over")]
+        fn cycles_exposure_mode(current: Option<ExposureMode>, expected: ExposureMode) {
+            let camera = Camera::simulated();
+            let mut exposure_mode = current;
+
+            press(&camera, Key::Char('e'), &mut exposure_mode);
+
+            assert_eq!(exposure_mode, Some(expected));
+        }
+
+        #[test]
+        fn preset_stops_tracking() {
+            let camera = Camera::simulated();
+            camera.set_ai_mode(AIMode::Group).unwrap();
+
+            press(&camera, Key::Char('2'), &mut None);
+
+            assert_eq!(camera.get_status().unwrap().ai_mode, AIMode::NoTracking);
+        }
+
+        #[test]
+        fn ignores_other_keys() {
+            let camera = Camera::simulated();
+            let before = camera.get_status().unwrap();
+            let mut exposure_mode = None;
+
+            press(&camera, Key::Char('x'), &mut exposure_mode);
+
+            assert_eq!(camera.get_status().unwrap(), before);
+            assert_eq!(exposure_mode, None);
+        }
+    }
+}
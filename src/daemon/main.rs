@@ -0,0 +1,124 @@
+// SPDX-License-Identifier: EUPL-1.2
+
+use clap::Parser;
+use rust_i18n::{i18n, set_locale, t};
+use std::thread;
+use std::time::Duration;
+use tiny4linux::{
+    AutoSleep, CAMERA_HINT, Camera, CameraInterface, DAEMON_BUS_NAME, DAEMON_OBJECT_PATH,
+    Scheduler, T4lError, find_camera, get_language, list_cameras,
+};
+use zbus::blocking::connection;
+
+i18n!("src/locales", fallback = "en");
+
+#[derive(Parser)]
+#[command(name = "tiny4linux-daemon", version, about = t!("daemon.help.about"), long_about = None)]
+struct Args {
+    #[arg(short, long, help = t!("cli.help.args.device"))]
+    device: Option<String>,
+    #[arg(long, default_value_t = 1000, help = t!("daemon.help.poll_interval"))]
+    poll_interval: u64,
+    #[arg(long, value_name = "SECONDS", help = t!("daemon.help.auto_sleep"))]
+    auto_sleep: Option<u64>,
+    #[arg(long, help = t!("daemon.help.schedule"))]
+    schedule: bool,
+    #[arg(long, help = t!("cli.help.args.lang"))]
+    lang: Option<String>,
+}
+
+fn main() {
+    let language = get_language(false);
+    set_locale(language.as_str());
+
+    let args = Args::parse();
+
+    let device = match &args.device {
+        Some(selector) => find_camera(CAMERA_HINT, selector),
+        None => list_cameras(CAMERA_HINT)
+            .into_iter()
+            .next()
+            .ok_or(T4lError::NoCameraFound),
+    }
+    .unwrap_or_else(|error| exit_with_error(error));
+
+    let camera = Camera::new_local(&device.path).unwrap_or_else(|error| exit_with_error(error));
+    let mut auto_sleep = args
+        .auto_sleep
+        .map(|idle_timeout| AutoSleep::new(&device.path, Duration::from_secs(idle_timeout)));
+    let mut scheduler = args.schedule.then(Scheduler::new);
+
+    println!(
+        "{}",
+        t!("daemon.serving", card = device.card, path = device.path)
+    );
+
+    let connection = connection::Builder::session()
+        .and_then(|builder| builder.name(DAEMON_BUS_NAME))
+        .and_then(|builder| {
+            builder.serve_at(DAEMON_OBJECT_PATH, CameraInterface::new(camera, device))
+        })
+        .and_then(|builder| builder.build())
+        .unwrap_or_else(|error| {
+            eprintln!("{}", t!("daemon.errors.bus", error = error));
+            std::process::exit(1);
+        });
+
+    let interface = connection
+        .object_server()
+        .interface::<_, CameraInterface>(DAEMON_OBJECT_PATH)
+        .unwrap();
+
+    // changes made through raw requests or on the camera itself are only noticed by polling
+    loop {
+        thread::sleep(Duration::from_millis(args.poll_interval));
+
+        match zbus::block_on(interface.get().reconnect(interface.signal_context())) {
+            Ok(Some(device)) => println!(
+                "{}",
+                t!("daemon.reconnected", card = device.card, path = device.path)
+            ),
+            Ok(None) => {}
+            Err(error) => eprintln!("{}", t!("daemon.errors.bus", error = error)),
+        }
+
+        let announced =
+            zbus::block_on(interface.get().announce_changes(interface.signal_context()));
+        if let Err(error) = announced {
+            eprintln!("{}", t!("daemon.errors.bus", error = error));
+        }
+
+        // the camera served on the bus is shared, so its changes are announced with the next poll
+        if let Some(auto_sleep) = auto_sleep.as_mut()
+            && let Err(error) = auto_sleep.poll(&*interface.get().camera())
+        {
+            eprintln!("{}", error);
+        }
+
+        if let Some(scheduler) = scheduler.as_mut() {
+            match scheduler.poll(&*interface.get().camera()) {
+                Ok(ran) => ran.into_iter().for_each(|(rule, result)| match result {
+                    Ok(()) => println!(
+                        "{}",
+                        t!("cli.schedule.ran", when = rule.when, action = rule.action)
+                    ),
+                    Err(error) => eprintln!(
+                        "{}",
+                        t!(
+                            "cli.schedule.failed",
+                            when = rule.when,
+                            action = rule.action,
+                            error = error
+                        )
+                    ),
+                }),
+                Err(error) => eprintln!("{}", error),
+            }
+        }
+    }
+}
+
+fn exit_with_error(error: T4lError) -> ! {
+    eprintln!("{}", error);
+    std::process::exit(error.exit_code());
+}
@@ -6,91 +6,278 @@ mod ui_modules;
 use crate::styles::theme::obsbot_theme;
 use crate::ui_modules::window_layout::window_layout;
 use iced::alignment::{Horizontal, Vertical};
+use iced::futures::channel::mpsc;
+use iced::futures::{SinkExt, Stream, StreamExt};
+use iced::widget::image::Handle;
 use iced::widget::{Container, text};
 use iced::window::Position;
 use iced::{Element, Point};
-use iced::{Length, Size, Subscription, Task, time, window};
+use iced::{Length, Size, Subscription, Task, stream, time, window};
 use rust_i18n::{i18n, set_locale, t};
+use std::fmt::Debug;
+use std::sync::{Arc, OnceLock};
+use std::thread;
 use std::time::Duration;
 use tiny4linux::{
-    AIMode, Camera, ExposureMode, SleepMode, Tiny2Camera, TrackingSpeed, get_language,
+    AIMode, CAMERA_HINT, Camera, CameraDevice, ControlRange, ExposureMode, GlobalShortcut,
+    GlobalShortcuts, HotkeyAction, HotplugEvent, HotplugWatcher, OffsetLabels, PanTilt, Profile,
+    Profiles, RawSample, RawWatcher, Settings, SleepMode, StatusChange, StatusWatcher, T4lError,
+    Tiny2Camera, TrackingSpeed, Tray, TrayEvent, TrayIcon, TrayMenuItem, get_language,
+    list_cameras, parse_hex, persisted_settings_path, record_persisted_settings,
+    restore_persisted_settings,
 };
+use tiny4linux_assets::handle_t4l_asset;
 
 i18n!("src/locales", fallback = "en");
 
+/// How often the status of the camera is read to take over changes made elsewhere.
+const STATUS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How often the raw data of the camera is read while it's watched in the debug area.
+const RAW_WATCH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The tracking speeds in the order of the dashboard.
+const TRACKING_SPEEDS: [TrackingSpeed; 2] = [TrackingSpeed::Standard, TrackingSpeed::Sport];
+
+/// Size in pixels the icons of the tray are scaled down to.
+const TRAY_ICON_SIZE: u32 = 64;
+
+/// Whether a camera is connected, its sleep mode, tracking mode and speed, as shown in the tray.
+type TrayShown = (bool, SleepMode, AIMode, TrackingSpeed);
+
+/// The tray icon, shared between the state and the messages that carry it.
+#[derive(Clone)]
+struct TrayHandle(Arc<Tray>);
+
+impl Debug for TrayHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("TrayHandle")
+    }
+}
+
+impl PartialEq for TrayHandle {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 enum Message {
     RequestWindowModeChange(WindowMode),
     ApplyWindowMode(WindowMode),
     ChangeMainWindowId(Option<window::Id>),
+    SelectCamera(CameraDevice),
+    ApplyProfile(String),
+    ChangePersistSettings(bool),
     ChangeSleeping(bool),
     ChangeTracking(AIMode),
     ChangeTrackingSpeed(TrackingSpeed),
     ChangePresetPosition(i8),
+    #[cfg(feature = "experimental")]
+    ChangeStoringPreset(bool),
+    #[cfg(feature = "experimental")]
+    SavePresetPosition(i8),
+    ChangePanTilt(PanTilt),
     ChangeHDR(bool),
     ChangeExposure(ExposureMode),
+    ChangeZoom(i32),
     ChangeDebugging(bool),
     TextInput(String),
     TextInput02(String),
+    Hotplug(HotplugEvent),
+    PollStatus,
+    Hotkey(HotkeyAction),
+    TrayReady(TrayHandle),
+    Tray(TrayEvent),
+    CloseRequested(window::Id),
     CheckCamera,
     SendCommand,
     SendCommand02,
     HexDump,
     HexDump02,
+    ChangeWatchingRaw(bool),
+    PollRaw,
+    SelectLabelSelector(u8),
+    LabelOffsetInput(String),
+    LabelTextInput(String),
+    SaveLabel,
 }
 
 struct MainPanel {
     camera: Option<Camera>,
+    devices: Vec<CameraDevice>,
+    selected_device: Option<CameraDevice>,
+    profiles: Profiles,
+    persist_settings: bool,
     main_window_id: Option<window::Id>,
     window_mode: WindowMode,
+    tray: Option<TrayHandle>,
+    tray_shown: Option<TrayShown>,
     awake: SleepMode,
     tracking: AIMode,
     tracking_speed: TrackingSpeed,
     hdr_on: bool,
+    exposure_mode: Option<ExposureMode>,
+    status_watcher: StatusWatcher,
+    #[cfg(feature = "experimental")]
+    storing_preset: bool,
+    zoom: i32,
+    zoom_range: Option<ControlRange>,
     debugging_on: bool,
+    error: Option<String>,
     text_input: String,
     text_input_02: String,
+    watching_raw: bool,
+    raw_watcher: RawWatcher,
+    raw_samples: Vec<RawSample>,
+    offset_labels: OffsetLabels,
+    label_selector: u8,
+    label_offset: String,
+    label_text: String,
 }
 
 impl MainPanel {
     fn init_state(window_mode: WindowMode) -> (Self, Task<Message>) {
-        let camera = Camera::new("OBSBOT Tiny 2").ok();
+        let mut panel = MainPanel {
+            camera: None,
+            devices: Vec::new(),
+            selected_device: None,
+            profiles: Profiles::load().unwrap_or_default(),
+            persist_settings: Settings::load().unwrap_or_default().persist_settings,
+            main_window_id: None,
+            window_mode,
+            tray: None,
+            tray_shown: None,
+            awake: SleepMode::Unknown,
+            tracking: AIMode::Unknown,
+            tracking_speed: TrackingSpeed::Standard,
+            hdr_on: false,
+            exposure_mode: None,
+            status_watcher: StatusWatcher::new(STATUS_INTERVAL),
+            #[cfg(feature = "experimental")]
+            storing_preset: false,
+            zoom: 0,
+            zoom_range: None,
+            debugging_on: false,
+            error: None,
+            text_input: String::new(),
+            text_input_02: String::new(),
+            watching_raw: false,
+            raw_watcher: RawWatcher::new(RAW_WATCH_INTERVAL),
+            raw_samples: Vec::new(),
+            offset_labels: OffsetLabels::load().unwrap_or_default(),
+            label_selector: 0x06,
+            label_offset: String::new(),
+            label_text: String::new(),
+        };
+        panel.connect_camera();
+        let open_task = panel.open_window();
 
-        let status = camera
+        (panel, open_task)
+    }
+
+    /// Opens the selected camera, or the first one found if none is selected or it's gone.
+    fn connect_camera(&mut self) {
+        self.devices = list_cameras(CAMERA_HINT);
+
+        if !self
+            .selected_device
             .as_ref()
+            .is_some_and(|device| self.devices.contains(device))
+        {
+            self.selected_device = self.devices.first().cloned();
+        }
+
+        self.camera = self
+            .selected_device
+            .as_ref()
+            .and_then(|device| Camera::open(device).ok());
+        self.status_watcher = StatusWatcher::new(STATUS_INTERVAL);
+        self.raw_watcher = RawWatcher::new(RAW_WATCH_INTERVAL);
+        if let Some(camera) = self.camera.as_mut() {
+            camera.set_debugging(self.debugging_on);
+        }
+
+        // The camera doesn't report its exposure mode, so it's only known once it's set
+        self.exposure_mode = None;
+        self.read_camera_state();
+    }
+
+    /// Takes over the current settings of the camera, as they differ between cameras.
+    fn read_camera_state(&mut self) {
+        let camera = self.camera.as_ref();
+
+        let status = camera
             .and_then(|c| c.get_status().ok())
             .unwrap_or_else(|| tiny4linux::CameraStatus::default());
 
-        (
-            MainPanel {
-                camera,
-                main_window_id: None,
-                window_mode,
-                awake: status.awake,
-                tracking: status.ai_mode,
-                tracking_speed: status.speed,
-                hdr_on: status.hdr_on,
-                debugging_on: false,
-                text_input: String::new(),
-                text_input_02: String::new(),
-            },
-            window::get_latest().map(Message::ChangeMainWindowId),
-        )
+        self.awake = status.awake;
+        self.tracking = status.ai_mode;
+        self.tracking_speed = status.speed;
+        self.hdr_on = status.hdr_on;
+        self.zoom = camera.and_then(|c| c.get_zoom().ok()).unwrap_or_default();
+        self.zoom_range = camera.and_then(|c| c.get_zoom_range().ok());
     }
 
     fn update(&mut self, message: Message) -> Task<Message> {
-        if self.camera.is_none() {
-            self.camera = Camera::new("OBSBOT Tiny 2").ok();
+        let task = self.handle_message(message);
+        self.update_tray();
+        task
+    }
 
-            if self.camera.is_none() {
-                return Task::none();
-            }
-        } else if !self.camera.as_ref().unwrap().get_status().is_ok() {
-            self.camera = None;
+    fn handle_message(&mut self, message: Message) -> Task<Message> {
+        if let Message::SelectCamera(device) = message {
+            self.selected_device = Some(device);
+            self.connect_camera();
+            return Task::none();
+        }
+
+        if let Message::Hotplug(event) = message {
+            self.handle_hotplug(event);
+            return Task::none();
+        }
+
+        if let Message::CheckCamera = message {
+            self.check_camera();
+            return Task::none();
+        }
+
+        if let Message::PollStatus = message {
+            self.poll_status();
+            return Task::none();
+        }
+
+        if let Message::PollRaw = message {
+            self.poll_raw();
+            return Task::none();
+        }
+
+        if let Message::Hotkey(action) = message {
+            return self.handle_message(self.hotkey_message(action));
+        }
+
+        if let Message::ChangeMainWindowId(id) = message {
+            self.main_window_id = id;
+            return Task::none();
+        }
+
+        if let Message::CloseRequested(id) = message {
+            return self.close_window(id);
+        }
+
+        if let Message::TrayReady(tray) = message {
+            self.tray = Some(tray);
+            self.tray_shown = None;
             return Task::none();
         }
 
-        let camera = self.camera.as_ref().unwrap();
+        if let Message::Tray(event) = message {
+            return self.handle_tray_event(event);
+        }
+
+        let Some(camera) = self.camera.as_ref() else {
+            return Task::none();
+        };
 
         match message {
             Message::RequestWindowModeChange(new_mode) => {
@@ -111,45 +298,121 @@ impl MainPanel {
                 self.window_mode = new_mode;
                 Task::none()
             }
-            Message::ChangeMainWindowId(id) => {
-                self.main_window_id = id;
+            Message::SelectCamera(_) => Task::none(),
+            Message::ChangePersistSettings(persist) => {
+                // An unreadable config is kept, so that its other settings aren't lost
+                let saved = Settings::load().and_then(|mut settings| {
+                    settings.persist_settings = persist;
+                    settings.save()
+                });
+                if saved.is_ok() {
+                    self.persist_settings = persist;
+                }
+                self.error = error_text(saved);
+                Task::none()
+            }
+            Message::ApplyProfile(name) => {
+                let applied = Profiles::load().and_then(|profiles| {
+                    self.profiles = profiles;
+                    let profile = self.profiles.get(&name)?.clone();
+                    profile.apply(camera).map(|()| profile)
+                });
+                if let Ok(profile) = &applied {
+                    self.exposure_mode = profile.exposure_mode.or(self.exposure_mode);
+                    self.persist(|settings| {
+                        settings.ai_mode = profile.ai_mode.or(settings.ai_mode);
+                        settings.tracking_speed =
+                            profile.tracking_speed.or(settings.tracking_speed);
+                        settings.hdr = profile.hdr.or(settings.hdr);
+                        settings.exposure_mode = profile.exposure_mode.or(settings.exposure_mode);
+                    })
+                }
+                self.error = error_text(applied);
+                self.read_camera_state();
                 Task::none()
             }
             Message::ChangeSleeping(should_sleep) => {
-                if should_sleep {
+                let result = if should_sleep {
                     self.awake = SleepMode::Sleep;
-                    camera.set_sleep_mode(SleepMode::Sleep).unwrap();
+                    camera.set_sleep_mode(SleepMode::Sleep)
                 } else {
                     self.awake = SleepMode::Awake;
-                    camera.set_sleep_mode(SleepMode::Awake).unwrap();
-                }
+                    camera.set_sleep_mode(SleepMode::Awake)
+                };
+                self.error = error_text(result);
 
                 Task::none()
             }
             Message::ChangeTracking(tracking_type) => {
                 self.tracking = tracking_type;
-                camera.set_ai_mode(tracking_type).unwrap();
+                let result = camera.set_ai_mode(tracking_type);
+                if result.is_ok() {
+                    self.persist(|settings| settings.ai_mode = Some(tracking_type));
+                }
+                self.error = error_text(result);
                 Task::none()
             }
             Message::ChangeTrackingSpeed(new_speed) => {
                 self.tracking_speed = new_speed;
-                camera.set_tracking_speed(new_speed).unwrap();
+                let result = camera.set_tracking_speed(new_speed);
+                if result.is_ok() {
+                    self.persist(|settings| settings.tracking_speed = Some(new_speed));
+                }
+                self.error = error_text(result);
                 Task::none()
             }
             Message::ChangePresetPosition(new_position) => {
                 self.tracking = AIMode::NoTracking;
                 self.awake = SleepMode::Awake;
-                camera.set_ai_mode(AIMode::NoTracking).unwrap();
-                camera.goto_preset_position(new_position).unwrap();
+                self.error = error_text(
+                    camera
+                        .set_ai_mode(AIMode::NoTracking)
+                        .and_then(|_| camera.goto_preset_position(new_position)),
+                );
+                Task::none()
+            }
+            #[cfg(feature = "experimental")]
+            Message::ChangeStoringPreset(storing) => {
+                self.storing_preset = storing;
+                Task::none()
+            }
+            #[cfg(feature = "experimental")]
+            Message::SavePresetPosition(position) => {
+                self.storing_preset = false;
+                self.error = error_text(camera.save_preset_position(position));
+                Task::none()
+            }
+            Message::ChangePanTilt(movement) => {
+                self.tracking = AIMode::NoTracking;
+                self.awake = SleepMode::Awake;
+                self.error = error_text(
+                    camera
+                        .set_ai_mode(AIMode::NoTracking)
+                        .and_then(|_| camera.set_pan_tilt(movement)),
+                );
                 Task::none()
             }
             Message::ChangeHDR(new_mode) => {
                 self.hdr_on = new_mode;
-                camera.set_hdr_mode(new_mode).unwrap();
+                let result = camera.set_hdr_mode(new_mode);
+                if result.is_ok() {
+                    self.persist(|settings| settings.hdr = Some(new_mode));
+                }
+                self.error = error_text(result);
                 Task::none()
             }
             Message::ChangeExposure(mode) => {
-                camera.set_exposure_mode(mode).unwrap();
+                self.exposure_mode = Some(mode);
+                let result = camera.set_exposure_mode(mode);
+                if result.is_ok() {
+                    self.persist(|settings| settings.exposure_mode = Some(mode));
+                }
+                self.error = error_text(result);
+                Task::none()
+            }
+            Message::ChangeZoom(new_zoom) => {
+                self.zoom = new_zoom;
+                self.error = error_text(camera.set_zoom(new_zoom));
                 Task::none()
             }
             Message::ChangeDebugging(new_mode) => {
@@ -184,11 +447,41 @@ impl MainPanel {
                 camera.dump_02().unwrap();
                 Task::none()
             }
-            Message::CheckCamera => Task::none(),
+            Message::ChangeWatchingRaw(watching) => {
+                self.watching_raw = watching;
+                self.raw_watcher = RawWatcher::new(RAW_WATCH_INTERVAL);
+                self.raw_samples.clear();
+                Task::none()
+            }
+            Message::SelectLabelSelector(selector) => {
+                self.label_selector = selector;
+                Task::none()
+            }
+            Message::LabelOffsetInput(offset) => {
+                self.label_offset = offset;
+                Task::none()
+            }
+            Message::LabelTextInput(label) => {
+                self.label_text = label;
+                Task::none()
+            }
+            Message::SaveLabel => {
+                self.save_label();
+                Task::none()
+            }
+            Message::Hotplug(_)
+            | Message::PollStatus
+            | Message::PollRaw
+            | Message::Hotkey(_)
+            | Message::ChangeMainWindowId(_)
+            | Message::CloseRequested(_)
+            | Message::TrayReady(_)
+            | Message::Tray(_)
+            | Message::CheckCamera => Task::none(),
         }
     }
 
-    fn view(&'_ self) -> Element<'_, Message> {
+    fn view(&'_ self, _window: window::Id) -> Element<'_, Message> {
         if self.camera.is_some() {
             get_current_ui_elements(self).into()
         } else {
@@ -202,11 +495,465 @@ impl MainPanel {
         }
     }
 
-    fn subscription(&self) -> Subscription<Message> {
+    /// Drops the camera once its device is removed and connects as soon as one is added.
+    fn handle_hotplug(&mut self, event: HotplugEvent) {
+        let selected_removed = matches!(&event, HotplugEvent::Removed(path)
+            if self.selected_device.as_ref().is_some_and(|device| &device.path == path));
+
+        if selected_removed {
+            self.camera = None;
+        }
+
+        if self.camera.is_none() {
+            self.reconnect_camera();
+        } else {
+            self.devices = list_cameras(CAMERA_HINT);
+        }
+    }
+
+    /// Polls whether the camera is still there, if devices can't be watched for hotplug events.
+    fn check_camera(&mut self) {
         if self.camera.is_none() {
-            time::every(Duration::from_secs(4)).map(|_| Message::CheckCamera)
+            self.reconnect_camera();
+        } else if !self.camera.as_ref().unwrap().get_status().is_ok() {
+            self.camera = None;
+        }
+    }
+
+    /// Connects to the camera again after it was gone, applying the persisted settings if enabled.
+    fn reconnect_camera(&mut self) {
+        self.connect_camera();
+
+        if self.persist_settings
+            && let Some(camera) = self.camera.as_ref()
+        {
+            if let Err(error) = restore_persisted_settings(&persisted_settings_path(), camera) {
+                eprintln!("{}", error);
+            }
+            self.read_camera_state();
+        }
+    }
+
+    /// Records the settings applied by the user, so that they're applied again on a reconnect.
+    fn persist(&self, update: impl FnOnce(&mut Profile)) {
+        if self.persist_settings
+            && let Err(error) = record_persisted_settings(&persisted_settings_path(), update)
+        {
+            eprintln!("{}", error);
+        }
+    }
+
+    /// Translates a global shortcut into the message of the button it stands for.
+    fn hotkey_message(&self, action: HotkeyAction) -> Message {
+        match action {
+            HotkeyAction::ToggleSleep => Message::ChangeSleeping(self.awake != SleepMode::Sleep),
+            HotkeyAction::NextAiMode => Message::ChangeTracking(self.tracking.next()),
+            HotkeyAction::PreviousAiMode => Message::ChangeTracking(self.tracking.previous()),
+            HotkeyAction::Preset1 => Message::ChangePresetPosition(0),
+            HotkeyAction::Preset2 => Message::ChangePresetPosition(1),
+            HotkeyAction::Preset3 => Message::ChangePresetPosition(2),
+            HotkeyAction::ToggleHdr => Message::ChangeHDR(!self.hdr_on),
+        }
+    }
+
+    /// Opens the main window in the current mode.
+    fn open_window(&mut self) -> Task<Message> {
+        let (id, open_task) = window::open(get_window_settings_for_window_mode(self.window_mode));
+        self.main_window_id = Some(id);
+        open_task.discard()
+    }
+
+    /// Closes the window, keeping the application running in the tray if there is one.
+    fn close_window(&mut self, id: window::Id) -> Task<Message> {
+        if self.tray.is_none() {
+            return iced::exit();
+        }
+
+        if self.main_window_id == Some(id) {
+            self.main_window_id = None;
+        }
+        window::close(id)
+    }
+
+    fn handle_tray_event(&mut self, event: TrayEvent) -> Task<Message> {
+        match event {
+            TrayEvent::Activated => match self.main_window_id {
+                Some(id) => self.close_window(id),
+                None => self.open_window(),
+            },
+            TrayEvent::MenuItem(id) => match id.as_str() {
+                "show_window" => match self.main_window_id {
+                    Some(id) => window::gain_focus(id),
+                    None => self.open_window(),
+                },
+                "quit" => iced::exit(),
+                _ => match tray_message(&id).or_else(|| {
+                    HotkeyAction::from_id(&id).map(|action| self.hotkey_message(action))
+                }) {
+                    Some(message) => self.handle_message(message),
+                    None => Task::none(),
+                },
+            },
+        }
+    }
+
+    /// Shows the current state in the tray, unless the state it shows didn't change.
+    fn update_tray(&mut self) {
+        let Some(TrayHandle(tray)) = &self.tray else {
+            return;
+        };
+        let shown = (
+            self.camera.is_some(),
+            self.awake,
+            self.tracking,
+            self.tracking_speed,
+        );
+        if self.tray_shown == Some(shown) {
+            return;
+        }
+        self.tray_shown = Some(shown);
+
+        let result = tray
+            .set_icon(tray_icon(self.awake))
+            .and_then(|_| tray.set_menu(self.tray_menu()));
+        if let Err(error) = result {
+            eprintln!("{}", t!("gui.errors.tray", error = error));
+        }
+    }
+
+    /// The quick actions of the tray, which only offers to show the window without a camera.
+    fn tray_menu(&self) -> Vec<TrayMenuItem> {
+        let action = |id: String, label: String, checked: Option<bool>| TrayMenuItem::Action {
+            id,
+            label,
+            checked,
+        };
+        let mut menu = Vec::new();
+
+        if self.camera.is_some() {
+            let sleep_label = match self.awake {
+                SleepMode::Sleep => t!("gui.buttons.sleep.wake_up"),
+                _ => t!("gui.buttons.sleep.set_sleep"),
+            };
+            menu.push(action(
+                HotkeyAction::ToggleSleep.id().to_string(),
+                sleep_label.to_string(),
+                None,
+            ));
+            menu.push(TrayMenuItem::Submenu {
+                label: t!("gui.tray.tracking_mode").to_string(),
+                items: AIMode::ALL
+                    .iter()
+                    .map(|mode| {
+                        action(
+                            format!("ai_mode_{:?}", mode),
+                            mode.to_string(),
+                            Some(*mode == self.tracking),
+                        )
+                    })
+                    .collect(),
+            });
+            menu.push(TrayMenuItem::Submenu {
+                label: t!("gui.tray.tracking_speed").to_string(),
+                items: TRACKING_SPEEDS
+                    .iter()
+                    .map(|speed| {
+                        action(
+                            format!("tracking_speed_{:?}", speed),
+                            speed.to_string(),
+                            Some(*speed == self.tracking_speed),
+                        )
+                    })
+                    .collect(),
+            });
+            menu.push(TrayMenuItem::Submenu {
+                label: t!("gui.tray.presets").to_string(),
+                items: [
+                    HotkeyAction::Preset1,
+                    HotkeyAction::Preset2,
+                    HotkeyAction::Preset3,
+                ]
+                .iter()
+                .map(|preset| {
+                    action(
+                        preset.id().to_string(),
+                        preset.description().to_string(),
+                        None,
+                    )
+                })
+                .collect(),
+            });
+            menu.push(TrayMenuItem::Separator);
+        }
+
+        menu.push(action(
+            "show_window".to_string(),
+            t!("gui.tray.show_window").to_string(),
+            None,
+        ));
+        menu.push(action(
+            "quit".to_string(),
+            t!("gui.tray.quit").to_string(),
+            None,
+        ));
+        menu
+    }
+
+    /// Shows the new readings of the raw data, keeping the highlights of the last change
+    /// of a selector until it changes again.
+    fn take_raw_samples(&mut self, samples: Vec<RawSample>) {
+        for sample in samples {
+            match self
+                .raw_samples
+                .iter_mut()
+                .find(|shown| shown.selector == sample.selector)
+            {
+                Some(shown) if !sample.changes.is_empty() => *shown = sample,
+                Some(_) => {}
+                None => self.raw_samples.push(sample),
+            }
+        }
+    }
+
+    /// Labels the entered offset of the selected selector, or removes its label if none is entered.
+    fn save_label(&mut self) {
+        let offset = self.label_offset.trim();
+        let Some(offset) = parse_hex(offset) else {
+            return eprintln!("{}", t!("gui.errors.invalid_offset", offset = offset));
+        };
+
+        let label = self.label_text.trim();
+        if label.is_empty() {
+            self.offset_labels.remove(self.label_selector, offset);
         } else {
-            time::every(Duration::from_secs(20)).map(|_| Message::CheckCamera)
+            self.offset_labels
+                .insert(self.label_selector, offset, label);
+        }
+
+        match self.offset_labels.save() {
+            Ok(()) => {
+                self.label_offset.clear();
+                self.label_text.clear();
+            }
+            Err(error) => eprintln!("{}", error),
+        }
+    }
+
+    /// Reads the status through the camera of the app and takes over the settings that changed
+    /// since the last reading.
+    fn poll_status(&mut self) {
+        let Some(Ok(changes)) = self
+            .camera
+            .as_ref()
+            .map(|camera| self.status_watcher.poll(camera))
+        else {
+            return;
+        };
+
+        for change in changes {
+            self.apply_status_change(change);
+        }
+    }
+
+    /// Reads the raw data of the camera and shows the bytes that changed since the last reading.
+    fn poll_raw(&mut self) {
+        let Some(Ok(samples)) = self
+            .camera
+            .as_ref()
+            .map(|camera| self.raw_watcher.poll(camera))
+        else {
+            return;
+        };
+
+        self.take_raw_samples(samples);
+    }
+
+    /// Takes over a setting changed elsewhere, e.g. by the CLI or the remote.
+    fn apply_status_change(&mut self, change: StatusChange) {
+        match change {
+            StatusChange::SleepMode(mode) => self.awake = mode,
+            StatusChange::AIMode(mode) => self.tracking = mode,
+            StatusChange::TrackingSpeed(speed) => self.tracking_speed = speed,
+            StatusChange::Hdr(hdr_on) => self.hdr_on = hdr_on,
+        }
+    }
+
+    fn subscription(&self) -> Subscription<Message> {
+        let status = match &self.camera {
+            Some(_) => time::every(STATUS_INTERVAL).map(|_| Message::PollStatus),
+            None => Subscription::none(),
+        };
+        let raw = match &self.camera {
+            Some(_) if self.watching_raw => {
+                time::every(RAW_WATCH_INTERVAL).map(|_| Message::PollRaw)
+            }
+            _ => Subscription::none(),
+        };
+
+        Subscription::batch([
+            Subscription::run(watch_cameras),
+            Subscription::run(watch_hotkeys),
+            Subscription::run(watch_tray),
+            window::close_requests().map(Message::CloseRequested),
+            status,
+            raw,
+        ])
+    }
+}
+
+/// The message shown in the dashboard if a change couldn't be applied to the camera.
+fn error_text<T>(result: Result<T, T4lError>) -> Option<String> {
+    result.err().map(|error| error.to_string())
+}
+
+/// Translates an entry of the tray menu for the tracking mode or speed into its message.
+fn tray_message(id: &str) -> Option<Message> {
+    if let Some(name) = id.strip_prefix("ai_mode_") {
+        let mode = AIMode::ALL
+            .iter()
+            .find(|mode| format!("{:?}", mode) == name)?;
+        return Some(Message::ChangeTracking(*mode));
+    }
+
+    let name = id.strip_prefix("tracking_speed_")?;
+    TRACKING_SPEEDS
+        .iter()
+        .find(|speed| format!("{:?}", speed) == name)
+        .map(|speed| Message::ChangeTrackingSpeed(*speed))
+}
+
+/// The camera icon of the dashboard, crossed out while the camera sleeps.
+fn tray_icon(sleep_mode: SleepMode) -> TrayIcon {
+    static ICONS: OnceLock<(TrayIcon, TrayIcon)> = OnceLock::new();
+
+    let (awake, asleep) = ICONS.get_or_init(|| {
+        (
+            load_tray_icon("generated/png/icons/inverted-camera.png"),
+            load_tray_icon("generated/png/icons/inverted-camera-asleep.png"),
+        )
+    });
+    if sleep_mode == SleepMode::Sleep {
+        asleep.clone()
+    } else {
+        awake.clone()
+    }
+}
+
+/// Decodes an icon of the assets for the tray, falling back to the icon of the application.
+fn load_tray_icon(asset: &str) -> TrayIcon {
+    let image = match handle_t4l_asset(asset) {
+        Handle::Path(_, path) => ::image::open(path).ok(),
+        Handle::Bytes(_, bytes) => ::image::load_from_memory(&bytes).ok(),
+        Handle::Rgba { .. } => None,
+    };
+
+    match image {
+        Some(image) => {
+            let image = image.thumbnail(TRAY_ICON_SIZE, TRAY_ICON_SIZE).to_rgba8();
+            TrayIcon::from_rgba(image.width(), image.height(), image.as_raw())
+        }
+        None => TrayIcon::named("tiny4linux"),
+    }
+}
+
+/// Reports video devices being plugged in or out, falling back to polling every few seconds
+/// if `/dev` can't be watched.
+fn watch_cameras() -> impl Stream<Item = Message> {
+    stream::channel(16, |output| async move {
+        let (sender, receiver) = mpsc::unbounded();
+
+        match HotplugWatcher::new() {
+            Ok(watcher) => {
+                watcher.spawn(move |event| sender.unbounded_send(Message::Hotplug(event)).is_ok());
+            }
+            Err(_) => {
+                thread::spawn(move || {
+                    while sender.unbounded_send(Message::CheckCamera).is_ok() {
+                        thread::sleep(Duration::from_secs(4));
+                    }
+                });
+            }
+        }
+
+        forward(receiver, output).await;
+    })
+}
+
+/// Reports the global shortcuts configured in the settings being pressed.
+///
+/// Nothing is bound without any configured, as the desktop may ask the user to confirm them.
+fn watch_hotkeys() -> impl Stream<Item = Message> {
+    stream::channel(16, |output| async move {
+        let (sender, receiver) = mpsc::unbounded();
+        let shortcuts: Vec<GlobalShortcut> = Settings::load()
+            .unwrap_or_default()
+            .hotkeys
+            .into_iter()
+            .map(|(action, trigger)| GlobalShortcut {
+                id: action.id().to_string(),
+                description: action.description().to_string(),
+                preferred_trigger: Some(trigger),
+            })
+            .collect();
+
+        if !shortcuts.is_empty() {
+            // binding waits until the user confirmed the shortcuts
+            thread::spawn(move || match GlobalShortcuts::bind(&shortcuts) {
+                Ok(shortcuts) => {
+                    let _ = shortcuts
+                        .spawn(move |id| {
+                            HotkeyAction::from_id(id).is_none_or(|action| {
+                                sender.unbounded_send(Message::Hotkey(action)).is_ok()
+                            })
+                        })
+                        .join();
+                }
+                Err(error) => eprintln!("{}", t!("gui.errors.hotkeys", error = error)),
+            });
+        }
+
+        forward(receiver, output).await;
+    })
+}
+
+/// Shows the icon in the tray and reports what the user does with it.
+///
+/// Without a host for tray icons, closing the window quits the application as before.
+fn watch_tray() -> impl Stream<Item = Message> {
+    stream::channel(16, |output| async move {
+        let (sender, receiver) = mpsc::unbounded();
+
+        thread::spawn(move || {
+            let events = sender.clone();
+            let tray = Tray::new(
+                "tiny4linux",
+                "Tiny4Linux",
+                tray_icon(SleepMode::Awake),
+                move |event| {
+                    let _ = events.unbounded_send(Message::Tray(event));
+                },
+            );
+
+            match tray {
+                Ok(tray) => {
+                    let _ = sender.unbounded_send(Message::TrayReady(TrayHandle(Arc::new(tray))));
+                }
+                Err(error) => eprintln!("{}", t!("gui.errors.tray", error = error)),
+            }
+        });
+
+        forward(receiver, output).await;
+    })
+}
+
+/// Passes the messages of a watcher thread on to the subscription.
+async fn forward(
+    mut receiver: mpsc::UnboundedReceiver<Message>,
+    mut output: mpsc::Sender<Message>,
+) {
+    while let Some(message) = receiver.next().await {
+        if output.send(message).await.is_err() {
+            break;
         }
     }
 }
@@ -246,6 +993,8 @@ fn get_window_settings_for_window_mode(window_mode: WindowMode) -> window::Setti
         max_size: Some(window_size),
         position: get_position_for_window_mode(window_mode),
         decorations: true,
+        // closing is handled by the application, which may keep running in the tray
+        exit_on_close_request: false,
         ..Default::default()
     }
 }
@@ -287,9 +1036,8 @@ fn main() -> iced::Result {
 
     println!("Starting Tiny4Linux in {:?} mode", start_mode);
 
-    iced::application("Tiny4Linux", MainPanel::update, MainPanel::view)
-        .theme(|_| obsbot_theme())
-        .window(get_window_settings_for_window_mode(start_mode))
+    iced::daemon("Tiny4Linux", MainPanel::update, MainPanel::view)
+        .theme(|_, _| obsbot_theme())
         .subscription(MainPanel::subscription)
         .run_with(move || MainPanel::init_state(start_mode))
 }
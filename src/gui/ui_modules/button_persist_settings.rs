@@ -0,0 +1,24 @@
+// SPDX-License-Identifier: EUPL-1.2
+
+use crate::Message;
+use crate::styles::tooltip_style::tooltip_content;
+use iced::widget::button::{primary, secondary};
+use iced::widget::tooltip::Position;
+use iced::widget::{Container, button, container, text, tooltip};
+use iced_font_awesome::fa_icon_solid;
+use rust_i18n::t;
+
+/// Turns on applying the last settings again once the camera reconnects.
+pub fn button_persist_settings(persist_settings: bool) -> Container<'static, Message> {
+    container(tooltip(
+        button(fa_icon_solid("thumbtack"))
+            .on_press(Message::ChangePersistSettings(!persist_settings))
+            .style(if persist_settings { primary } else { secondary }),
+        tooltip_content(container(text(if persist_settings {
+            t!("gui.tooltips.persist_settings.turns_off")
+        } else {
+            t!("gui.tooltips.persist_settings.turns_on")
+        }))),
+        Position::Bottom,
+    ))
+}
@@ -0,0 +1,24 @@
+// SPDX-License-Identifier: EUPL-1.2
+
+use crate::styles::tooltip_style::tooltip_content;
+use crate::{MainPanel, Message};
+use iced::widget::tooltip::Position;
+use iced::widget::{Container, Space, container, pick_list, text, tooltip};
+use rust_i18n::t;
+
+/// Lets the user choose between the cameras, only shown if more than one is connected.
+pub fn camera_picker(app: &MainPanel) -> Container<'static, Message> {
+    if app.devices.len() < 2 {
+        return container(Space::new(0, 0));
+    }
+
+    container(tooltip(
+        pick_list(
+            app.devices.clone(),
+            app.selected_device.clone(),
+            Message::SelectCamera,
+        ),
+        tooltip_content(container(text(t!("gui.tooltips.camera_picker")))),
+        Position::Bottom,
+    ))
+}
@@ -1,6 +1,7 @@
 // SPDX-License-Identifier: EUPL-1.2
 
 use crate::Message::{HexDump, HexDump02};
+use crate::ui_modules::raw_watch_area::raw_watch_area;
 use crate::{MainPanel, Message};
 use iced::Length;
 use iced::alignment::Horizontal;
@@ -91,7 +92,8 @@ pub fn debug_area(app: &MainPanel) -> Container<'static, Message> {
                             .width(DEBUG_BUTTON_WIDTH)
                             .on_press(HexDump02),
                     ]
-                    .spacing(15)
+                    .spacing(15),
+                    raw_watch_area(app),
                 ]
                 .spacing(10)
             } else {
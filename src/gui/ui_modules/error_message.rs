@@ -0,0 +1,19 @@
+// SPDX-License-Identifier: EUPL-1.2
+
+use crate::Message;
+use iced::widget::{Container, container, row, text};
+use iced_font_awesome::fa_icon_solid;
+
+/// Shows why the last change couldn't be applied to the camera.
+pub fn error_message(error: &Option<String>) -> Option<Container<'static, Message>> {
+    error.as_ref().map(|error| {
+        container(
+            row![
+                fa_icon_solid("triangle-exclamation").style(text::danger),
+                text(error.clone()).style(text::danger)
+            ]
+            .spacing(10),
+        )
+        .padding(5)
+    })
+}
@@ -2,10 +2,15 @@
 
 mod button_exposure_mode;
 mod button_hdr;
+mod button_persist_settings;
 mod button_sleep_wake;
 mod button_tracking_mode;
 mod button_window_mode_change;
+mod camera_picker;
 mod current_stats;
 mod debug_area;
+mod error_message;
+mod profile_picker;
+mod raw_watch_area;
 mod settings_area;
 pub mod window_layout;
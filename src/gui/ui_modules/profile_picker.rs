@@ -0,0 +1,22 @@
+// SPDX-License-Identifier: EUPL-1.2
+
+use crate::styles::tooltip_style::tooltip_content;
+use crate::{MainPanel, Message};
+use iced::widget::tooltip::Position;
+use iced::widget::{Container, Space, container, pick_list, text, tooltip};
+use rust_i18n::t;
+
+/// Applies one of the profiles saved with `t4l profile save`, only shown if there are any.
+pub fn profile_picker(app: &MainPanel) -> Container<'static, Message> {
+    let names = app.profiles.names();
+    if names.is_empty() {
+        return container(Space::new(0, 0));
+    }
+
+    container(tooltip(
+        pick_list(names, None::<String>, Message::ApplyProfile)
+            .placeholder(t!("gui.text.profile_placeholder")),
+        tooltip_content(container(text(t!("gui.tooltips.profile_picker")))),
+        Position::Bottom,
+    ))
+}
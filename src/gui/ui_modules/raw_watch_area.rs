@@ -0,0 +1,152 @@
+// SPDX-License-Identifier: EUPL-1.2
+
+use crate::styles::colors::COLOR_PRIMARY_OBSBOT;
+use crate::styles::tooltip_style::tooltip_content;
+use crate::{MainPanel, Message};
+use iced::widget::button::{primary, secondary};
+use iced::widget::tooltip::Position;
+use iced::widget::{
+    Column, Row, button, column, container, horizontal_space, row, text, text_input, tooltip,
+};
+use iced::{Element, Font};
+use iced_font_awesome::fa_icon_solid;
+use rust_i18n::t;
+use tiny4linux::{RawSample, WATCHED_SELECTORS};
+
+/// Bytes per line of the hex dumps.
+const BYTES_PER_LINE: usize = 16;
+const DEBUG_BUTTON_WIDTH: f32 = 100.0;
+const LABEL_OFFSET_WIDTH: f32 = 80.0;
+
+/// The raw data of the watched selectors with their changed bytes highlighted,
+/// and the inputs to label an offset.
+pub fn raw_watch_area(app: &MainPanel) -> Column<'static, Message> {
+    let watching = app.watching_raw;
+
+    let mut area = column![
+        row![
+            row![
+                fa_icon_solid("magnifying-glass"),
+                text(t!("gui.text.debugging.watch_status"))
+            ]
+            .spacing(5),
+            horizontal_space(),
+            button(text(if watching {
+                t!("gui.text.debugging.watch_stop")
+            } else {
+                t!("gui.text.debugging.watch_start")
+            }))
+            .on_press(Message::ChangeWatchingRaw(!watching))
+            .style(if watching { primary } else { secondary })
+            .width(DEBUG_BUTTON_WIDTH),
+        ]
+        .spacing(15)
+    ]
+    .spacing(10);
+
+    if !watching {
+        return area;
+    }
+
+    for sample in &app.raw_samples {
+        area = area.push(hex_dump(app, sample));
+    }
+
+    area.push(label_editor(app))
+}
+
+fn hex_dump(app: &MainPanel, sample: &RawSample) -> Column<'static, Message> {
+    let mut dump = column![text(format!("{:#04x}", sample.selector)).font(Font::MONOSPACE)];
+
+    for (line, bytes) in sample.bytes.chunks(BYTES_PER_LINE).enumerate() {
+        let offset = line * BYTES_PER_LINE;
+        let mut cells = Row::new()
+            .spacing(6)
+            .push(text(format!("{:02x}:", offset)).font(Font::MONOSPACE));
+
+        for (index, byte) in bytes.iter().enumerate() {
+            cells = cells.push(byte_cell(app, sample, offset + index, *byte));
+        }
+        dump = dump.push(cells);
+    }
+
+    for change in &sample.changes {
+        dump = dump.push(
+            text(format!(
+                "{:#04x}  {:02x} → {:02x}  {}",
+                change.offset,
+                change.old,
+                change.new,
+                app.offset_labels
+                    .describe(sample.selector, change.offset)
+                    .unwrap_or_default()
+            ))
+            .font(Font::MONOSPACE)
+            .color(COLOR_PRIMARY_OBSBOT),
+        );
+    }
+
+    dump.spacing(2)
+}
+
+/// A byte of the dump, highlighted if it changed and described by a tooltip if its offset is known.
+fn byte_cell(
+    app: &MainPanel,
+    sample: &RawSample,
+    offset: usize,
+    byte: u8,
+) -> Element<'static, Message> {
+    let mut cell = text(format!("{:02x}", byte)).font(Font::MONOSPACE);
+    if sample.is_changed(offset) {
+        cell = cell.color(COLOR_PRIMARY_OBSBOT);
+    }
+
+    match app.offset_labels.describe(sample.selector, offset) {
+        Some(description) => tooltip(
+            cell,
+            tooltip_content(container(text(format!("{:#04x}: {}", offset, description)))),
+            Position::Top,
+        )
+        .into(),
+        None => cell.into(),
+    }
+}
+
+fn label_editor(app: &MainPanel) -> Row<'static, Message> {
+    let mut editor = row![text(t!("gui.text.debugging.label"))].spacing(10);
+
+    for selector in WATCHED_SELECTORS {
+        editor = editor.push(
+            button(text(format!("{:#04x}", selector)))
+                .on_press(Message::SelectLabelSelector(selector))
+                .style(if app.label_selector == selector {
+                    primary
+                } else {
+                    secondary
+                }),
+        );
+    }
+
+    editor
+        .push(
+            text_input(
+                t!("gui.text.debugging.label_offset").as_ref(),
+                &app.label_offset,
+            )
+            .on_input(Message::LabelOffsetInput)
+            .width(LABEL_OFFSET_WIDTH),
+        )
+        .push(
+            text_input(
+                t!("gui.text.debugging.label_text").as_ref(),
+                &app.label_text,
+            )
+            .on_input(Message::LabelTextInput)
+            .on_submit(Message::SaveLabel),
+        )
+        .push(
+            button(text(t!("gui.text.debugging.label_save")))
+                .on_press(Message::SaveLabel)
+                .width(DEBUG_BUTTON_WIDTH),
+        )
+}
@@ -7,52 +7,165 @@ use crate::ui_modules::button_tracking_mode::{button_tracking_mode, button_track
 use crate::{MainPanel, Message, WindowMode};
 use iced::Length;
 use iced::alignment::{Horizontal, Vertical};
+use iced::widget::button::primary;
+#[cfg(feature = "experimental")]
+use iced::widget::button::secondary;
 use iced::widget::tooltip::Position;
 use iced::widget::{
-    Container, Row, button, column, container, horizontal_rule, horizontal_space, row, text,
-    tooltip,
+    Container, Row, Tooltip, button, column, container, horizontal_rule, horizontal_space, row,
+    slider, text, tooltip,
 };
 use iced_font_awesome::fa_icon_solid;
 use rust_i18n::t;
-use tiny4linux::{AIMode, ExposureMode, TrackingSpeed};
+use tiny4linux::{AIMode, ControlRange, ExposureMode, PanTilt, TrackingSpeed};
 
 pub fn settings_area(app: &MainPanel) -> Container<'static, Message> {
-    container(
-        column![
-            presets(),
-            horizontal_rule(8),
-            tracking_modes(app.window_mode == WindowMode::Widget, app.tracking),
-            tracking_speed(app.tracking_speed),
-            horizontal_rule(8),
-            row![hdr(app.hdr_on), exposure_mode()]
-                .spacing(10)
-                .align_y(Vertical::Center),
-        ]
-        .spacing(20),
-    )
-    .padding(10)
+    let mut settings = column![
+        presets(
+            #[cfg(feature = "experimental")]
+            app.storing_preset,
+        ),
+        pan_tilt(),
+        horizontal_rule(8),
+        tracking_modes(app.window_mode == WindowMode::Widget, app.tracking),
+        tracking_speed(app.tracking_speed),
+        horizontal_rule(8),
+        row![hdr(app.hdr_on), exposure_mode(app.exposure_mode)]
+            .spacing(10)
+            .align_y(Vertical::Center),
+    ]
+    .spacing(20);
+
+    if let Some(range) = app.zoom_range {
+        settings = settings.push(zoom(app.zoom, range));
+    }
+
+    container(settings).padding(10)
 }
 
-fn presets() -> Row<'static, Message> {
-    row![
+fn presets(#[cfg(feature = "experimental")] storing: bool) -> Row<'static, Message> {
+    let presets = row![
         text(format!("{}:", t!("shared.info.presets"))),
         horizontal_space().width(Length::FillPortion(2)),
         (0..=2)
             .fold(row![], |r, n| {
-                let r = r.push(tooltip(
-                    button(fa_icon_solid(&(n + 1).to_string()))
-                        .on_press(Message::ChangePresetPosition(n)),
-                    tooltip_content(container(text(t!(
-                        "gui.tooltips.preset",
-                        preset_number = n + 1
-                    )))),
-                    Position::Bottom,
+                let r = r.push(preset_button(
+                    n,
+                    #[cfg(feature = "experimental")]
+                    storing,
                 ));
                 r.push(horizontal_space().width(Length::FillPortion(1)))
             })
             .width(Length::FillPortion(6)),
+    ];
+
+    // Saving presets sends a frame that hasn't been captured from the OBSBOT app yet.
+    #[cfg(feature = "experimental")]
+    let presets = presets.push(tooltip(
+        button(fa_icon_solid("floppy-disk"))
+            .on_press(Message::ChangeStoringPreset(!storing))
+            .style(if storing { primary } else { secondary }),
+        tooltip_content(container(text(if storing {
+            t!("gui.tooltips.storing_preset.turns_off")
+        } else {
+            t!("gui.tooltips.storing_preset.turns_on")
+        }))),
+        Position::Bottom,
+    ));
+
+    presets.push(horizontal_space().width(Length::FillPortion(1)))
+}
+
+/// Moves to the preset.
+#[cfg(not(feature = "experimental"))]
+fn preset_button(n: i8) -> Tooltip<'static, Message> {
+    tooltip(
+        button(fa_icon_solid(&(n + 1).to_string()))
+            .on_press(Message::ChangePresetPosition(n))
+            .style(primary),
+        tooltip_content(container(text(t!(
+            "gui.tooltips.preset",
+            preset_number = n + 1
+        )))),
+        Position::Bottom,
+    )
+}
+
+/// Moves to the preset, or saves the current position into it while storing presets.
+#[cfg(feature = "experimental")]
+fn preset_button(n: i8, storing: bool) -> Tooltip<'static, Message> {
+    tooltip(
+        button(fa_icon_solid(&(n + 1).to_string()))
+            .on_press(if storing {
+                Message::SavePresetPosition(n)
+            } else {
+                Message::ChangePresetPosition(n)
+            })
+            .style(if storing { secondary } else { primary }),
+        tooltip_content(container(text(if storing {
+            t!("gui.tooltips.save_preset", preset_number = n + 1)
+        } else {
+            t!("gui.tooltips.preset", preset_number = n + 1)
+        }))),
+        Position::Bottom,
+    )
+}
+
+fn pan_tilt() -> Row<'static, Message> {
+    row![
+        text(format!("{}:", t!("shared.info.pan_tilt"))),
+        horizontal_space().width(Length::FillPortion(2)),
+        column![
+            pan_tilt_button(
+                "arrow-up",
+                t!("gui.tooltips.pan_tilt.up"),
+                PanTilt::Relative { pan: 0, tilt: 1 }
+            ),
+            row![
+                pan_tilt_button(
+                    "arrow-left",
+                    t!("gui.tooltips.pan_tilt.left"),
+                    PanTilt::Relative { pan: -1, tilt: 0 }
+                ),
+                pan_tilt_button(
+                    "crosshairs",
+                    t!("gui.tooltips.pan_tilt.center"),
+                    PanTilt::Absolute {
+                        pan: 0.0,
+                        tilt: 0.0
+                    }
+                ),
+                pan_tilt_button(
+                    "arrow-right",
+                    t!("gui.tooltips.pan_tilt.right"),
+                    PanTilt::Relative { pan: 1, tilt: 0 }
+                ),
+            ]
+            .spacing(5),
+            pan_tilt_button(
+                "arrow-down",
+                t!("gui.tooltips.pan_tilt.down"),
+                PanTilt::Relative { pan: 0, tilt: -1 }
+            ),
+        ]
+        .spacing(5)
+        .align_x(Horizontal::Center)
+        .width(Length::FillPortion(6)),
         horizontal_space().width(Length::FillPortion(2))
     ]
+    .align_y(Vertical::Center)
+}
+
+fn pan_tilt_button(
+    icon: &'static str,
+    tooltip_text: impl ToString,
+    movement: PanTilt,
+) -> Container<'static, Message> {
+    container(tooltip(
+        button(fa_icon_solid(icon)).on_press(Message::ChangePanTilt(movement)),
+        tooltip_content(container(text(tooltip_text.to_string()))),
+        Position::Bottom,
+    ))
 }
 
 fn tracking_modes(reduced: bool, current_mode: AIMode) -> Container<'static, Message> {
@@ -138,16 +251,31 @@ fn hdr(current_mode: bool) -> Container<'static, Message> {
     )
 }
 
-fn exposure_mode() -> Container<'static, Message> {
+fn exposure_mode(current_mode: Option<ExposureMode>) -> Container<'static, Message> {
     container(
         column![
             text(format!("{}:", t!("shared.info.exposure"))),
-            button_exposure_mode(ExposureMode::Manual),
-            button_exposure_mode(ExposureMode::Global),
-            button_exposure_mode(ExposureMode::Face),
+            button_exposure_mode(ExposureMode::Manual, current_mode),
+            button_exposure_mode(ExposureMode::Global, current_mode),
+            button_exposure_mode(ExposureMode::Face, current_mode),
         ]
         .align_x(Horizontal::Center)
         .width(Length::Fill)
         .spacing(5),
     )
 }
+
+fn zoom(current_zoom: i32, range: ControlRange) -> Row<'static, Message> {
+    row![
+        text(format!("{}:", t!("shared.info.zoom"))),
+        slider(
+            range.minimum..=range.maximum,
+            current_zoom,
+            Message::ChangeZoom
+        )
+        .step(range.step.max(1)),
+        text(current_zoom.to_string()),
+    ]
+    .spacing(10)
+    .align_y(Vertical::Center)
+}
@@ -2,10 +2,14 @@
 
 use crate::styles::button_non_styled::button_non_styled;
 use crate::styles::general_area_style::general_area_style;
+use crate::ui_modules::button_persist_settings::button_persist_settings;
 use crate::ui_modules::button_sleep_wake::button_sleep_wake;
 use crate::ui_modules::button_window_mode_change::button_window_mode_change;
+use crate::ui_modules::camera_picker::camera_picker;
 use crate::ui_modules::current_stats::current_stats;
 use crate::ui_modules::debug_area::debug_area;
+use crate::ui_modules::error_message::error_message;
+use crate::ui_modules::profile_picker::profile_picker;
 use crate::ui_modules::settings_area::settings_area;
 use crate::{MainPanel, Message, WindowMode};
 use iced::alignment::Vertical;
@@ -84,14 +88,16 @@ fn dashboard_general_area(app: &MainPanel) -> Container<'static, Message> {
 }
 
 fn dashboard_settings_area(app: &MainPanel) -> Column<'static, Message> {
-    column![
-        row![
-            Space::with_width(Length::Fill),
-            button_window_mode_change(WindowMode::Dashboard)
-        ],
-        settings_area(app),
-        debug_area(app)
-    ]
+    column![row![
+        camera_picker(app),
+        profile_picker(app),
+        Space::with_width(Length::Fill),
+        button_persist_settings(app.persist_settings),
+        button_window_mode_change(WindowMode::Dashboard)
+    ]]
+    .push_maybe(error_message(&app.error))
+    .push(settings_area(app))
+    .push(debug_area(app))
 }
 
 fn widget_head_area(app: &MainPanel) -> Container<'static, Message> {
@@ -118,5 +124,7 @@ fn widget_head_area(app: &MainPanel) -> Container<'static, Message> {
 }
 
 fn widget_body_area(app: &MainPanel) -> Column<'static, Message> {
-    column![settings_area(app)]
+    column![]
+        .push_maybe(error_message(&app.error))
+        .push(settings_area(app))
 }
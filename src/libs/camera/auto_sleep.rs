@@ -0,0 +1,153 @@
+// SPDX-License-Identifier: EUPL-1.2
+
+use crate::libs::camera::camera::Tiny2Camera;
+use crate::libs::camera::enums::SleepMode;
+use crate::libs::errors::T4lError;
+use crate::libs::usbio::is_streaming;
+use std::time::{Duration, Instant};
+
+/// Puts the camera to sleep once no application streamed from it for a while,
+/// and wakes it up as soon as one starts streaming.
+///
+/// The camera is only woken up or put to sleep when the streaming starts or the timeout passes,
+/// so it can still be put to sleep or woken up by hand in between.
+/// The camera is passed to every poll, so that e.g. the daemon can share its handle.
+pub struct AutoSleep {
+    device_path: String,
+    idle_timeout: Duration,
+    streaming: bool,
+    idle_since: Option<Instant>,
+    put_to_sleep: bool,
+}
+
+impl AutoSleep {
+    /// Watches the video device of the camera, e.g. `/dev/video0`.
+    pub fn new(device_path: &str, idle_timeout: Duration) -> Self {
+        Self {
+            device_path: device_path.to_string(),
+            idle_timeout,
+            streaming: false,
+            idle_since: None,
+            put_to_sleep: false,
+        }
+    }
+
+    /// Checks once whether an application is streaming and wakes up or puts the camera to sleep
+    /// if that's due, returning the sleep mode it was set to.
+    pub fn poll(&mut self, camera: &impl Tiny2Camera) -> Result<Option<SleepMode>, T4lError> {
+        let streaming = is_streaming(&self.device_path)?;
+
+        let mode = self.next_mode(streaming, Instant::now());
+        if let Some(mode) = mode {
+            camera.set_sleep_mode(mode)?;
+        }
+        Ok(mode)
+    }
+
+    /// Decides about the sleep mode for the current state of the streaming.
+    fn next_mode(&mut self, streaming: bool, now: Instant) -> Option<SleepMode> {
+        let started = streaming && !self.streaming;
+        self.streaming = streaming;
+
+        if streaming {
+            self.idle_since = None;
+            self.put_to_sleep = false;
+            return started.then_some(SleepMode::Awake);
+        }
+
+        // put to sleep only once per idle time
+        let idle_since = *self.idle_since.get_or_insert(now);
+        if self.put_to_sleep || now.duration_since(idle_since) < self.idle_timeout {
+            return None;
+        }
+
+        self.put_to_sleep = true;
+        Some(SleepMode::Sleep)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod unit {
+        use crate::{AutoSleep, SleepMode};
+        use std::time::{Duration, Instant};
+
+        fn auto_sleep() -> AutoSleep {
+            AutoSleep::new("simulated", Duration::from_secs(60))
+        }
+
+        #[test]
+        fn sleeps_after_timeout() {
+            let mut auto_sleep = auto_sleep();
+            let start = Instant::now();
+
+            assert_eq!(auto_sleep.next_mode(false, start), None);
+            assert_eq!(
+                auto_sleep.next_mode(false, start + Duration::from_secs(59)),
+                None
+            );
+            assert_eq!(
+                auto_sleep.next_mode(false, start + Duration::from_secs(60)),
+                Some(SleepMode::Sleep)
+            );
+        }
+
+        #[test]
+        fn sleeps_only_once() {
+            let mut auto_sleep = auto_sleep();
+            let start = Instant::now();
+
+            auto_sleep.next_mode(false, start);
+            auto_sleep.next_mode(false, start + Duration::from_secs(60));
+
+            assert_eq!(
+                auto_sleep.next_mode(false, start + Duration::from_secs(600)),
+                None
+            );
+        }
+
+        #[test]
+        fn wakes_when_streaming_starts() {
+            let mut auto_sleep = auto_sleep();
+            let start = Instant::now();
+
+            assert_eq!(auto_sleep.next_mode(true, start), Some(SleepMode::Awake));
+            assert_eq!(
+                auto_sleep.next_mode(true, start + Duration::from_secs(1)),
+                None
+            );
+        }
+
+        #[test]
+        fn idle_time_starts_when_streaming_stops() {
+            let mut auto_sleep = auto_sleep();
+            let start = Instant::now();
+
+            auto_sleep.next_mode(false, start);
+            auto_sleep.next_mode(true, start + Duration::from_secs(30));
+
+            assert_eq!(
+                auto_sleep.next_mode(false, start + Duration::from_secs(70)),
+                None
+            );
+            assert_eq!(
+                auto_sleep.next_mode(false, start + Duration::from_secs(130)),
+                Some(SleepMode::Sleep)
+            );
+        }
+    }
+
+    mod integration {
+        use crate::{AutoSleep, Camera, SleepMode, Tiny2Camera};
+        use std::time::Duration;
+
+        #[test]
+        fn puts_idle_camera_to_sleep() {
+            let camera = Camera::simulated();
+            let mut auto_sleep = AutoSleep::new("simulated", Duration::ZERO);
+
+            assert_eq!(auto_sleep.poll(&camera).unwrap(), Some(SleepMode::Sleep));
+            assert_eq!(camera.get_sleep_mode().unwrap(), SleepMode::Sleep);
+        }
+    }
+}
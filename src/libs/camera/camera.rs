@@ -1,14 +1,29 @@
 // SPDX-License-Identifier: EUPL-1.2
 
-use crate::libs::camera::enums::{AIMode, ExposureMode, SleepMode, TrackingSpeed};
+#[cfg(feature = "experimental")]
+use crate::SavePresetPositionCommand;
+use crate::libs::camera::enums::{AIMode, ExposureMode, PanTilt, SleepMode, TrackingSpeed};
 use crate::libs::camera::status::CameraStatus;
 use crate::libs::camera::transport::CameraTransport;
 use crate::libs::errors::T4lError;
+use crate::libs::usbio::{
+    CameraDevice, CameraInfo, ControlRange, V4L2_CID_PAN_ABSOLUTE, V4L2_CID_TILT_ABSOLUTE,
+    V4L2_CID_ZOOM_ABSOLUTE,
+};
 use crate::{
-    AIModeCommand, ExposureModeCommand, ExposureModeTypeCommand, GotoPresetPositionCommand,
-    HdrModeCommand, SleepCommand, TrackingSpeedCommand,
+    AIModeCommand, Command02Frame, ExposureModeCommand, ExposureModeTypeCommand,
+    GotoPresetPositionCommand, HdrModeCommand, SleepCommand, TrackingSpeedCommand, find_camera,
 };
 use errno::Errno;
+use std::path::Path;
+
+/// Part of the card name all supported cameras share, e.g. `OBSBOT Tiny 2` and `OBSBOT Tiny 2 Lite`.
+pub const CAMERA_HINT: &str = "OBSBOT Tiny 2";
+
+/// The pan and tilt controls are reported in arc seconds.
+const ARC_SECONDS_PER_DEGREE: f64 = 3600.0;
+/// Degrees the gimbal moves per step of a relative movement.
+const PAN_TILT_NUDGE_DEGREES: i32 = 5;
 
 pub struct Camera {
     transport: CameraTransport,
@@ -23,7 +38,34 @@ impl Camera {
         })
     }
 
-    pub fn info(&self) -> Result<(), Errno> {
+    /// Opens the camera directly instead of through the daemon, as the daemon itself does.
+    pub fn new_local(hint: &str) -> Result<Self, T4lError> {
+        Ok(Self {
+            transport: CameraTransport::new_local(hint)?,
+            debugging: false,
+        })
+    }
+
+    /// Opens the camera meant by the selector, which is its index in [`list_cameras`](crate::list_cameras),
+    /// its path, its bus or its serial.
+    pub fn select(selector: &str) -> Result<Self, T4lError> {
+        Self::open(&find_camera(CAMERA_HINT, selector)?)
+    }
+
+    /// Opens the given camera, e.g. one returned by [`list_cameras`](crate::list_cameras).
+    pub fn open(device: &CameraDevice) -> Result<Self, T4lError> {
+        Self::new(&device.path)
+    }
+
+    /// Creates a camera that isn't backed by a device but by an in-memory simulation.
+    pub fn simulated() -> Self {
+        Self {
+            transport: CameraTransport::simulated(),
+            debugging: false,
+        }
+    }
+
+    pub fn info(&self) -> Result<CameraInfo, Errno> {
         self.transport.info()
     }
 
@@ -31,10 +73,23 @@ impl Camera {
         self.transport.send_cmd(unit, selector, cmd, self.debugging)
     }
 
+    pub fn send_cmd02(&self, frame: &[u8; 36]) -> Result<(), T4lError> {
+        self.transport.send_cmd02(frame, self.debugging)
+    }
+
     pub fn get_status(&self) -> Result<CameraStatus, T4lError> {
         self.transport.get_status(self.debugging)
     }
 
+    pub fn get_frame02(&self) -> Result<Command02Frame, T4lError> {
+        self.transport.get_frame02()
+    }
+
+    /// Reads the current data of the unit and selector, e.g. `0x2, 0x6` for the raw status.
+    pub fn get_raw(&self, unit: u8, selector: u8) -> Result<Vec<u8>, T4lError> {
+        self.transport.get_raw(unit, selector)
+    }
+
     pub fn dump(&self) -> Result<(), Errno> {
         self.transport.dump()
     }
@@ -46,6 +101,67 @@ impl Camera {
     pub fn set_debugging(&mut self, debugging: bool) {
         self.debugging = debugging
     }
+
+    /// Writes every request sent from now on to the camera to the file as a [`Recording`](crate::Recording),
+    /// e.g. to reverse-engineer a feature.
+    pub fn start_recording(&mut self, path: &Path) -> Result<(), T4lError> {
+        self.transport.start_recording(path)
+    }
+
+    /// Sets a standard V4L2 control without checking its range, e.g. for a [`CameraRequest`](crate::CameraRequest).
+    pub(crate) fn set_ctrl(&self, id: u32, value: i32) -> Result<(), T4lError> {
+        self.transport.set_ctrl(id, value, self.debugging)
+    }
+
+    /// Sends a raw UVC request, e.g. `UVC_GET_CUR`, to the unit and selector of the camera.
+    pub fn query(
+        &self,
+        unit: u8,
+        selector: u8,
+        query: u8,
+        data: &mut [u8],
+    ) -> Result<(), T4lError> {
+        self.transport.query(unit, selector, query, data)
+    }
+
+    /// Sends a raw UVC request to the unit and selector of the camera and returns the data of its answer,
+    /// with the data sized as the request needs it, e.g. for `t4l raw`.
+    pub fn request(
+        &self,
+        unit: u8,
+        selector: u8,
+        query: u8,
+        data: &[u8],
+    ) -> Result<Vec<u8>, T4lError> {
+        self.transport.request(unit, selector, query, data)
+    }
+
+    #[cfg(feature = "dbus")]
+    pub(crate) fn handle(&self) -> &crate::libs::usbio::CameraHandleType {
+        self.transport.handle()
+    }
+
+    #[cfg(feature = "dbus")]
+    pub(crate) fn relay_io(
+        &self,
+        unit: u8,
+        selector: u8,
+        query: u8,
+        data: &mut [u8],
+    ) -> Result<(), Errno> {
+        self.transport.relay_io(unit, selector, query, data)
+    }
+
+    #[cfg(feature = "dbus")]
+    pub(crate) fn relay(
+        &self,
+        unit: u8,
+        selector: u8,
+        query: u8,
+        data: &mut [u8],
+    ) -> Result<usize, Errno> {
+        self.transport.relay(unit, selector, query, data)
+    }
 }
 
 pub trait Tiny2Camera {
@@ -54,10 +170,18 @@ pub trait Tiny2Camera {
     fn set_ai_mode(&self, mode: AIMode) -> Result<(), T4lError>;
     fn get_ai_mode(&self) -> Result<AIMode, T4lError>;
     fn goto_preset_position(&self, preset_nr: i8) -> Result<(), T4lError>;
+    #[cfg(feature = "experimental")]
+    fn save_preset_position(&self, preset_nr: i8) -> Result<(), T4lError>;
     fn get_tracking_speed(&self) -> Result<TrackingSpeed, T4lError>;
     fn set_tracking_speed(&self, speed: TrackingSpeed) -> Result<(), T4lError>;
     fn set_hdr_mode(&self, mode: bool) -> Result<(), T4lError>;
+    fn get_hdr_mode(&self) -> Result<bool, T4lError>;
     fn set_exposure_mode(&self, mode: ExposureMode) -> Result<(), T4lError>;
+    fn get_zoom(&self) -> Result<i32, T4lError>;
+    fn get_zoom_range(&self) -> Result<ControlRange, T4lError>;
+    fn set_zoom(&self, zoom: i32) -> Result<(), T4lError>;
+    fn get_pan_tilt(&self) -> Result<(f64, f64), T4lError>;
+    fn set_pan_tilt(&self, movement: PanTilt) -> Result<(), T4lError>;
     fn set_debugging(&mut self, debugging: bool);
 }
 
@@ -65,7 +189,7 @@ impl Tiny2Camera for Camera {
     fn set_sleep_mode(&self, mode: SleepMode) -> Result<(), T4lError> {
         let cmd = SleepCommand::build(mode)?;
 
-        self.send_cmd(0x2, 0x2, &cmd)
+        self.send_cmd02(&cmd)
     }
 
     fn get_sleep_mode(&self) -> Result<SleepMode, T4lError> {
@@ -85,7 +209,14 @@ impl Tiny2Camera for Camera {
     fn goto_preset_position(&self, preset_nr: i8) -> Result<(), T4lError> {
         let cmd = GotoPresetPositionCommand::build(preset_nr)?;
 
-        self.send_cmd(0x2, 0x2, &cmd)
+        self.send_cmd02(&cmd)
+    }
+
+    #[cfg(feature = "experimental")]
+    fn save_preset_position(&self, preset_nr: i8) -> Result<(), T4lError> {
+        let cmd = SavePresetPositionCommand::build(preset_nr)?;
+
+        self.send_cmd02(&cmd)
     }
 
     fn get_tracking_speed(&self) -> Result<TrackingSpeed, T4lError> {
@@ -97,7 +228,7 @@ impl Tiny2Camera for Camera {
 
         self.get_status()?.speed = speed;
 
-        self.send_cmd(0x2, 0x2, &cmd)
+        self.send_cmd02(&cmd)
     }
 
     fn set_hdr_mode(&self, mode: bool) -> Result<(), T4lError> {
@@ -106,10 +237,14 @@ impl Tiny2Camera for Camera {
         self.send_cmd(0x2, 0x6, &cmd)
     }
 
+    fn get_hdr_mode(&self) -> Result<bool, T4lError> {
+        Ok(self.get_status()?.hdr_on)
+    }
+
     fn set_exposure_mode(&self, mode: ExposureMode) -> Result<(), T4lError> {
         let exposure_mode_type_command = ExposureModeTypeCommand::build(mode);
 
-        self.send_cmd(0x2, 0x2, &exposure_mode_type_command)?;
+        self.send_cmd02(&exposure_mode_type_command)?;
 
         let exposure_mode_command = ExposureModeCommand::build(mode);
 
@@ -119,6 +254,79 @@ impl Tiny2Camera for Camera {
         Ok(())
     }
 
+    fn get_zoom(&self) -> Result<i32, T4lError> {
+        self.transport.get_ctrl(V4L2_CID_ZOOM_ABSOLUTE)
+    }
+
+    fn get_zoom_range(&self) -> Result<ControlRange, T4lError> {
+        self.transport.query_ctrl(V4L2_CID_ZOOM_ABSOLUTE)
+    }
+
+    fn set_zoom(&self, zoom: i32) -> Result<(), T4lError> {
+        if !self.get_zoom_range()?.contains(zoom) {
+            return Err(T4lError::UnsupportedIntValue("zoom".to_string(), zoom));
+        }
+
+        self.transport
+            .set_ctrl(V4L2_CID_ZOOM_ABSOLUTE, zoom, self.debugging)
+    }
+
+    /// Returns the current pan and tilt of the gimbal in degrees.
+    fn get_pan_tilt(&self) -> Result<(f64, f64), T4lError> {
+        let pan = self.transport.get_ctrl(V4L2_CID_PAN_ABSOLUTE)?;
+        let tilt = self.transport.get_ctrl(V4L2_CID_TILT_ABSOLUTE)?;
+
+        Ok((
+            pan as f64 / ARC_SECONDS_PER_DEGREE,
+            tilt as f64 / ARC_SECONDS_PER_DEGREE,
+        ))
+    }
+
+    /// Moves the gimbal, using the absolute pan and tilt controls.
+    ///
+    /// Relative movements are applied to the current position and stop at the limits of the gimbal.
+    /// The relative UVC controls aren't used for them, as uvcvideo maps those to a speed,
+    /// which moves the gimbal until it is stopped again instead of by a distance.
+    fn set_pan_tilt(&self, movement: PanTilt) -> Result<(), T4lError> {
+        let pan_range = self.transport.query_ctrl(V4L2_CID_PAN_ABSOLUTE)?;
+        let tilt_range = self.transport.query_ctrl(V4L2_CID_TILT_ABSOLUTE)?;
+
+        let (pan, tilt) = match movement {
+            PanTilt::Absolute { pan, tilt } => {
+                let pan = (pan * ARC_SECONDS_PER_DEGREE).round() as i32;
+                let tilt = (tilt * ARC_SECONDS_PER_DEGREE).round() as i32;
+
+                if !pan_range.contains(pan) {
+                    return Err(T4lError::UnsupportedIntValue("pan".to_string(), pan));
+                }
+                if !tilt_range.contains(tilt) {
+                    return Err(T4lError::UnsupportedIntValue("tilt".to_string(), tilt));
+                }
+
+                (pan, tilt)
+            }
+            PanTilt::Relative { pan, tilt } => {
+                let step = PAN_TILT_NUDGE_DEGREES * ARC_SECONDS_PER_DEGREE as i32;
+                let current_pan = self.transport.get_ctrl(V4L2_CID_PAN_ABSOLUTE)?;
+                let current_tilt = self.transport.get_ctrl(V4L2_CID_TILT_ABSOLUTE)?;
+
+                (
+                    current_pan
+                        .saturating_add(pan.saturating_mul(step))
+                        .clamp(pan_range.minimum, pan_range.maximum),
+                    current_tilt
+                        .saturating_add(tilt.saturating_mul(step))
+                        .clamp(tilt_range.minimum, tilt_range.maximum),
+                )
+            }
+        };
+
+        self.transport
+            .set_ctrl(V4L2_CID_PAN_ABSOLUTE, pan, self.debugging)?;
+        self.transport
+            .set_ctrl(V4L2_CID_TILT_ABSOLUTE, tilt, self.debugging)
+    }
+
     fn set_debugging(&mut self, debugging: bool) {
         self.set_debugging(debugging);
     }
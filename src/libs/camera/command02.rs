@@ -2,43 +2,107 @@
 
 use bon::builder;
 
+const FRAME_ID: [u8; 2] = [0xaa, 0x25];
+const SEGMENT_SIZE: [u8; 2] = [0x0c, 0x00];
+
+/// Builds a 36-byte frame for the extension unit selector 0x02.
+///
+/// The frame consists of a 12-byte header and the payload:
+///
+/// | Bytes    | Content                                                   |
+/// |----------|-----------------------------------------------------------|
+/// | `0..2`   | Frame id `aa 25`                                          |
+/// | `2..4`   | Sequence number (little endian)                           |
+/// | `4..6`   | Segment size, i.e. the size of the header (`0c 00`)       |
+/// | `6..8`   | Checksum of the header                                    |
+/// | `8..14`  | Function group, ending with the payload length            |
+/// | `14..16` | Checksum of the payload                                   |
+/// | `16..20` | Value of the command                                      |
+/// | `20..36` | Appendix, i.e. further values of the command              |
+///
+/// Both checksums are computed by [`checksum`], the sequence number defaults to 0
+/// and is usually replaced by the transport using [`set_sequence_nr`].
 #[builder(finish_fn = build)]
 pub fn command02(
     function_group: [u8; 6],
-    sequence_nr: [u8; 2],
-    checksum: [u8; 2],
-    command: [u8; 6],
+    #[builder(default)] sequence_nr: u16,
+    value: [u8; 4],
     appendix: Option<[u8; 16]>,
 ) -> [u8; 36] {
-    const FRAME_ID: [u8; 2] = [0xaa, 0x25];
-    const SEGMENT_SIZE: [u8; 2] = [0x0c, 0x00];
-
-    [
+    let mut frame: [u8; 36] = [
         FRAME_ID.as_slice(),
-        sequence_nr.as_slice(),
+        [0x00; 2].as_slice(),
         SEGMENT_SIZE.as_slice(),
-        checksum.as_slice(),
+        [0x00; 2].as_slice(),
         function_group.as_slice(),
-        command.as_slice(),
+        [0x00; 2].as_slice(),
+        value.as_slice(),
         appendix.unwrap_or([0x00; 16]).as_slice(),
     ]
     .concat()
     .try_into()
-    .unwrap()
+    .unwrap();
+
+    let payload_checksum = payload_checksum(&frame);
+    frame[14..16].copy_from_slice(&payload_checksum);
+    set_sequence_nr(&mut frame, sequence_nr);
+
+    frame
+}
+
+/// Replaces the sequence number of a frame and updates the header checksum accordingly.
+pub fn set_sequence_nr(frame: &mut [u8; 36], sequence_nr: u16) {
+    frame[2..4].copy_from_slice(&sequence_nr.to_le_bytes());
+
+    let header_checksum = header_checksum(frame);
+    frame[6..8].copy_from_slice(&header_checksum);
+}
+
+/// Computes the checksum used in 0x02 frames, which is a CRC-16/USB in little endian.
+pub fn checksum(data: &[u8]) -> [u8; 2] {
+    let mut crc: u16 = 0xffff;
+
+    for byte in data {
+        crc ^= *byte as u16;
+        for _ in 0..8 {
+            crc = if crc & 0x0001 != 0 {
+                (crc >> 1) ^ 0xa001
+            } else {
+                crc >> 1
+            };
+        }
+    }
+
+    (crc ^ 0xffff).to_le_bytes()
+}
+
+/// Computes the checksum of the first 12 bytes of a frame, ignoring the checksum itself.
+pub(crate) fn header_checksum(frame: &[u8]) -> [u8; 2] {
+    checksum(&[&frame[0..6], &[0x00, 0x00], &frame[8..12]].concat())
+}
+
+/// Computes the checksum of the payload of a frame, ignoring the checksum itself.
+///
+/// It covers the payload length and as many bytes of the payload as the length states.
+pub(crate) fn payload_checksum(frame: &[u8]) -> [u8; 2] {
+    let length = u16::from_le_bytes([frame[12], frame[13]]) as usize;
+    let end = (16 + length).min(frame.len());
+
+    checksum(&[&frame[12..14], &[0x00, 0x00], &frame[16..end]].concat())
 }
 
 #[cfg(test)]
 mod tests {
     mod unit {
-        use crate::libs::camera::command02::command02;
+        use crate::libs::camera::command02::{checksum, command02, set_sequence_nr};
+        use test_case::test_case;
 
         #[test]
         fn full_builder() {
             let command = command02()
-                .function_group([0x01, 0x02, 0x03, 0x04, 0x05, 0x06])
-                .sequence_nr([0x10, 0x11])
-                .checksum([0x20, 0x21])
-                .command([0x30, 0x31, 0x32, 0x33, 0x34, 0x35])
+                .function_group([0x01, 0x02, 0x03, 0x04, 0x04, 0x00])
+                .sequence_nr(0x1110)
+                .value([0x30, 0x31, 0x32, 0x33])
                 .appendix([
                     0x40, 0x41, 0x42, 0x43, 0x44, 0x45, 0x46, 0x47, 0x48, 0x49, 0x4a, 0x4b, 0x4c,
                     0x4d, 0x4e, 0x4f,
@@ -52,16 +116,27 @@ mod tests {
             );
             assert_eq!(command[2..4], [0x10, 0x11], "Sequence number should be set");
             assert_eq!(command[4..6], [0x0c, 0x00], "Segment size should be fix");
-            assert_eq!(command[6..8], [0x20, 0x21], "Checksum should be set");
+            assert_eq!(
+                command[6..8],
+                checksum(&[
+                    0xaa, 0x25, 0x10, 0x11, 0x0c, 0x00, 0x00, 0x00, 0x01, 0x02, 0x03, 0x04
+                ]),
+                "Header checksum should be computed"
+            );
             assert_eq!(
                 command[8..14],
-                [0x01, 0x02, 0x03, 0x04, 0x05, 0x06],
+                [0x01, 0x02, 0x03, 0x04, 0x04, 0x00],
                 "Function group should be set"
             );
             assert_eq!(
-                command[14..20],
-                [0x30, 0x31, 0x32, 0x33, 0x34, 0x35],
-                "Command should be set"
+                command[14..16],
+                checksum(&[0x04, 0x00, 0x00, 0x00, 0x30, 0x31, 0x32, 0x33]),
+                "Payload checksum should be computed"
+            );
+            assert_eq!(
+                command[16..20],
+                [0x30, 0x31, 0x32, 0x33],
+                "Value should be set"
             );
             assert_eq!(
                 command[20..36],
@@ -76,10 +151,8 @@ mod tests {
         #[test]
         fn minimal_builder() {
             let command = command02()
-                .function_group([0xa0, 0xa1, 0xa2, 0xa3, 0xa4, 0xa5])
-                .sequence_nr([0xb0, 0xb1])
-                .checksum([0xc0, 0xc1])
-                .command([0xd0, 0xd1, 0xd2, 0xd3, 0xd4, 0xd5])
+                .function_group([0xa0, 0xa1, 0xa2, 0xa3, 0x01, 0x00])
+                .value([0xd0, 0xd1, 0xd2, 0xd3])
                 .build();
 
             assert_eq!(
@@ -87,18 +160,26 @@ mod tests {
                 [0xaa, 0x25],
                 "Command should start with frame id"
             );
-            assert_eq!(command[2..4], [0xb0, 0xb1], "Sequence number should be set");
+            assert_eq!(
+                command[2..4],
+                [0x00, 0x00],
+                "Sequence number should default to 0"
+            );
             assert_eq!(command[4..6], [0x0c, 0x00], "Segment size should be fix");
-            assert_eq!(command[6..8], [0xc0, 0xc1], "Checksum should be set");
             assert_eq!(
                 command[8..14],
-                [0xa0, 0xa1, 0xa2, 0xa3, 0xa4, 0xa5],
+                [0xa0, 0xa1, 0xa2, 0xa3, 0x01, 0x00],
                 "Function group should be set"
             );
             assert_eq!(
-                command[14..20],
-                [0xd0, 0xd1, 0xd2, 0xd3, 0xd4, 0xd5],
-                "Command should be set"
+                command[14..16],
+                checksum(&[0x01, 0x00, 0x00, 0x00, 0xd0]),
+                "Payload checksum should only cover the payload length"
+            );
+            assert_eq!(
+                command[16..20],
+                [0xd0, 0xd1, 0xd2, 0xd3],
+                "Value should be set"
             );
             assert_eq!(
                 command[20..36],
@@ -109,5 +190,29 @@ mod tests {
                 "Appendix should be empty"
             );
         }
+
+        #[test_case(&[], [0x00, 0x00]; "empty data")]
+        #[test_case(b"123456789", [0xc8, 0xb4]; "check value of CRC-16/USB")]
+        #[test_case(&[0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], [0xbe, 0x07]; "captured payload")]
+        fn checksum_of_data(data: &[u8], expected: [u8; 2]) {
+            assert_eq!(checksum(data), expected);
+        }
+
+        #[test]
+        fn sequence_nr_updates_header_checksum() {
+            let mut command = command02()
+                .function_group([0x0a, 0x02, 0xc2, 0xa0, 0x04, 0x00])
+                .value([0x00, 0x00, 0x00, 0x00])
+                .build();
+
+            set_sequence_nr(&mut command, 0x00a5);
+
+            assert_eq!(command[2..4], [0xa5, 0x00], "Sequence number should be set");
+            assert_eq!(
+                command[6..8],
+                [0x5f, 0xef],
+                "Header checksum should match the captured one"
+            );
+        }
     }
 }
@@ -0,0 +1,212 @@
+// SPDX-License-Identifier: EUPL-1.2
+
+use crate::libs::camera::command02::{header_checksum, payload_checksum};
+use crate::libs::errors::T4lError;
+use crate::{
+    FUNCTION_GROUP_EXPOSURE_MODE_TYPE, FUNCTION_GROUP_PRESETS, FUNCTION_GROUP_SAVE_PRESET,
+    FUNCTION_GROUP_SLEEP, FUNCTION_GROUP_TRACKING_SPEED,
+};
+
+/// The function group of a 0x02 frame, i.e. the setting the frame is about.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FunctionGroup {
+    Sleep,
+    Presets,
+    SavePreset,
+    TrackingSpeed,
+    ExposureModeType,
+    Unknown([u8; 6]),
+}
+
+impl From<[u8; 6]> for FunctionGroup {
+    fn from(bytes: [u8; 6]) -> Self {
+        match bytes {
+            FUNCTION_GROUP_SLEEP => FunctionGroup::Sleep,
+            FUNCTION_GROUP_PRESETS => FunctionGroup::Presets,
+            FUNCTION_GROUP_SAVE_PRESET => FunctionGroup::SavePreset,
+            FUNCTION_GROUP_TRACKING_SPEED => FunctionGroup::TrackingSpeed,
+            FUNCTION_GROUP_EXPOSURE_MODE_TYPE => FunctionGroup::ExposureModeType,
+            _ => FunctionGroup::Unknown(bytes),
+        }
+    }
+}
+
+impl FunctionGroup {
+    pub fn bytes(&self) -> [u8; 6] {
+        match self {
+            FunctionGroup::Sleep => FUNCTION_GROUP_SLEEP,
+            FunctionGroup::Presets => FUNCTION_GROUP_PRESETS,
+            FunctionGroup::SavePreset => FUNCTION_GROUP_SAVE_PRESET,
+            FunctionGroup::TrackingSpeed => FUNCTION_GROUP_TRACKING_SPEED,
+            FunctionGroup::ExposureModeType => FUNCTION_GROUP_EXPOSURE_MODE_TYPE,
+            FunctionGroup::Unknown(bytes) => *bytes,
+        }
+    }
+}
+
+/// A decoded frame of the extension unit selector 0x02, the inverse of `command02`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Command02Frame {
+    pub frame_id: [u8; 2],
+    pub sequence_nr: u16,
+    pub segment_size: u16,
+    pub header_checksum: [u8; 2],
+    pub function_group: FunctionGroup,
+    pub payload_length: u16,
+    pub payload_checksum: [u8; 2],
+    pub value: [u8; 4],
+    pub appendix: [u8; 16],
+}
+
+impl Command02Frame {
+    const FRAME_ID: [u8; 2] = [0xaa, 0x25];
+    const MIN_SIZE: usize = 36;
+
+    /// Splits a 0x02 frame into its parts and validates both of its checksums.
+    ///
+    /// # Errors
+    /// Returns `T4lError::InvalidFrame` if the data is too short, doesn't start with the
+    /// frame id or if one of the checksums doesn't match the content.
+    pub fn parse(bytes: &[u8]) -> Result<Self, T4lError> {
+        if bytes.len() < Self::MIN_SIZE {
+            return Err(T4lError::InvalidFrame(format!(
+                "expected at least {} bytes, got {}",
+                Self::MIN_SIZE,
+                bytes.len()
+            )));
+        }
+
+        let frame = Command02Frame {
+            frame_id: [bytes[0], bytes[1]],
+            sequence_nr: u16::from_le_bytes([bytes[2], bytes[3]]),
+            segment_size: u16::from_le_bytes([bytes[4], bytes[5]]),
+            header_checksum: [bytes[6], bytes[7]],
+            function_group: FunctionGroup::from(<[u8; 6]>::try_from(&bytes[8..14]).unwrap()),
+            payload_length: u16::from_le_bytes([bytes[12], bytes[13]]),
+            payload_checksum: [bytes[14], bytes[15]],
+            value: bytes[16..20].try_into().unwrap(),
+            appendix: bytes[20..36].try_into().unwrap(),
+        };
+
+        if frame.frame_id != Self::FRAME_ID {
+            return Err(T4lError::InvalidFrame(format!(
+                "unknown frame id {}",
+                hex::encode(frame.frame_id)
+            )));
+        }
+
+        if frame.header_checksum != header_checksum(bytes) {
+            return Err(T4lError::InvalidFrame(
+                "header checksum doesn't match".to_string(),
+            ));
+        }
+
+        if frame.payload_checksum != payload_checksum(bytes) {
+            return Err(T4lError::InvalidFrame(
+                "payload checksum doesn't match".to_string(),
+            ));
+        }
+
+        Ok(frame)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod unit {
+        use crate::{
+            Command02Frame, FunctionGroup, GotoPresetPositionCommand, SleepCommand, SleepMode,
+            command02, set_sequence_nr,
+        };
+        use assertables::assert_err;
+        use test_case::test_case;
+
+        #[test]
+        fn parse_captured_frame() {
+            let mut sleep_command = SleepCommand::build(SleepMode::Sleep).unwrap();
+            set_sequence_nr(&mut sleep_command, 0x0042);
+
+            let frame = Command02Frame::parse(&sleep_command).unwrap();
+
+            assert_eq!(frame.frame_id, [0xaa, 0x25], "Frame id should be parsed");
+            assert_eq!(
+                frame.sequence_nr, 0x0042,
+                "Sequence number should be parsed"
+            );
+            assert_eq!(frame.segment_size, 12, "Segment size should be parsed");
+            assert_eq!(
+                frame.header_checksum,
+                [0xea, 0x63],
+                "Header checksum should be parsed"
+            );
+            assert_eq!(
+                frame.function_group,
+                FunctionGroup::Sleep,
+                "Function group should be named"
+            );
+            assert_eq!(frame.payload_length, 4, "Payload length should be parsed");
+            assert_eq!(
+                frame.payload_checksum,
+                [0xbf, 0xfb],
+                "Payload checksum should be parsed"
+            );
+            assert_eq!(
+                frame.value,
+                [0x01, 0x00, 0x00, 0x00],
+                "Value should be parsed"
+            );
+            assert_eq!(frame.appendix, [0x00; 16], "Appendix should be parsed");
+        }
+
+        #[test]
+        fn parse_response_buffer() {
+            let mut buffer = [0u8; 60];
+            buffer[..36].copy_from_slice(&GotoPresetPositionCommand::build(1).unwrap());
+
+            let frame = Command02Frame::parse(&buffer).unwrap();
+
+            assert_eq!(frame.function_group, FunctionGroup::Presets);
+            assert_eq!(frame.value[0], 0x01);
+        }
+
+        #[test]
+        fn parse_unknown_function_group() {
+            let command = command02()
+                .function_group([0x0a, 0x02, 0xc2, 0xff, 0x04, 0x00])
+                .value([0x00; 4])
+                .build();
+
+            let frame = Command02Frame::parse(&command).unwrap();
+
+            assert_eq!(
+                frame.function_group,
+                FunctionGroup::Unknown([0x0a, 0x02, 0xc2, 0xff, 0x04, 0x00])
+            );
+            assert_eq!(
+                frame.function_group.bytes(),
+                [0x0a, 0x02, 0xc2, 0xff, 0x04, 0x00]
+            );
+        }
+
+        #[test_case(0; "wrong frame id")]
+        #[test_case(3; "wrong header checksum")]
+        #[test_case(17; "wrong payload checksum")]
+        fn invalid_frame(corrupted_position: usize) {
+            let mut sleep_command = SleepCommand::build(SleepMode::Awake).unwrap();
+            sleep_command[corrupted_position] ^= 0xff;
+
+            assert_err!(
+                Command02Frame::parse(&sleep_command),
+                "corrupted frames should be rejected"
+            );
+        }
+
+        #[test]
+        fn too_short_frame() {
+            assert_err!(
+                Command02Frame::parse(&[0xaa, 0x25, 0x00]),
+                "short frames should be rejected"
+            );
+        }
+    }
+}
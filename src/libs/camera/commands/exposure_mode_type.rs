@@ -2,43 +2,34 @@
 
 use crate::{ExposureMode, command02};
 
+pub(crate) const FUNCTION_GROUP_EXPOSURE_MODE_TYPE: [u8; 6] = [0x0a, 0x02, 0x82, 0x29, 0x05, 0x00];
+
 pub struct ExposureModeTypeCommand;
 
 impl ExposureModeTypeCommand {
     pub fn build(mode: ExposureMode) -> [u8; 36] {
-        const FUNCTION_GROUP_EXPOSURE_MODE_TYPE: [u8; 6] = [0x0a, 0x02, 0x82, 0x29, 0x05, 0x00];
-
-        let (sequence_nr, checksum, command) = if mode == ExposureMode::Manual {
-            (
-                [0x16, 0x00],
-                [0x58, 0x91],
-                [0xb2, 0xaf, 0x02, 0x04, 0x00, 0x00],
-            )
+        let value = if mode == ExposureMode::Manual {
+            [0x02, 0x04, 0x00, 0x00]
         } else {
-            (
-                [0x15, 0x00],
-                [0xa8, 0x9e],
-                [0xf9, 0x27, 0x01, 0x32, 0x00, 0x00],
-            )
+            [0x01, 0x32, 0x00, 0x00]
         };
 
         command02()
             .function_group(FUNCTION_GROUP_EXPOSURE_MODE_TYPE)
-            .sequence_nr(sequence_nr)
-            .checksum(checksum)
-            .command(command)
+            .value(value)
             .build()
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{ExposureMode, ExposureModeTypeCommand};
+    use crate::{ExposureMode, ExposureModeTypeCommand, set_sequence_nr};
     use test_case::test_case;
 
     #[test]
     fn manual_exposure_mode() {
-        let exposure_command = ExposureModeTypeCommand::build(ExposureMode::Manual);
+        let mut exposure_command = ExposureModeTypeCommand::build(ExposureMode::Manual);
+        set_sequence_nr(&mut exposure_command, 0x0016);
 
         assert_eq!(
             exposure_command[8..14],
@@ -53,19 +44,20 @@ mod tests {
         assert_eq!(
             exposure_command[6..8],
             [0x58, 0x91],
-            "Checksum should be set"
+            "Checksum should match the captured one"
         );
         assert_eq!(
             exposure_command[14..20],
             [0xb2, 0xaf, 0x02, 0x04, 0x00, 0x00],
-            "Command should be set"
+            "Command should match the captured one"
         );
     }
 
     #[test_case(ExposureMode::Global; "global automatic exposure should be set")]
     #[test_case(ExposureMode::Face; "face automatic exposure should be set")]
     fn automatic_exposure_modes(mode: ExposureMode) {
-        let exposure_command = ExposureModeTypeCommand::build(mode);
+        let mut exposure_command = ExposureModeTypeCommand::build(mode);
+        set_sequence_nr(&mut exposure_command, 0x0015);
 
         assert_eq!(
             exposure_command[8..14],
@@ -80,12 +72,12 @@ mod tests {
         assert_eq!(
             exposure_command[6..8],
             [0xa8, 0x9e],
-            "Checksum should be set"
+            "Checksum should match the captured one"
         );
         assert_eq!(
             exposure_command[14..20],
             [0xf9, 0x27, 0x01, 0x32, 0x00, 0x00],
-            "Command should be set"
+            "Command should match the captured one"
         );
     }
 }
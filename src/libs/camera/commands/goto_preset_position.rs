@@ -3,43 +3,23 @@
 use crate::command02;
 use crate::libs::errors::T4lError;
 
+pub(crate) const FUNCTION_GROUP_PRESETS: [u8; 6] = [0x0a, 0x04, 0xc4, 0x39, 0x14, 0x00];
+
 pub struct GotoPresetPositionCommand;
 
 impl GotoPresetPositionCommand {
     pub fn build(preset_nr: i8) -> Result<[u8; 36], T4lError> {
-        const FUNCTION_GROUP_PRESETS: [u8; 6] = [0x0a, 0x04, 0xc4, 0x39, 0x14, 0x00];
-
-        let (sequence_nr, checksum, command) = match preset_nr {
-            0 => (
-                [0x20, 0x00],
-                [0x6b, 0xdc],
-                [0xd6, 0xfb, 0x00, 0x00, 0x00, 0x00],
-            ),
-            1 => (
-                [0x1a, 0x00],
-                [0x4b, 0x03],
-                [0xeb, 0x2a, 0x01, 0x00, 0x00, 0x00],
-            ),
-            2 => (
-                [0x26, 0x00],
-                [0x8b, 0xc3],
-                [0xaf, 0x19, 0x02, 0x00, 0x00, 0x00],
-            ),
-            _ => {
-                println!("Invalid preset nr {}", preset_nr + 1);
-                return Err(T4lError::InvalidSetting);
-            }
-        };
+        if preset_nr < 0 {
+            return Err(T4lError::InvalidSetting);
+        }
 
         Ok(command02()
             .function_group(FUNCTION_GROUP_PRESETS)
-            .sequence_nr(sequence_nr)
-            .checksum(checksum)
-            .command(command)
+            .value([preset_nr as u8, 0x00, 0x00, 0x00])
             .appendix({
                 let mut arr = [0u8; 16];
                 for i in 0..4 {
-                    arr[i * 4..(i + 1) * 4].copy_from_slice(&[0x00, 0x00, 0x80, 0x3f]);
+                    arr[i * 4..(i + 1) * 4].copy_from_slice(&1.0f32.to_le_bytes());
                 }
                 arr
             })
@@ -49,7 +29,7 @@ impl GotoPresetPositionCommand {
 
 #[cfg(test)]
 mod tests {
-    use crate::GotoPresetPositionCommand;
+    use crate::{GotoPresetPositionCommand, checksum, set_sequence_nr};
     use assertables::{assert_err, assert_ok};
     use test_case::test_case;
 
@@ -69,7 +49,9 @@ mod tests {
             "preset position command should be built"
         );
 
-        let position_command = position_command_option.unwrap();
+        let mut position_command = position_command_option.unwrap();
+        set_sequence_nr(&mut position_command, u16::from_le_bytes(sequence_nr));
+
         assert_eq!(
             position_command[8..14],
             [0x0a, 0x04, 0xc4, 0x39, 0x14, 0x00],
@@ -80,14 +62,36 @@ mod tests {
             sequence_nr,
             "Sequence number should be set"
         );
-        assert_eq!(position_command[6..8], checksum, "Checksum should be set");
-        assert_eq!(position_command[14..20], command, "Command should be set");
+        assert_eq!(
+            position_command[6..8],
+            checksum,
+            "Checksum should match the captured one"
+        );
+        assert_eq!(
+            position_command[14..20],
+            command,
+            "Command should match the captured one"
+        );
+    }
+
+    #[test_case(3; "preset position 4 (id 3)")]
+    #[test_case(i8::MAX; "preset position with maximal number")]
+    fn uncaptured_preset_position(position: i8) {
+        let position_command = GotoPresetPositionCommand::build(position).unwrap();
+
+        assert_eq!(
+            position_command[16], position as u8,
+            "Preset position should be set"
+        );
+        assert_eq!(
+            position_command[14..16],
+            checksum(&[&[0x14, 0x00, 0x00, 0x00], &position_command[16..36]].concat()),
+            "Checksum should cover the whole payload"
+        );
     }
 
     #[test_case(-1; "invalid preset position (lower than 0)")]
-    #[test_case(3; "invalid preset position (greater than 2)")]
     #[test_case(i8::MIN; "invalid preset position (minimal number)")]
-    #[test_case(i8::MAX - 1; "invalid preset position (maximal number - 1)")]
     fn invalid_preset_position(invalid_position: i8) {
         let position_command_option = GotoPresetPositionCommand::build(invalid_position);
 
@@ -5,6 +5,7 @@ mod exposure_mode;
 mod exposure_mode_type;
 mod goto_preset_position;
 mod hdr_mode;
+mod save_preset_position;
 mod sleep;
 mod tracking_speed;
 
@@ -13,5 +14,8 @@ pub use exposure_mode::*;
 pub use exposure_mode_type::*;
 pub use goto_preset_position::*;
 pub use hdr_mode::*;
+pub(crate) use save_preset_position::FUNCTION_GROUP_SAVE_PRESET;
+#[cfg(feature = "experimental")]
+pub use save_preset_position::SavePresetPositionCommand;
 pub use sleep::*;
 pub use tracking_speed::*;
@@ -0,0 +1,85 @@
+// SPDX-License-Identifier: EUPL-1.2
+
+#[cfg(feature = "experimental")]
+use crate::command02;
+#[cfg(feature = "experimental")]
+use crate::libs::errors::T4lError;
+
+/// Function group to store the current gimbal position into a preset.
+///
+/// It hasn't been captured from the OBSBOT app yet. It is derived from [`FUNCTION_GROUP_PRESETS`],
+/// whose fourth byte selects the preset action, so it's only sent with the `experimental` feature.
+///
+/// [`FUNCTION_GROUP_PRESETS`]: crate::libs::camera::FUNCTION_GROUP_PRESETS
+pub(crate) const FUNCTION_GROUP_SAVE_PRESET: [u8; 6] = [0x0a, 0x04, 0xc4, 0x38, 0x14, 0x00];
+
+#[cfg(feature = "experimental")]
+pub struct SavePresetPositionCommand;
+
+#[cfg(feature = "experimental")]
+impl SavePresetPositionCommand {
+    pub fn build(preset_nr: i8) -> Result<[u8; 36], T4lError> {
+        if preset_nr < 0 {
+            return Err(T4lError::InvalidSetting);
+        }
+
+        Ok(command02()
+            .function_group(FUNCTION_GROUP_SAVE_PRESET)
+            .value([preset_nr as u8, 0x00, 0x00, 0x00])
+            .appendix({
+                let mut arr = [0u8; 16];
+                for i in 0..4 {
+                    arr[i * 4..(i + 1) * 4].copy_from_slice(&1.0f32.to_le_bytes());
+                }
+                arr
+            })
+            .build())
+    }
+}
+
+#[cfg(all(test, feature = "experimental"))]
+mod tests {
+    use crate::{GotoPresetPositionCommand, SavePresetPositionCommand, checksum};
+    use assertables::assert_err;
+    use test_case::test_case;
+
+    #[test_case(0; "preset position 1 (id 0)")]
+    #[test_case(2; "preset position 3 (id 2)")]
+    #[test_case(i8::MAX; "preset position with maximal number")]
+    fn valid_preset_position(position: i8) {
+        let save_command = SavePresetPositionCommand::build(position).unwrap();
+
+        assert_eq!(
+            save_command[8..14],
+            [0x0a, 0x04, 0xc4, 0x38, 0x14, 0x00],
+            "Function group should be set for saving a preset position"
+        );
+        assert_eq!(
+            save_command[16], position as u8,
+            "Preset position should be set"
+        );
+        assert_eq!(
+            save_command[14..16],
+            checksum(&[&[0x14, 0x00, 0x00, 0x00], &save_command[16..36]].concat()),
+            "Checksum should cover the whole payload"
+        );
+    }
+
+    #[test]
+    fn differs_from_goto_preset_position() {
+        assert_ne!(
+            SavePresetPositionCommand::build(1).unwrap()[8..14],
+            GotoPresetPositionCommand::build(1).unwrap()[8..14],
+            "Saving a preset shouldn't move to it"
+        );
+    }
+
+    #[test_case(-1; "invalid preset position (lower than 0)")]
+    #[test_case(i8::MIN; "invalid preset position (minimal number)")]
+    fn invalid_preset_position(invalid_position: i8) {
+        assert_err!(
+            SavePresetPositionCommand::build(invalid_position),
+            "invalid position should return an error"
+        );
+    }
+}
@@ -3,42 +3,28 @@
 use crate::libs::errors::T4lError;
 use crate::{SleepMode, command02};
 
+pub(crate) const FUNCTION_GROUP_SLEEP: [u8; 6] = [0x0a, 0x02, 0xc2, 0xa0, 0x04, 0x00];
+
 pub struct SleepCommand;
 
 impl SleepCommand {
     pub fn build(mode: SleepMode) -> Result<[u8; 36], T4lError> {
-        if mode == SleepMode::Unknown {
-            return Err(T4lError::InvalidSetting);
-        }
-
-        const FUNCTION_GROUP_SLEEP: [u8; 6] = [0x0a, 0x02, 0xc2, 0xa0, 0x04, 0x00];
-
-        let (sequence_nr, checksum, command) = match mode {
-            SleepMode::Awake => (
-                [0xa5, 0x00],
-                [0x5f, 0xef],
-                [0xbe, 0x07, 0x00, 0x00, 0x00, 0x00],
-            ),
-            SleepMode::Sleep => (
-                [0x42, 0x00],
-                [0xea, 0x63],
-                [0xbf, 0xfb, 0x01, 0x00, 0x00, 0x00],
-            ),
-            SleepMode::Unknown => panic!(),
+        let value = match mode {
+            SleepMode::Awake => [0x00, 0x00, 0x00, 0x00],
+            SleepMode::Sleep => [0x01, 0x00, 0x00, 0x00],
+            SleepMode::Unknown => return Err(T4lError::InvalidSetting),
         };
 
         Ok(command02()
             .function_group(FUNCTION_GROUP_SLEEP)
-            .sequence_nr(sequence_nr)
-            .checksum(checksum)
-            .command(command)
+            .value(value)
             .build())
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{SleepCommand, SleepMode};
+    use crate::{SleepCommand, SleepMode, set_sequence_nr};
     use assertables::assert_ok;
     use test_case::test_case;
 
@@ -49,7 +35,9 @@ mod tests {
 
         assert_ok!(&sleep_command_option, "sleep mode command should be built");
 
-        let sleep_command = sleep_command_option.unwrap();
+        let mut sleep_command = sleep_command_option.unwrap();
+        set_sequence_nr(&mut sleep_command, u16::from_le_bytes(sequence_nr));
+
         assert_eq!(
             sleep_command[8..14],
             [0x0a, 0x02, 0xc2, 0xa0, 0x04, 0x00],
@@ -60,8 +48,16 @@ mod tests {
             sequence_nr,
             "Sequence number should be set"
         );
-        assert_eq!(sleep_command[6..8], checksum, "Checksum should be set");
-        assert_eq!(sleep_command[14..20], command, "Command should be set");
+        assert_eq!(
+            sleep_command[6..8],
+            checksum,
+            "Checksum should match the captured one"
+        );
+        assert_eq!(
+            sleep_command[14..20],
+            command,
+            "Command should match the captured one"
+        );
     }
 
     #[test]
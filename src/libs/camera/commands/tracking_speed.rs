@@ -3,44 +3,27 @@
 use crate::libs::errors::T4lError;
 use crate::{TrackingSpeed, command02};
 
+pub(crate) const FUNCTION_GROUP_TRACKING_SPEED: [u8; 6] = [0x0a, 0x04, 0xc4, 0x0c, 0x01, 0x00];
+
 pub struct TrackingSpeedCommand;
 
 impl TrackingSpeedCommand {
     pub fn build(speed: TrackingSpeed) -> Result<[u8; 36], T4lError> {
-        const FUNCTION_GROUP_TRACKING_SPEED: [u8; 6] = [0x0a, 0x04, 0xc4, 0x0c, 0x01, 0x00];
-
-        let appendix: [u8; 16] = {
-            let mut a = [0x00; 16];
-            a[..4].fill(0x00);
-            a
-        };
-
-        let (sequence_nr, checksum, command) = match speed {
-            TrackingSpeed::Standard => (
-                [0x20, 0x00],
-                [0xab, 0xcb],
-                [0xe6, 0x3f, 0x00, 0x00, 0x00, 0x00],
-            ),
-            TrackingSpeed::Sport => (
-                [0x21, 0x00],
-                [0xfa, 0x0e],
-                [0x67, 0xfe, 0x02, 0x00, 0x00, 0x00],
-            ),
+        let value = match speed {
+            TrackingSpeed::Standard => [0x00, 0x00, 0x00, 0x00],
+            TrackingSpeed::Sport => [0x02, 0x00, 0x00, 0x00],
         };
 
         Ok(command02()
             .function_group(FUNCTION_GROUP_TRACKING_SPEED)
-            .sequence_nr(sequence_nr)
-            .checksum(checksum)
-            .command(command)
-            .appendix(appendix)
+            .value(value)
             .build())
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{TrackingSpeed, TrackingSpeedCommand};
+    use crate::{TrackingSpeed, TrackingSpeedCommand, set_sequence_nr};
     use assertables::assert_ok;
     use test_case::test_case;
 
@@ -54,7 +37,9 @@ mod tests {
             "tracking speed command should be built"
         );
 
-        let speed_command = speed_command_option.unwrap();
+        let mut speed_command = speed_command_option.unwrap();
+        set_sequence_nr(&mut speed_command, u16::from_le_bytes(sequence_nr));
+
         assert_eq!(
             speed_command[8..14],
             [0x0a, 0x04, 0xc4, 0x0c, 0x01, 0x00],
@@ -65,7 +50,15 @@ mod tests {
             sequence_nr,
             "Sequence number should be set"
         );
-        assert_eq!(speed_command[6..8], checksum, "Checksum should be set");
-        assert_eq!(speed_command[14..20], command, "Command should be set");
+        assert_eq!(
+            speed_command[6..8],
+            checksum,
+            "Checksum should match the captured one"
+        );
+        assert_eq!(
+            speed_command[14..20],
+            command,
+            "Command should match the captured one"
+        );
     }
 }
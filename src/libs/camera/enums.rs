@@ -1,9 +1,12 @@
 // SPDX-License-Identifier: EUPL-1.2
 
 use rust_i18n::t;
+use serde::{Deserialize, Serialize};
 use std::fmt::Display;
+use std::str::FromStr;
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum SleepMode {
     Awake,
     Sleep,
@@ -20,7 +23,8 @@ impl Display for SleepMode {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum AIMode {
     NoTracking,
     NormalTracking,
@@ -35,6 +39,87 @@ pub enum AIMode {
     Unknown,
 }
 
+impl AIMode {
+    /// The tracking modes in the order of the dashboards, which is also the order they're cycled
+    /// through in.
+    pub const ALL: [AIMode; 10] = [
+        AIMode::NoTracking,
+        AIMode::NormalTracking,
+        AIMode::CloseUp,
+        AIMode::UpperBody,
+        AIMode::Headless,
+        AIMode::LowerBody,
+        AIMode::DeskMode,
+        AIMode::Whiteboard,
+        AIMode::Hand,
+        AIMode::Group,
+    ];
+
+    /// The tracking mode after this one in [`AIMode::ALL`], starting over at the end.
+    /// An unknown mode is followed by the first one.
+    pub fn next(self) -> Self {
+        self.step(1)
+    }
+
+    /// The tracking mode before this one in [`AIMode::ALL`], continuing at the end.
+    /// An unknown mode is followed by the first one.
+    pub fn previous(self) -> Self {
+        self.step(Self::ALL.len() - 1)
+    }
+
+    /// The name of the tracking mode in the CLI and in scripts, e.g. `close-up`.
+    pub fn name(self) -> &'static str {
+        match self {
+            AIMode::NoTracking => "static",
+            AIMode::NormalTracking => "normal",
+            AIMode::UpperBody => "upper-body",
+            AIMode::CloseUp => "close-up",
+            AIMode::Headless => "headless",
+            AIMode::LowerBody => "lower-body",
+            AIMode::DeskMode => "desk",
+            AIMode::Whiteboard => "whiteboard",
+            AIMode::Hand => "hand",
+            AIMode::Group => "group",
+            AIMode::Unknown => "unknown",
+        }
+    }
+
+    /// Further names of the tracking mode in the CLI and in scripts, e.g. `close` for `close-up`.
+    pub fn aliases(self) -> &'static [&'static str] {
+        match self {
+            AIMode::NoTracking => &["none", "off"],
+            AIMode::NormalTracking => &["standard", "on"],
+            AIMode::CloseUp => &["close"],
+            AIMode::Hand => &["point"],
+            _ => &[],
+        }
+    }
+
+    fn step(self, steps: usize) -> Self {
+        Self::ALL
+            .iter()
+            .position(|mode| *mode == self)
+            .map(|index| Self::ALL[(index + steps) % Self::ALL.len()])
+            .unwrap_or(Self::ALL[0])
+    }
+}
+
+impl FromStr for AIMode {
+    /// The reason the name is invalid.
+    type Err = String;
+
+    /// Parses the [`AIMode::name`] or one of the [`AIMode::aliases`], in any case and with `_`
+    /// in place of `-`, e.g. `close`, `Close-Up` or `upper_body`.
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        let name = name.to_lowercase().replace('_', "-");
+
+        Self::ALL
+            .into_iter()
+            .find(|mode| mode.name() == name || mode.aliases().contains(&name.as_str()))
+            .ok_or_else(|| format!("unknown tracking mode `{}`", name))
+    }
+}
+
 impl Display for AIMode {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -53,7 +138,8 @@ impl Display for AIMode {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum TrackingSpeed {
     Standard,
     Sport,
@@ -68,7 +154,8 @@ impl Display for TrackingSpeed {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ExposureMode {
     Manual,
     Global,
@@ -85,12 +172,23 @@ impl Display for ExposureMode {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ExposureModeType {
     Auto,
     Manual,
 }
 
+/// A movement of the gimbal.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PanTilt {
+    /// Moves to a position in degrees, where 0/0 is the center and positive values are right and up.
+    Absolute { pan: f64, tilt: f64 },
+    /// Moves by a number of nudge steps relative to the current position.
+    Relative { pan: i32, tilt: i32 },
+}
+
 #[cfg(test)]
 mod tests {
     mod unit {
@@ -150,5 +248,101 @@ mod tests {
                 }
             }
         }
+
+        mod ai_mode {
+            use crate::AIMode;
+            use test_case::test_case;
+
+            #[test_case(AIMode::NoTracking, AIMode::NormalTracking; "first")]
+            #[test_case(AIMode::UpperBody, AIMode::Headless; "middle")]
+            #[test_case(AIMode::Group, AIMode::NoTracking; "starts over")]
+            #[test_case(AIMode::Unknown, AIMode::NoTracking; "unknown")]
+            fn next(mode: AIMode, expected: AIMode) {
+                assert_eq!(mode.next(), expected);
+            }
+
+            #[test_case(AIMode::NormalTracking, AIMode::NoTracking; "second")]
+            #[test_case(AIMode::NoTracking, AIMode::Group; "continues at end")]
+            #[test_case(AIMode::Unknown, AIMode::NoTracking; "unknown")]
+            fn previous(mode: AIMode, expected: AIMode) {
+                assert_eq!(mode.previous(), expected);
+            }
+
+            #[test_case("close-up", AIMode::CloseUp; "name")]
+            #[test_case("close", AIMode::CloseUp; "alias")]
+            #[test_case("Upper_Body", AIMode::UpperBody; "any case and underscore")]
+            #[test_case("off", AIMode::NoTracking; "off")]
+            fn parses_name(name: &str, expected: AIMode) {
+                assert_eq!(name.parse::<AIMode>(), Ok(expected));
+            }
+
+            #[test_case("unknown"; "unknown")]
+            #[test_case("fast"; "other word")]
+            fn rejects_name(name: &str) {
+                assert!(name.parse::<AIMode>().is_err());
+            }
+
+            #[test]
+            fn parses_own_names() {
+                for mode in AIMode::ALL {
+                    assert_eq!(mode.name().parse::<AIMode>(), Ok(mode));
+                }
+            }
+
+            #[test]
+            fn all_without_unknown() {
+                assert!(!AIMode::ALL.contains(&AIMode::Unknown));
+            }
+        }
+
+        mod serialization {
+            use crate::{AIMode, ExposureMode, PanTilt, SleepMode, TrackingSpeed};
+            use test_case::test_case;
+
+            #[test_case(SleepMode::Awake, "awake"; "awake")]
+            #[test_case(SleepMode::Sleep, "sleep"; "sleep")]
+            #[test_case(SleepMode::Unknown, "unknown"; "unknown")]
+            fn sleep_mode(mode: SleepMode, expected: &str) {
+                assert_eq!(serde_json::to_value(mode).unwrap(), expected);
+                assert_eq!(
+                    serde_json::from_value::<SleepMode>(expected.into()).unwrap(),
+                    mode
+                );
+            }
+
+            #[test_case(AIMode::NoTracking, "no_tracking"; "no tracking")]
+            #[test_case(AIMode::NormalTracking, "normal_tracking"; "normal tracking")]
+            #[test_case(AIMode::UpperBody, "upper_body"; "upper body")]
+            #[test_case(AIMode::DeskMode, "desk_mode"; "desk mode")]
+            #[test_case(AIMode::Hand, "hand"; "hand")]
+            fn ai_mode(mode: AIMode, expected: &str) {
+                assert_eq!(serde_json::to_value(mode).unwrap(), expected);
+                assert_eq!(
+                    serde_json::from_value::<AIMode>(expected.into()).unwrap(),
+                    mode
+                );
+            }
+
+            #[test_case(TrackingSpeed::Standard, "standard"; "standard")]
+            #[test_case(TrackingSpeed::Sport, "sport"; "sport")]
+            fn tracking_speed(speed: TrackingSpeed, expected: &str) {
+                assert_eq!(serde_json::to_value(speed).unwrap(), expected);
+            }
+
+            #[test_case(ExposureMode::Manual, "manual"; "manual")]
+            #[test_case(ExposureMode::Global, "global"; "global")]
+            #[test_case(ExposureMode::Face, "face"; "face")]
+            fn exposure_mode(mode: ExposureMode, expected: &str) {
+                assert_eq!(serde_json::to_value(mode).unwrap(), expected);
+            }
+
+            #[test]
+            fn pan_tilt() {
+                assert_eq!(
+                    serde_json::to_string(&PanTilt::Relative { pan: 1, tilt: -1 }).unwrap(),
+                    r#"{"relative":{"pan":1,"tilt":-1}}"#
+                );
+            }
+        }
     }
 }
@@ -1,15 +1,29 @@
 // SPDX-License-Identifier: EUPL-1.2
 
+mod auto_sleep;
 mod camera;
 mod command02;
+mod command02_frame;
 mod commands;
 mod enums;
+mod raw_watcher;
+mod setting_change;
+mod state;
 mod status;
+mod status_watcher;
 mod transport;
 
-pub use camera::Camera;
+pub use auto_sleep::AutoSleep;
 pub use camera::Tiny2Camera;
-pub use command02::command02;
+pub use camera::{CAMERA_HINT, Camera};
+pub use command02::{checksum, command02, set_sequence_nr};
+pub use command02_frame::{Command02Frame, FunctionGroup};
 pub use commands::*;
 pub use enums::*;
+pub use raw_watcher::{
+    ByteChange, RawSample, RawWatcher, WATCHED_SELECTORS, known_offset, parse_hex,
+};
+pub use setting_change::{SettingChange, SettingError};
+pub use state::CameraState;
 pub use status::CameraStatus;
+pub use status_watcher::{StatusChange, StatusWatcher};
@@ -0,0 +1,238 @@
+// SPDX-License-Identifier: EUPL-1.2
+
+use crate::libs::camera::camera::Camera;
+use crate::libs::camera::status::STATUS_OFFSETS;
+use crate::libs::errors::T4lError;
+use serde::Serialize;
+use std::borrow::Cow;
+use std::ops::Range;
+use std::thread;
+use std::time::Duration;
+
+/// The unit of the camera the watched selectors belong to.
+const EXTENSION_UNIT: u8 = 0x2;
+
+/// The selectors of the extension unit a [`RawWatcher`] reads, the status and the last 0x02 frame.
+pub const WATCHED_SELECTORS: [u8; 2] = [0x06, 0x02];
+
+/// The parts of a 0x02 frame, as split by [`Command02Frame`](crate::Command02Frame).
+const FRAME02_OFFSETS: [(Range<usize>, &str); 8] = [
+    (0x00..0x02, "frame id"),
+    (0x02..0x04, "sequence number"),
+    (0x04..0x06, "segment size"),
+    (0x06..0x08, "header checksum"),
+    (0x08..0x0e, "function group"),
+    (0x0e..0x10, "payload checksum"),
+    (0x10..0x14, "value"),
+    (0x14..0x24, "appendix"),
+];
+
+/// What is known about the byte at the offset of the selector, e.g. `hdr` for 0x06 at 0x06.
+///
+/// Offsets of the status that aren't confirmed by captures yet are marked as such.
+pub fn known_offset(selector: u8, offset: usize) -> Option<Cow<'static, str>> {
+    match selector {
+        0x06 => STATUS_OFFSETS
+            .iter()
+            .find(|known| known.range.contains(&offset))
+            .map(|known| match known.confirmed {
+                true => Cow::Borrowed(known.name),
+                false => Cow::Owned(format!("{} (unconfirmed)", known.name)),
+            }),
+        0x02 => FRAME02_OFFSETS
+            .iter()
+            .find(|(range, _)| range.contains(&offset))
+            .map(|(_, name)| Cow::Borrowed(*name)),
+        _ => None,
+    }
+}
+
+/// Parses a number written in hex, with or without `0x`, e.g. a selector or an offset.
+pub fn parse_hex<T: TryFrom<u64>>(text: &str) -> Option<T> {
+    let digits = text.strip_prefix("0x").unwrap_or(text);
+
+    u64::from_str_radix(digits, 16)
+        .ok()
+        .and_then(|number| T::try_from(number).ok())
+}
+
+/// A byte that differs between two readings of a selector.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ByteChange {
+    pub offset: usize,
+    pub old: u8,
+    pub new: u8,
+}
+
+/// A reading of the raw data of a selector, with the bytes that changed since the previous one.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RawSample {
+    pub selector: u8,
+    #[serde(with = "hex::serde")]
+    pub bytes: Vec<u8>,
+    pub changes: Vec<ByteChange>,
+}
+
+impl RawSample {
+    /// Compares the reading to the previous one, if any, where bytes missing in either count as changed.
+    pub fn compare(selector: u8, bytes: Vec<u8>, previous: Option<&[u8]>) -> Self {
+        let changes = match previous {
+            Some(previous) => (0..bytes.len().max(previous.len()))
+                .filter_map(|offset| {
+                    let old = previous.get(offset).copied();
+                    let new = bytes.get(offset).copied();
+                    (old != new).then(|| ByteChange {
+                        offset,
+                        old: old.unwrap_or_default(),
+                        new: new.unwrap_or_default(),
+                    })
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+
+        Self {
+            selector,
+            bytes,
+            changes,
+        }
+    }
+
+    pub fn is_changed(&self, offset: usize) -> bool {
+        self.changes.iter().any(|change| change.offset == offset)
+    }
+}
+
+/// Polls the raw data of the [`WATCHED_SELECTORS`] and reports the bytes that changed,
+/// e.g. to find the offset of a setting by changing it.
+///
+/// The camera is passed to each poll, so that an app can share its own camera with the watcher.
+pub struct RawWatcher {
+    interval: Duration,
+    last_bytes: [Option<Vec<u8>>; WATCHED_SELECTORS.len()],
+}
+
+impl RawWatcher {
+    /// Watches a camera, reading the selectors once per interval while waiting.
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            last_bytes: Default::default(),
+        }
+    }
+
+    /// Reads the selectors once and compares them with the last reading.
+    ///
+    /// The first reading only serves as the base for later ones, so it reports no changes.
+    pub fn poll(&mut self, camera: &Camera) -> Result<Vec<RawSample>, T4lError> {
+        let mut samples = Vec::new();
+
+        for (selector, last_bytes) in WATCHED_SELECTORS.iter().zip(&mut self.last_bytes) {
+            let bytes = camera.get_raw(EXTENSION_UNIT, *selector)?;
+            let sample = RawSample::compare(*selector, bytes, last_bytes.as_deref());
+            *last_bytes = Some(sample.bytes.clone());
+            samples.push(sample);
+        }
+
+        Ok(samples)
+    }
+
+    /// Blocks until a reading differs from the previous one and returns the readings.
+    ///
+    /// The first call returns the first reading right away.
+    pub fn wait(&mut self, camera: &Camera) -> Result<Vec<RawSample>, T4lError> {
+        let first = self.last_bytes.iter().all(Option::is_none);
+
+        loop {
+            let samples = self.poll(camera)?;
+            if first || samples.iter().any(|sample| !sample.changes.is_empty()) {
+                return Ok(samples);
+            }
+
+            thread::sleep(self.interval);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod unit {
+        use crate::{ByteChange, RawSample, known_offset, parse_hex};
+        use test_case::test_case;
+
+        #[test]
+        fn first_reading_has_no_changes() {
+            let sample = RawSample::compare(0x06, vec![1, 2, 3], None);
+
+            assert!(sample.changes.is_empty());
+        }
+
+        #[test]
+        fn lists_changed_bytes() {
+            let sample = RawSample::compare(0x06, vec![1, 5, 3, 7], Some(&[1, 2, 3]));
+
+            assert_eq!(
+                sample.changes,
+                [
+                    ByteChange {
+                        offset: 1,
+                        old: 2,
+                        new: 5
+                    },
+                    ByteChange {
+                        offset: 3,
+                        old: 0,
+                        new: 7
+                    },
+                ]
+            );
+            assert!(sample.is_changed(1));
+            assert!(!sample.is_changed(2));
+        }
+
+        #[test_case(0x06, 0x06, Some("hdr"); "status hdr")]
+        #[test_case(0x06, 0x03, Some("gesture control (unconfirmed)"); "unconfirmed status offset")]
+        #[test_case(0x06, 0x05, None; "unknown status offset")]
+        #[test_case(0x02, 0x03, Some("sequence number"); "inside frame part")]
+        #[test_case(0x02, 0x24, None; "after frame")]
+        #[test_case(0x04, 0x00, None; "unknown selector")]
+        fn annotates_known_offsets(selector: u8, offset: usize, expected: Option<&str>) {
+            assert_eq!(known_offset(selector, offset).as_deref(), expected);
+        }
+
+        #[test_case("0x06", Some(0x06); "with prefix")]
+        #[test_case("2a", Some(0x2a); "without prefix")]
+        #[test_case("0x0x06", None; "prefix twice")]
+        #[test_case("0x100", None; "out of range")]
+        #[test_case("zoom", None; "not hex")]
+        fn parses_hex_byte(text: &str, expected: Option<u8>) {
+            assert_eq!(parse_hex::<u8>(text), expected);
+        }
+
+        #[test]
+        fn parses_hex_offset() {
+            assert_eq!(parse_hex::<usize>("0x1f4"), Some(0x1f4));
+        }
+    }
+
+    mod integration {
+        use crate::{Camera, RawWatcher, SleepMode, Tiny2Camera};
+        use std::time::Duration;
+
+        #[test]
+        fn reports_changed_status_byte() {
+            let camera = Camera::simulated();
+            let mut watcher = RawWatcher::new(Duration::ZERO);
+            let first = watcher.poll(&camera).unwrap();
+            assert_eq!(first.len(), 2);
+            assert!(first.iter().all(|sample| sample.changes.is_empty()));
+
+            camera.set_sleep_mode(SleepMode::Sleep).unwrap();
+            let samples = watcher.poll(&camera).unwrap();
+
+            let status = &samples[0];
+            assert_eq!(status.selector, 0x06);
+            assert!(status.is_changed(0x02), "sleep mode is at 0x02");
+        }
+    }
+}
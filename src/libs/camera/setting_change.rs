@@ -0,0 +1,153 @@
+// SPDX-License-Identifier: EUPL-1.2
+
+use crate::libs::errors::T4lError;
+use serde::{Deserialize, Serialize};
+
+/// The outcome of changing a setting of the camera, e.g. for machine-readable output.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SettingChange<T> {
+    /// Stable, non-localized identifier of the setting, e.g. `ai_mode`.
+    pub setting: String,
+    /// The value before the change, if it could be read.
+    pub old: Option<T>,
+    /// The value after the change, if it succeeded.
+    pub new: Option<T>,
+    pub error: Option<SettingError>,
+}
+
+/// An error that occurred while changing a setting.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SettingError {
+    /// Stable, non-localized identifier of the error, see [`T4lError::code`].
+    pub code: String,
+    pub message: String,
+    /// Exit code of a process that ends because of this error, see [`T4lError::exit_code`].
+    pub exit_code: i32,
+}
+
+impl From<&T4lError> for SettingError {
+    fn from(error: &T4lError) -> Self {
+        SettingError {
+            code: error.code().to_string(),
+            message: error.to_string(),
+            exit_code: error.exit_code(),
+        }
+    }
+}
+
+impl<T: Clone> SettingChange<T> {
+    /// Reads the current value of a setting, sets the new one and records the outcome.
+    ///
+    /// A failure to read the old value doesn't prevent the change, `old` is `None` then.
+    pub fn apply(
+        setting: &str,
+        new: T,
+        get: impl FnOnce() -> Result<T, T4lError>,
+        set: impl FnOnce(T) -> Result<(), T4lError>,
+    ) -> Self {
+        let old = get().ok();
+
+        Self::from_result(setting, old, set(new.clone()).map(|_| new))
+    }
+
+    /// Records the outcome of a change whose new value is only known after it was applied.
+    pub fn from_result(setting: &str, old: Option<T>, result: Result<T, T4lError>) -> Self {
+        match result {
+            Ok(new) => SettingChange {
+                setting: setting.to_string(),
+                old,
+                new: Some(new),
+                error: None,
+            },
+            Err(error) => SettingChange {
+                setting: setting.to_string(),
+                old,
+                new: None,
+                error: Some(SettingError::from(&error)),
+            },
+        }
+    }
+}
+
+impl<T> SettingChange<T> {
+    pub fn is_ok(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod unit {
+        use crate::libs::errors::T4lError;
+        use crate::{SettingChange, SleepMode};
+        use assertables::{assert_none, assert_some};
+
+        #[test]
+        fn successful_change() {
+            let change = SettingChange::apply(
+                "sleep_mode",
+                SleepMode::Sleep,
+                || Ok(SleepMode::Awake),
+                |_| Ok(()),
+            );
+
+            assert_eq!(change.setting, "sleep_mode");
+            assert_eq!(
+                change.old,
+                Some(SleepMode::Awake),
+                "old value should be read"
+            );
+            assert_eq!(
+                change.new,
+                Some(SleepMode::Sleep),
+                "new value should be set"
+            );
+            assert_none!(change.error, "no error should be recorded");
+            assert!(change.is_ok());
+        }
+
+        #[test]
+        fn unreadable_old_value() {
+            let change = SettingChange::apply(
+                "sleep_mode",
+                SleepMode::Sleep,
+                || Err(T4lError::USBIOError(5)),
+                |_| Ok(()),
+            );
+
+            assert_none!(change.old, "old value should be unknown");
+            assert_eq!(
+                change.new,
+                Some(SleepMode::Sleep),
+                "change should be applied"
+            );
+        }
+
+        #[test]
+        fn failed_change() {
+            let change = SettingChange::apply(
+                "zoom",
+                500,
+                || Ok(0),
+                |zoom| Err(T4lError::UnsupportedIntValue("zoom".to_string(), zoom)),
+            );
+
+            assert_eq!(change.old, Some(0), "old value should be read");
+            assert_none!(change.new, "new value shouldn't be set");
+            assert_some!(&change.error, "error should be recorded");
+            let error = change.error.unwrap();
+            assert_eq!(error.code, "unsupported_value");
+            assert_eq!(error.exit_code, 7);
+        }
+
+        #[test]
+        fn serialize() {
+            let change = SettingChange::apply("hdr", false, || Ok(true), |_| Ok(()));
+
+            assert_eq!(
+                serde_json::to_string(&change).unwrap(),
+                r#"{"setting":"hdr","old":true,"new":false,"error":null}"#
+            );
+        }
+    }
+}
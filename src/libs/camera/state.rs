@@ -0,0 +1,194 @@
+// SPDX-License-Identifier: EUPL-1.2
+
+use crate::libs::camera::camera::{Camera, Tiny2Camera};
+use crate::libs::camera::enums::{AIMode, PanTilt, SleepMode, TrackingSpeed};
+use crate::libs::errors::T4lError;
+use serde::{Deserialize, Serialize};
+
+/// Difference in degrees below which the gimbal counts as being in the same position.
+const PAN_TILT_TOLERANCE_DEGREES: f64 = 0.5;
+
+/// The full configuration of the camera, as captured by [`Camera::snapshot`].
+///
+/// Next to the settings of the [`CameraStatus`](crate::CameraStatus), it holds the zoom and the
+/// position of the gimbal, which are read from the V4L2 controls.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CameraState {
+    pub sleep_mode: SleepMode,
+    pub ai_mode: AIMode,
+    pub tracking_speed: TrackingSpeed,
+    pub hdr_on: bool,
+    pub zoom: i32,
+    /// Pan of the gimbal in degrees.
+    pub pan: f64,
+    /// Tilt of the gimbal in degrees.
+    pub tilt: f64,
+}
+
+impl CameraState {
+    fn same_position(&self, other: &CameraState) -> bool {
+        (self.pan - other.pan).abs() < PAN_TILT_TOLERANCE_DEGREES
+            && (self.tilt - other.tilt).abs() < PAN_TILT_TOLERANCE_DEGREES
+    }
+}
+
+impl Camera {
+    /// Captures the current configuration, so that it can be put back with [`Camera::restore`].
+    pub fn snapshot(&self) -> Result<CameraState, T4lError> {
+        let status = self.get_status()?;
+        let (pan, tilt) = self.get_pan_tilt()?;
+
+        Ok(CameraState {
+            sleep_mode: status.awake,
+            ai_mode: status.ai_mode,
+            tracking_speed: status.speed,
+            hdr_on: status.hdr_on,
+            zoom: self.get_zoom()?,
+            pan,
+            tilt,
+        })
+    }
+
+    /// Puts back a configuration captured by [`Camera::snapshot`].
+    ///
+    /// Only the settings that differ from the current ones are sent, so nothing changes if the
+    /// camera is still configured like that. Settings that were unknown when the snapshot was
+    /// taken are left as they are. The position is only restored without tracking, as the
+    /// tracking moves the gimbal anyway.
+    ///
+    /// Returns the number of settings that had to be changed.
+    pub fn restore(&self, state: &CameraState) -> Result<usize, T4lError> {
+        let current = self.snapshot()?;
+        let mut changed = 0;
+
+        if state.sleep_mode == SleepMode::Awake && current.sleep_mode != SleepMode::Awake {
+            self.set_sleep_mode(SleepMode::Awake)?;
+            changed += 1;
+        }
+        if state.tracking_speed != current.tracking_speed {
+            self.set_tracking_speed(state.tracking_speed)?;
+            changed += 1;
+        }
+        if state.hdr_on != current.hdr_on {
+            self.set_hdr_mode(state.hdr_on)?;
+            changed += 1;
+        }
+        if state.zoom != current.zoom {
+            self.set_zoom(state.zoom)?;
+            changed += 1;
+        }
+        if state.ai_mode != AIMode::Unknown && state.ai_mode != current.ai_mode {
+            self.set_ai_mode(state.ai_mode)?;
+            changed += 1;
+        }
+        if state.ai_mode == AIMode::NoTracking && !state.same_position(&current) {
+            self.set_pan_tilt(PanTilt::Absolute {
+                pan: state.pan,
+                tilt: state.tilt,
+            })?;
+            changed += 1;
+        }
+        if state.sleep_mode == SleepMode::Sleep && current.sleep_mode != SleepMode::Sleep {
+            self.set_sleep_mode(SleepMode::Sleep)?;
+            changed += 1;
+        }
+
+        Ok(changed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod unit {
+        use crate::{AIMode, CameraState, SleepMode, TrackingSpeed};
+        use test_case::test_case;
+
+        fn state(pan: f64, tilt: f64) -> CameraState {
+            CameraState {
+                sleep_mode: SleepMode::Awake,
+                ai_mode: AIMode::NoTracking,
+                tracking_speed: TrackingSpeed::Standard,
+                hdr_on: false,
+                zoom: 0,
+                pan,
+                tilt,
+            }
+        }
+
+        #[test_case(10.0, -5.0, true; "same")]
+        #[test_case(10.2, -5.3, true; "within tolerance")]
+        #[test_case(11.0, -5.0, false; "other pan")]
+        #[test_case(10.0, 5.0, false; "other tilt")]
+        fn same_position(pan: f64, tilt: f64, expected: bool) {
+            assert_eq!(state(10.0, -5.0).same_position(&state(pan, tilt)), expected);
+        }
+
+        #[test]
+        fn serialize_state() {
+            let json = serde_json::to_value(state(1.0, 2.0)).unwrap();
+
+            assert_eq!(json["ai_mode"], "no_tracking");
+            assert_eq!(json["hdr_on"], false);
+            assert_eq!(json["pan"], 1.0);
+        }
+    }
+
+    mod integration {
+        use crate::{AIMode, Camera, PanTilt, SleepMode, Tiny2Camera, TrackingSpeed};
+
+        #[test]
+        fn restores_snapshot() {
+            let camera = Camera::simulated();
+            let snapshot = camera.snapshot().unwrap();
+
+            camera.set_ai_mode(AIMode::Group).unwrap();
+            camera.set_tracking_speed(TrackingSpeed::Standard).unwrap();
+            camera.set_zoom(70).unwrap();
+            camera.set_sleep_mode(SleepMode::Sleep).unwrap();
+            let changed = camera.snapshot().unwrap();
+            assert_ne!(changed.ai_mode, snapshot.ai_mode);
+            assert_ne!(changed.tracking_speed, snapshot.tracking_speed);
+            assert_ne!(changed.zoom, snapshot.zoom);
+            assert_ne!(changed.sleep_mode, snapshot.sleep_mode);
+
+            camera.restore(&snapshot).unwrap();
+
+            let restored = camera.snapshot().unwrap();
+            assert_eq!(restored.ai_mode, snapshot.ai_mode);
+            assert_eq!(restored.tracking_speed, snapshot.tracking_speed);
+            assert_eq!(restored.zoom, snapshot.zoom);
+            assert_eq!(restored.sleep_mode, snapshot.sleep_mode);
+        }
+
+        #[test]
+        fn restores_nothing_if_unchanged() {
+            let camera = Camera::simulated();
+            let snapshot = camera.snapshot().unwrap();
+
+            assert_eq!(camera.restore(&snapshot).unwrap(), 0);
+        }
+
+        #[test]
+        fn restores_position_without_tracking() {
+            let camera = Camera::simulated();
+            camera.set_ai_mode(AIMode::NoTracking).unwrap();
+            camera
+                .set_pan_tilt(PanTilt::Absolute {
+                    pan: 20.0,
+                    tilt: 10.0,
+                })
+                .unwrap();
+            let snapshot = camera.snapshot().unwrap();
+
+            camera
+                .set_pan_tilt(PanTilt::Absolute {
+                    pan: 0.0,
+                    tilt: 0.0,
+                })
+                .unwrap();
+
+            assert_eq!(camera.restore(&snapshot).unwrap(), 1);
+            assert_eq!(camera.get_pan_tilt().unwrap(), (20.0, 10.0));
+        }
+    }
+}
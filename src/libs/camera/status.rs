@@ -1,12 +1,64 @@
 // SPDX-License-Identifier: EUPL-1.2
 
 use crate::libs::camera::enums::{AIMode, SleepMode, TrackingSpeed};
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
 
+/// An offset of the status, as listed in the documentation of [`CameraStatus`].
+pub(crate) struct StatusOffset {
+    pub(crate) range: Range<usize>,
+    pub(crate) name: &'static str,
+    /// Whether captures confirmed the offset, otherwise it's only derived from the commands.
+    pub(crate) confirmed: bool,
+}
+
+/// The known offsets of the status, of which only the confirmed ones are decoded.
+pub(crate) const STATUS_OFFSETS: [StatusOffset; 10] = [
+    status_offset(0x02..0x03, "sleep mode", true),
+    status_offset(0x03..0x04, "gesture control", false),
+    status_offset(0x06..0x07, "hdr", true),
+    status_offset(0x07..0x08, "exposure mode type", false),
+    status_offset(0x08..0x09, "automatic exposure", false),
+    status_offset(0x14..0x15, "auto-framing", false),
+    status_offset(0x18..0x19, "ai mode", true),
+    status_offset(0x1c..0x1d, "ai sub mode", true),
+    status_offset(0x1f..0x20, "zoom", false),
+    status_offset(0x21..0x22, "tracking speed", true),
+];
+
+const fn status_offset(range: Range<usize>, name: &'static str, confirmed: bool) -> StatusOffset {
+    StatusOffset {
+        range,
+        name,
+        confirmed,
+    }
+}
+
+/// The status of the camera, as reported on the extension unit selector 0x06.
+///
+/// | Offset        | Content                                           | Confirmed |
+/// |---------------|---------------------------------------------------|-----------|
+/// | `0x02`        | Sleep mode                                        | yes       |
+/// | `0x03`        | Gesture control                                   | no        |
+/// | `0x06`        | HDR, set by the command `01 01 xx`                | yes       |
+/// | `0x07`        | Exposure mode type (`01` auto, `02` manual)       | no        |
+/// | `0x08`        | Automatic exposure, set by the command `03 01 xx` | no        |
+/// | `0x14`        | Auto-framing                                      | no        |
+/// | `0x18`/`0x1c` | AI mode and its sub mode                          | yes       |
+/// | `0x1f`        | Digital zoom in percent                           | no        |
+/// | `0x21`        | Tracking speed                                    | yes       |
+///
+/// Offsets that aren't confirmed by captures yet are derived from the commands changing them, so
+/// they aren't decoded, see `STATUS_OFFSETS`. The zoom is read from the V4L2 control instead.
+/// All bytes are kept in `raw`, so unknown offsets remain inspectable.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CameraStatus {
     pub awake: SleepMode,
     pub ai_mode: AIMode,
     pub speed: TrackingSpeed,
     pub hdr_on: bool,
+    #[serde(with = "hex::serde")]
+    pub raw: Vec<u8>,
 }
 
 impl CameraStatus {
@@ -16,6 +68,7 @@ impl CameraStatus {
             ai_mode: Self::decode_ai_mode(bytes),
             speed: Self::decode_tracking_speed(bytes),
             hdr_on: Self::decode_hdr_on(bytes),
+            raw: bytes.to_vec(),
         }
     }
 
@@ -64,6 +117,7 @@ impl CameraStatus {
             ai_mode: AIMode::Unknown,
             speed: TrackingSpeed::Standard,
             hdr_on: false,
+            raw: Vec::new(),
         }
     }
 }
@@ -134,6 +188,16 @@ mod tests {
                 assert_eq!(expected, status.hdr_on);
             }
 
+            #[test]
+            fn raw_bytes() {
+                let mut test_string = RAW_TEST_STRING;
+                test_string[0x30] = 0x42;
+
+                let status = CameraStatus::decode(&test_string);
+
+                assert_eq!(status.raw, test_string.to_vec());
+            }
+
             #[test]
             fn status_defaults() {
                 let default_status = CameraStatus::default();
@@ -150,6 +214,7 @@ mod tests {
                     "speed is standard"
                 );
                 assert_eq!(default_status.hdr_on, false, "hdr is off");
+                assert!(default_status.raw.is_empty(), "no bytes were received");
             }
         }
     }
@@ -172,6 +237,28 @@ mod tests {
                 assert_eq!(status.hdr_on, true);
                 assert_eq!(status.ai_mode, AIMode::UpperBody);
                 assert_eq!(status.speed, TrackingSpeed::Sport);
+                assert_eq!(status.raw, data.to_vec());
+            }
+
+            #[test]
+            fn serialize_status() {
+                let mut data = [0u8; 36];
+                data[0x06] = 0x01;
+                data[0x18] = 0x02;
+                let status = CameraStatus::decode(&data);
+
+                let json = serde_json::to_value(&status).unwrap();
+
+                assert_eq!(json["awake"], "awake");
+                assert_eq!(json["ai_mode"], "normal_tracking");
+                assert_eq!(json["speed"], "standard");
+                assert_eq!(json["hdr_on"], true);
+                assert_eq!(json["raw"], hex::encode(data));
+                assert_eq!(
+                    serde_json::from_value::<CameraStatus>(json).unwrap(),
+                    status,
+                    "status should be deserializable"
+                );
             }
         }
     }
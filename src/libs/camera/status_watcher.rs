@@ -0,0 +1,163 @@
+// SPDX-License-Identifier: EUPL-1.2
+
+use crate::libs::camera::camera::Camera;
+use crate::libs::camera::enums::{AIMode, SleepMode, TrackingSpeed};
+use crate::libs::camera::status::CameraStatus;
+use crate::libs::errors::T4lError;
+use std::thread;
+use std::time::Duration;
+
+/// A setting that changed between two readings of the [`CameraStatus`], with its new value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StatusChange {
+    SleepMode(SleepMode),
+    AIMode(AIMode),
+    TrackingSpeed(TrackingSpeed),
+    Hdr(bool),
+}
+
+impl StatusChange {
+    /// Lists the settings that differ between the two readings, with their values in `new`.
+    pub fn between(old: &CameraStatus, new: &CameraStatus) -> Vec<StatusChange> {
+        let mut changes = Vec::new();
+
+        if old.awake != new.awake {
+            changes.push(StatusChange::SleepMode(new.awake));
+        }
+        if old.ai_mode != new.ai_mode {
+            changes.push(StatusChange::AIMode(new.ai_mode));
+        }
+        if old.speed != new.speed {
+            changes.push(StatusChange::TrackingSpeed(new.speed));
+        }
+        if old.hdr_on != new.hdr_on {
+            changes.push(StatusChange::Hdr(new.hdr_on));
+        }
+
+        changes
+    }
+}
+
+/// Polls the status of the camera on selector 0x06 and reports the settings that changed,
+/// e.g. by the CLI, the button on the camera or the remote.
+///
+/// The camera is passed to each poll, so that an app can share its own camera with the watcher.
+pub struct StatusWatcher {
+    interval: Duration,
+    last_status: Option<CameraStatus>,
+}
+
+impl StatusWatcher {
+    /// Watches a camera, reading its status once per interval while waiting.
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            last_status: None,
+        }
+    }
+
+    /// Reads the status once and returns what changed since the last reading.
+    ///
+    /// The first reading only serves as the base for later ones, so it reports no changes.
+    pub fn poll(&mut self, camera: &Camera) -> Result<Vec<StatusChange>, T4lError> {
+        let status = camera.get_status()?;
+
+        let changes = match &self.last_status {
+            Some(last_status) => StatusChange::between(last_status, &status),
+            None => Vec::new(),
+        };
+
+        self.last_status = Some(status);
+        Ok(changes)
+    }
+
+    /// Blocks until a reading differs from the previous one and returns the changes.
+    pub fn wait(&mut self, camera: &Camera) -> Result<Vec<StatusChange>, T4lError> {
+        loop {
+            let changes = self.poll(camera)?;
+            if !changes.is_empty() {
+                return Ok(changes);
+            }
+
+            thread::sleep(self.interval);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod unit {
+        use crate::{AIMode, CameraStatus, SleepMode, StatusChange, TrackingSpeed};
+
+        #[test]
+        fn no_changes() {
+            let status = CameraStatus::default();
+            assert!(StatusChange::between(&status, &status.clone()).is_empty());
+        }
+
+        #[test]
+        fn lists_changed_settings() {
+            let old = CameraStatus::default();
+            let new = CameraStatus {
+                awake: SleepMode::Sleep,
+                speed: TrackingSpeed::Sport,
+                hdr_on: !old.hdr_on,
+                ..old.clone()
+            };
+
+            assert_eq!(
+                StatusChange::between(&old, &new),
+                vec![
+                    StatusChange::SleepMode(SleepMode::Sleep),
+                    StatusChange::TrackingSpeed(TrackingSpeed::Sport),
+                    StatusChange::Hdr(new.hdr_on),
+                ]
+            );
+        }
+
+        #[test]
+        fn ignores_raw_bytes_without_meaning() {
+            let old = CameraStatus::default();
+            let new = CameraStatus {
+                raw: vec![0xff; 60],
+                ..old.clone()
+            };
+
+            assert!(StatusChange::between(&old, &new).is_empty());
+        }
+
+        #[test]
+        fn reports_new_value() {
+            let old = CameraStatus::default();
+            let new = CameraStatus {
+                ai_mode: AIMode::Hand,
+                ..old.clone()
+            };
+
+            assert_eq!(
+                StatusChange::between(&old, &new),
+                vec![StatusChange::AIMode(AIMode::Hand)]
+            );
+        }
+    }
+
+    mod integration {
+        use crate::{Camera, SleepMode, StatusChange, StatusWatcher, Tiny2Camera};
+        use std::time::Duration;
+
+        #[test]
+        fn reports_changes_of_the_camera() {
+            let camera = Camera::simulated();
+            let mut watcher = StatusWatcher::new(Duration::ZERO);
+            assert!(watcher.poll(&camera).unwrap().is_empty());
+
+            camera.set_sleep_mode(SleepMode::Sleep).unwrap();
+
+            assert_eq!(
+                watcher.poll(&camera).unwrap(),
+                vec![StatusChange::SleepMode(SleepMode::Sleep)]
+            );
+            assert!(watcher.poll(&camera).unwrap().is_empty());
+        }
+    }
+}
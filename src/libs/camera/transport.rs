@@ -1,16 +1,27 @@
 // SPDX-License-Identifier: EUPL-1.2
 
-use crate::CameraStatus;
 use crate::libs::errors::T4lError;
+use crate::libs::recording::Recorder;
 use crate::libs::usbio::{
-    CameraHandle, UVC_GET_CUR, UVC_GET_LEN, UVC_SET_CUR, UvcUsbIo, open_camera,
+    CameraHandleType, CameraInfo, ControlRange, SimulatedCameraHandle, UVC_GET_CUR, UVC_GET_INFO,
+    UVC_GET_LEN, UVC_SET_CUR, UvcUsbIo, open_camera,
 };
+use crate::{CameraStatus, Command02Frame, set_sequence_nr};
 use errno::Errno;
+use nix::libc::EIO;
+use std::path::Path;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU16, Ordering};
 
 /// This is a wrapper around the USB camera transport.
 /// It is used to send commands to a camera using a camera handle.
+/// It also owns the frame counter used as sequence number for 0x02 frames.
+/// Requests are sent one operation at a time, so that e.g. the daemon can serve several clients.
 pub struct CameraTransport {
-    handle: CameraHandle,
+    handle: CameraHandleType,
+    sequence_nr: AtomicU16,
+    recorder: Option<Recorder>,
+    io_lock: Mutex<()>,
 }
 
 impl CameraTransport {
@@ -37,17 +48,46 @@ impl CameraTransport {
     /// ```
     pub fn new(hint: &str) -> Result<Self, T4lError> {
         Ok(Self {
-            handle: open_camera(hint)?,
+            handle: open_camera(hint, true)?,
+            sequence_nr: AtomicU16::new(0),
+            recorder: None,
+            io_lock: Mutex::new(()),
         })
     }
 
-    /// Retrieves and returns information about the current object or instance.
+    /// Creates a new instance of the CameraTransport struct that opens the device itself,
+    /// even if the daemon is running, as the daemon does.
+    pub fn new_local(hint: &str) -> Result<Self, T4lError> {
+        Ok(Self {
+            handle: open_camera(hint, false)?,
+            sequence_nr: AtomicU16::new(0),
+            recorder: None,
+            io_lock: Mutex::new(()),
+        })
+    }
+
+    /// Creates a new instance of the CameraTransport struct backed by a simulated camera.
     ///
-    /// This method invokes the `info` method on the associated handle to gather the required details.
-    /// The specific information returned depends on the implementation of the underlying handle's `info` method.
+    /// The simulated camera keeps its state in memory, so no device is needed.
+    pub fn simulated() -> Self {
+        Self {
+            handle: SimulatedCameraHandle::default().into(),
+            sequence_nr: AtomicU16::new(0),
+            recorder: None,
+            io_lock: Mutex::new(()),
+        }
+    }
+
+    /// The handle of the camera, e.g. to relay raw requests of clients to it.
+    #[cfg(feature = "dbus")]
+    pub(crate) fn handle(&self) -> &CameraHandleType {
+        &self.handle
+    }
+
+    /// Retrieves the name and the bus of the camera from the associated handle.
     ///
     /// # Returns
-    /// * `Ok(())` - If the operation completes successfully.
+    /// * `Ok(CameraInfo)` - The card name and bus info of the camera.
     /// * `Err(Errno)` - If an error occurs during the process. The `Errno` indicates the specific error encountered.
     ///
     /// # Errors
@@ -56,14 +96,12 @@ impl CameraTransport {
     ///
     /// # Example
     /// ```rust,ignore
-    /// let result = object.info();
-    /// if let Err(e) = result {
-    ///     eprintln!("Failed to retrieve information: {:?}", e);
-    /// } else {
-    ///     println!("Information retrieved successfully.");
+    /// match object.info() {
+    ///     Ok(info) => println!("Card: {}\nBus : {}", info.card, info.bus_info),
+    ///     Err(e) => eprintln!("Failed to retrieve information: {:?}", e),
     /// }
     /// ```
-    pub fn info(&self) -> Result<(), Errno> {
+    pub fn info(&self) -> Result<CameraInfo, Errno> {
         self.handle.info()
     }
 
@@ -77,10 +115,11 @@ impl CameraTransport {
     ///
     /// # Returns
     /// - `Ok(())`: If the command is successfully sent.
-    /// - `Err(T4lError)`: If an error occurs while sending the command, wrapped in a `T4lError::USBIOError`.
+    /// - `Err(T4lError)`: If an error occurs while sending the command, see `T4lError::from(Errno)`.
     ///
     /// # Errors
-    /// This method returns `T4lError::USBIOError` if the `set_cur` operation fails.
+    /// This method returns `T4lError::DataTooLong` if the command is longer than 60 bytes,
+    /// or a `T4lError` mapped from the errno if the `set_cur` operation fails.
     ///
     /// # Example
     /// ```rust,ignore
@@ -98,10 +137,32 @@ impl CameraTransport {
         debugging: bool,
     ) -> Result<(), T4lError> {
         let mut data = [0u8; 60];
+        if cmd.len() > data.len() {
+            return Err(T4lError::DataTooLong(cmd.len(), data.len()));
+        }
         data[..cmd.len()].copy_from_slice(cmd);
 
         self.set_cur(unit, selector, &mut data, debugging)
-            .map_err(|e| T4lError::USBIOError(e.0))
+            .map_err(T4lError::from)
+    }
+
+    /// Sends a 0x02 frame built by `command02` to the camera.
+    ///
+    /// Before sending, the frame is stamped with the next sequence number of the transport,
+    /// which also updates its header checksum.
+    ///
+    /// # Parameters
+    /// - `frame`: The frame to be sent.
+    /// - `debugging`: A boolean flag indicating whether debugging information should be printed to the console.
+    ///
+    /// # Returns
+    /// - `Ok(())`: If the frame is successfully sent.
+    /// - `Err(T4lError)`: If an error occurs while sending the frame, see `T4lError::from(Errno)`.
+    pub fn send_cmd02(&self, frame: &[u8; 36], debugging: bool) -> Result<(), T4lError> {
+        let mut frame = *frame;
+        set_sequence_nr(&mut frame, self.next_sequence_nr());
+
+        self.send_cmd(0x2, 0x2, &frame, debugging)
     }
 
     /// Retrieves the current status of the camera.
@@ -121,6 +182,8 @@ impl CameraTransport {
     /// # Errors
     /// This function can return the following errors:
     /// * `T4lError::USBIOError` - If there is an issue during the USB communication.
+    /// * `T4lError::PermissionDenied`, `T4lError::CameraBusy` or `T4lError::CameraDisconnected` -
+    ///   If the communication failed for one of these known reasons.
     ///
     /// # Debugging
     /// If the debugging mode is enabled via the `debugging` field, this function
@@ -136,8 +199,7 @@ impl CameraTransport {
     /// ```
     pub fn get_status(&self, debugging: bool) -> Result<CameraStatus, T4lError> {
         let mut data: [u8; 60] = [0u8; 60];
-        self.get_cur(0x2, 0x6, &mut data)
-            .map_err(|x| T4lError::USBIOError(x.0))?;
+        self.get_cur(0x2, 0x6, &mut data).map_err(T4lError::from)?;
 
         if debugging {
             println!("Current state: {:?} {:}", data, hex::encode(&data));
@@ -164,8 +226,29 @@ impl CameraTransport {
         Ok(())
     }
 
+    /// Retrieves the current 0x2, 0x2 data and decodes it as a 0x02 frame.
+    ///
+    /// # Returns
+    /// * `Ok(Command02Frame)` - The decoded frame the camera answered with.
+    /// * `Err(T4lError)` - An error encountered during the process, wrapped in `T4lError`.
+    ///
+    /// # Errors
+    /// This function can return the following errors:
+    /// * `T4lError::USBIOError` - If there is an issue during the USB communication.
+    /// * `T4lError::PermissionDenied`, `T4lError::CameraBusy` or `T4lError::CameraDisconnected` -
+    ///   If the communication failed for one of these known reasons.
+    /// * `T4lError::InvalidFrame` - If the answer isn't a valid 0x02 frame.
+    pub fn get_frame02(&self) -> Result<Command02Frame, T4lError> {
+        let mut data: [u8; 60] = [0u8; 60];
+        self.get_cur(0x2, 0x2, &mut data).map_err(T4lError::from)?;
+
+        Command02Frame::parse(&data)
+    }
+
     /// Dumps the current state of the 0x2, 0x2 data to the console in hexadecimal format.
     ///
+    /// If the data is a valid 0x02 frame, its decoded parts are printed as well.
+    ///
     /// # Returns
     ///
     /// - `Ok(())` if the data is successfully retrieved and dumped to the console.
@@ -179,25 +262,181 @@ impl CameraTransport {
         let mut data: [u8; 60] = [0u8; 60];
         self.get_cur(0x2, 0x2, &mut data)?;
         hexdump::hexdump(&data);
+        match Command02Frame::parse(&data) {
+            Ok(frame) => println!("{:?}", frame),
+            Err(err) => println!("{}", err),
+        }
         Ok(())
     }
 
-    fn get_cur(&self, unit: u8, selector: u8, data: &mut [u8]) -> Result<(), Errno> {
-        // always call get_len first
-        match self.get_len(unit, selector) {
-            Ok(size) => {
-                if data.len() < size {
-                    println!("Got size {}", size);
-                    return Err(Errno(1));
-                }
-            }
-            Err(err) => return Err(err),
+    /// Retrieves the current data of the specified unit and selector, as long as the camera reports it.
+    ///
+    /// # Parameters
+    /// - `unit`: The unit identifier the data is read from.
+    /// - `selector`: The selector value specifying the target control.
+    ///
+    /// # Returns
+    /// - `Ok(Vec<u8>)`: The data, with the length of `UVC_GET_LEN`.
+    /// - `Err(T4lError)`: If an error occurs while reading the data, see `T4lError::from(Errno)`.
+    pub fn get_raw(&self, unit: u8, selector: u8) -> Result<Vec<u8>, T4lError> {
+        let mut data = vec![0u8; self.get_len(unit, selector)?];
+        self.io(unit, selector, UVC_GET_CUR, &mut data)?;
+        Ok(data)
+    }
+
+    /// Reads the current value of a standard V4L2 control, e.g. the zoom.
+    ///
+    /// # Parameters
+    /// - `id`: The V4L2 control id, as defined in linux/v4l2-controls.h.
+    ///
+    /// # Returns
+    /// - `Ok(i32)`: The current value of the control.
+    /// - `Err(T4lError)`: If the control can't be read, see `T4lError::from(Errno)`.
+    pub fn get_ctrl(&self, id: u32) -> Result<i32, T4lError> {
+        self.handle.get_ctrl(id).map_err(T4lError::from)
+    }
+
+    /// Sets the value of a standard V4L2 control, e.g. the zoom.
+    ///
+    /// # Parameters
+    /// - `id`: The V4L2 control id, as defined in linux/v4l2-controls.h.
+    /// - `value`: The new value of the control.
+    /// - `debugging`: A boolean flag indicating whether debugging information should be printed to the console.
+    ///
+    /// # Returns
+    /// - `Ok(())`: If the control is successfully set.
+    /// - `Err(T4lError)`: If the control can't be set, see `T4lError::from(Errno)`.
+    pub fn set_ctrl(&self, id: u32, value: i32, debugging: bool) -> Result<(), T4lError> {
+        if debugging {
+            println!("control {:#010x} {:}", id, value);
+        }
+
+        self.handle.set_ctrl(id, value).map_err(T4lError::from)
+    }
+
+    /// Queries the range of values a standard V4L2 control accepts.
+    ///
+    /// # Parameters
+    /// - `id`: The V4L2 control id, as defined in linux/v4l2-controls.h.
+    ///
+    /// # Returns
+    /// - `Ok(ControlRange)`: The minimum, maximum, step and default value of the control.
+    /// - `Err(T4lError)`: If the control can't be queried, see `T4lError::from(Errno)`.
+    pub fn query_ctrl(&self, id: u32) -> Result<ControlRange, T4lError> {
+        self.handle.query_ctrl(id).map_err(T4lError::from)
+    }
+
+    /// Writes every request sent from now on to the camera to the file, see [`Recording`](crate::Recording).
+    ///
+    /// # Parameters
+    /// - `path`: The file the requests are written to, which is replaced if it exists.
+    ///
+    /// # Returns
+    /// - `Ok(())`: If the file is created.
+    /// - `Err(T4lError)`: If the file can't be created, see `T4lError::IOError`.
+    pub fn start_recording(&mut self, path: &Path) -> Result<(), T4lError> {
+        self.recorder = Some(Recorder::create(path)?);
+        Ok(())
+    }
+
+    /// Sends a raw UVC request to the specified unit and selector, without asking for its length first.
+    ///
+    /// # Parameters
+    /// - `unit`: The unit identifier to which the request is being sent.
+    /// - `selector`: The selector value specifying the target control.
+    /// - `query`: The UVC request code, e.g. `UVC_SET_CUR`.
+    /// - `data`: The data sent with a SET request, or the buffer for the answer to a GET request.
+    ///
+    /// # Returns
+    /// - `Ok(())`: If the request is successfully sent.
+    /// - `Err(T4lError)`: If an error occurs while sending the request, see `T4lError::from(Errno)`.
+    pub fn query(
+        &self,
+        unit: u8,
+        selector: u8,
+        query: u8,
+        data: &mut [u8],
+    ) -> Result<(), T4lError> {
+        self.io(unit, selector, query, data).map_err(T4lError::from)
+    }
+
+    /// Sends a raw UVC request to the specified unit and selector, sizing the data as the request needs it.
+    ///
+    /// `UVC_GET_LEN` takes 2 bytes and `UVC_GET_INFO` 1 byte, all other requests as many bytes as
+    /// `UVC_GET_LEN` reports for the selector. The given data is padded with zeros to this size.
+    ///
+    /// # Parameters
+    /// - `unit`: The unit identifier to which the request is being sent.
+    /// - `selector`: The selector value specifying the target control.
+    /// - `query`: The UVC request code, e.g. `UVC_GET_MAX`.
+    /// - `data`: The data sent with a SET request, empty for a GET request.
+    ///
+    /// # Returns
+    /// - `Ok(Vec<u8>)`: The answer to a GET request, or the padded data sent with a SET request.
+    /// - `Err(T4lError)`: `T4lError::DataTooLong` if the data doesn't fit the selector,
+    ///   or if an error occurs while sending the request, see `T4lError::from(Errno)`.
+    pub fn request(
+        &self,
+        unit: u8,
+        selector: u8,
+        query: u8,
+        data: &[u8],
+    ) -> Result<Vec<u8>, T4lError> {
+        let mut buffer = match query {
+            UVC_GET_LEN => vec![0u8; 2],
+            UVC_GET_INFO => vec![0u8; 1],
+            _ => vec![0u8; self.get_len(unit, selector)?],
         };
+        if data.len() > buffer.len() {
+            return Err(T4lError::DataTooLong(data.len(), buffer.len()));
+        }
+        buffer[..data.len()].copy_from_slice(data);
 
-        match self.io(unit, selector, UVC_GET_CUR, data) {
-            Ok(_) => Ok(()),
-            Err(err) => Err(err),
+        self.io(unit, selector, query, &mut buffer)?;
+        Ok(buffer)
+    }
+
+    fn next_sequence_nr(&self) -> u16 {
+        self.sequence_nr.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// Relays a single request of a client of the daemon.
+    #[cfg(feature = "dbus")]
+    pub(crate) fn relay_io(
+        &self,
+        unit: u8,
+        selector: u8,
+        query: u8,
+        data: &mut [u8],
+    ) -> Result<(), Errno> {
+        self.io(unit, selector, query, data)
+    }
+
+    /// Relays a request of a client of the daemon as one operation, see [`UvcUsbIo::sized_io`].
+    ///
+    /// 0x02 frames are stamped with the next sequence number of this transport, so that the
+    /// daemon counts the frames of all its clients.
+    #[cfg(feature = "dbus")]
+    pub(crate) fn relay(
+        &self,
+        unit: u8,
+        selector: u8,
+        query: u8,
+        data: &mut [u8],
+    ) -> Result<usize, Errno> {
+        if (unit, selector, query) == (0x2, 0x2, UVC_SET_CUR)
+            && Command02Frame::parse(data).is_ok()
+            && let Some(frame) = data.first_chunk_mut::<36>()
+        {
+            set_sequence_nr(frame, self.next_sequence_nr());
         }
+
+        self.sized_io(unit, selector, query, data)
+    }
+
+    fn get_cur(&self, unit: u8, selector: u8, data: &mut [u8]) -> Result<(), Errno> {
+        // always call get_len first
+        self.sized_io(unit, selector, UVC_GET_CUR, data).map(|_| ())
     }
 
     fn set_cur(
@@ -207,24 +446,11 @@ impl CameraTransport {
         data: &mut [u8],
         debugging: bool,
     ) -> Result<(), Errno> {
-        match self.get_len(unit, selector) {
-            Ok(size) => {
-                if data.len() > size {
-                    println!("Got size {}", size);
-                    return Err(Errno(1));
-                }
-            }
-            Err(err) => return Err(err),
-        };
-
         if debugging {
             println!("{:} {:} {:}", unit, selector, hex::encode(&data));
         }
 
-        match self.io(unit, selector, UVC_SET_CUR, data) {
-            Ok(_) => Ok(()),
-            Err(err) => Err(err),
-        }
+        self.sized_io(unit, selector, UVC_SET_CUR, data).map(|_| ())
     }
 
     fn get_len(&self, unit: u8, selector: u8) -> Result<usize, Errno> {
@@ -237,6 +463,137 @@ impl CameraTransport {
     }
 
     fn io(&self, unit: u8, selector: u8, query: u8, data: &mut [u8]) -> Result<(), Errno> {
-        self.handle.io(unit, selector, query, data)
+        let _guard = self.io_lock.lock().map_err(|_| Errno(EIO))?;
+        let result = self.handle.io(unit, selector, query, data);
+
+        if let Some(recorder) = &self.recorder {
+            recorder.record(query, unit, selector, data, result.err());
+        }
+
+        result
+    }
+
+    fn sized_io(&self, unit: u8, selector: u8, query: u8, data: &mut [u8]) -> Result<usize, Errno> {
+        let _guard = self.io_lock.lock().map_err(|_| Errno(EIO))?;
+        let result = self.handle.sized_io(unit, selector, query, data);
+
+        // a failure isn't told apart from one of the GET_LEN request before
+        match (&self.recorder, &result) {
+            (Some(recorder), Ok(size)) => {
+                let size = (*size as u16).to_le_bytes();
+                recorder.record(UVC_GET_LEN, unit, selector, &size, None);
+                recorder.record(query, unit, selector, data, None);
+            }
+            (Some(recorder), Err(error)) => {
+                recorder.record(query, unit, selector, data, Some(*error))
+            }
+            (None, _) => {}
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod unit {
+        use crate::libs::camera::transport::CameraTransport;
+        use crate::libs::usbio::{UVC_GET_CUR, UVC_GET_INFO, UVC_GET_LEN, UVC_SET_CUR};
+        use crate::{SleepCommand, SleepMode, T4lError, checksum};
+        use errno::Errno;
+        use nix::libc::EMSGSIZE;
+        use test_case::test_case;
+
+        #[test]
+        fn send_cmd02_increments_sequence_nr() {
+            let transport = CameraTransport::simulated();
+            let frame = SleepCommand::build(SleepMode::Awake).unwrap();
+            let mut sent = [0u8; 60];
+
+            for expected_sequence_nr in [0x0000u16, 0x0001, 0x0002] {
+                transport.send_cmd02(&frame, false).unwrap();
+                transport.get_cur(0x2, 0x2, &mut sent).unwrap();
+
+                assert_eq!(
+                    sent[2..4],
+                    expected_sequence_nr.to_le_bytes(),
+                    "Sequence number should be counted up"
+                );
+                assert_eq!(
+                    sent[6..8],
+                    checksum(&[&sent[0..6], &[0x00, 0x00], &sent[8..12]].concat()),
+                    "Header checksum should match the sequence number"
+                );
+            }
+        }
+
+        #[test_case(UVC_GET_LEN, &[0x3c, 0x00]; "get len")]
+        #[test_case(UVC_GET_INFO, &[0x03]; "get info")]
+        fn sizes_request_by_query(query: u8, expected: &[u8]) {
+            let transport = CameraTransport::simulated();
+
+            assert_eq!(transport.request(0x2, 0x6, query, &[]).unwrap(), expected);
+        }
+
+        #[test]
+        fn pads_request_data() {
+            let transport = CameraTransport::simulated();
+
+            let sent = transport
+                .request(0x2, 0x2, UVC_SET_CUR, &[0xaa, 0x25])
+                .unwrap();
+            let read = transport.request(0x2, 0x2, UVC_GET_CUR, &[]).unwrap();
+
+            assert_eq!(sent.len(), 60);
+            assert_eq!(read, sent);
+            assert_eq!(read[..3], [0xaa, 0x25, 0x00]);
+        }
+
+        #[test]
+        fn rejects_too_long_request_data() {
+            let transport = CameraTransport::simulated();
+
+            let error = transport
+                .request(0x2, 0x2, UVC_SET_CUR, &[0u8; 61])
+                .unwrap_err();
+
+            assert!(matches!(error, T4lError::DataTooLong(61, 60)));
+        }
+
+        #[test_case(UVC_GET_CUR, 59; "answer doesn't fit")]
+        #[test_case(UVC_SET_CUR, 61; "data too long")]
+        fn rejects_data_not_fitting_the_selector(query: u8, length: usize) {
+            let transport = CameraTransport::simulated();
+
+            let error = transport
+                .sized_io(0x2, 0x2, query, &mut vec![0u8; length])
+                .unwrap_err();
+
+            assert_eq!(error, Errno(EMSGSIZE));
+        }
+
+        #[test]
+        fn rejects_too_long_command() {
+            let transport = CameraTransport::simulated();
+
+            let error = transport.send_cmd(0x2, 0x6, &[0u8; 61], false).unwrap_err();
+
+            assert!(matches!(error, T4lError::DataTooLong(61, 60)));
+        }
+
+        #[cfg(feature = "dbus")]
+        #[test]
+        fn relay_stamps_frames_with_own_sequence_nr() {
+            let transport = CameraTransport::simulated();
+            transport
+                .send_cmd02(&SleepCommand::build(SleepMode::Awake).unwrap(), false)
+                .unwrap();
+            let mut frame = [0u8; 60];
+            frame[..36].copy_from_slice(&SleepCommand::build(SleepMode::Sleep).unwrap());
+            crate::set_sequence_nr(frame.first_chunk_mut().unwrap(), 0x0042);
+
+            assert_eq!(transport.relay(0x2, 0x2, UVC_SET_CUR, &mut frame), Ok(60));
+            assert_eq!(transport.get_frame02().unwrap().sequence_nr, 0x0001);
+        }
     }
 }
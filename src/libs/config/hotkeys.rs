@@ -0,0 +1,83 @@
+// SPDX-License-Identifier: EUPL-1.2
+
+use rust_i18n::t;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+/// An action that can be bound to a global keyboard shortcut, see [`Settings::hotkeys`](crate::Settings::hotkeys).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HotkeyAction {
+    ToggleSleep,
+    NextAiMode,
+    PreviousAiMode,
+    #[serde(rename = "preset_1")]
+    Preset1,
+    #[serde(rename = "preset_2")]
+    Preset2,
+    #[serde(rename = "preset_3")]
+    Preset3,
+    ToggleHdr,
+}
+
+impl HotkeyAction {
+    pub const ALL: [HotkeyAction; 7] = [
+        HotkeyAction::ToggleSleep,
+        HotkeyAction::NextAiMode,
+        HotkeyAction::PreviousAiMode,
+        HotkeyAction::Preset1,
+        HotkeyAction::Preset2,
+        HotkeyAction::Preset3,
+        HotkeyAction::ToggleHdr,
+    ];
+
+    /// The stable id, as used in the configuration file, e.g. `toggle_sleep`.
+    pub fn id(&self) -> &'static str {
+        match self {
+            HotkeyAction::ToggleSleep => "toggle_sleep",
+            HotkeyAction::NextAiMode => "next_ai_mode",
+            HotkeyAction::PreviousAiMode => "previous_ai_mode",
+            HotkeyAction::Preset1 => "preset_1",
+            HotkeyAction::Preset2 => "preset_2",
+            HotkeyAction::Preset3 => "preset_3",
+            HotkeyAction::ToggleHdr => "toggle_hdr",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.id() == id)
+    }
+
+    /// Describes the action for the user, e.g. in the shortcut settings of the desktop.
+    pub fn description(&self) -> Cow<'static, str> {
+        match self {
+            HotkeyAction::ToggleSleep => t!("display.hotkey_action.toggle_sleep"),
+            HotkeyAction::NextAiMode => t!("display.hotkey_action.next_ai_mode"),
+            HotkeyAction::PreviousAiMode => t!("display.hotkey_action.previous_ai_mode"),
+            HotkeyAction::Preset1 => t!("display.hotkey_action.preset", preset_number = 1),
+            HotkeyAction::Preset2 => t!("display.hotkey_action.preset", preset_number = 2),
+            HotkeyAction::Preset3 => t!("display.hotkey_action.preset", preset_number = 3),
+            HotkeyAction::ToggleHdr => t!("display.hotkey_action.toggle_hdr"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod unit {
+        use crate::HotkeyAction;
+
+        #[test]
+        fn ids_match_config() {
+            for action in HotkeyAction::ALL {
+                assert_eq!(serde_json::to_value(action).unwrap(), action.id());
+                assert_eq!(HotkeyAction::from_id(action.id()), Some(action));
+            }
+        }
+
+        #[test]
+        fn unknown_id() {
+            assert_eq!(HotkeyAction::from_id("toggle_zoom"), None);
+        }
+    }
+}
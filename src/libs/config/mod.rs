@@ -0,0 +1,111 @@
+// SPDX-License-Identifier: EUPL-1.2
+
+mod hotkeys;
+mod offset_labels;
+mod persisted;
+mod profiles;
+mod settings;
+
+use crate::libs::errors::T4lError;
+use serde::de::{DeserializeOwned, Error, Unexpected};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::env;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+pub use hotkeys::HotkeyAction;
+pub use offset_labels::OffsetLabels;
+pub use persisted::{
+    load_persisted_settings, persisted_settings_path, record_persisted_settings,
+    restore_persisted_settings,
+};
+pub use profiles::{Profile, Profiles};
+pub use settings::Settings;
+
+/// Directory of the configuration files, `$XDG_CONFIG_HOME/tiny4linux` or `~/.config/tiny4linux`.
+pub fn config_dir() -> PathBuf {
+    let base = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .unwrap_or_default();
+
+    base.join("tiny4linux")
+}
+
+/// Reads a preset counted from 1 as in the CLI and the GUI, so that a preset 0 is already refused
+/// when the file is loaded.
+pub(crate) fn deserialize_preset_nr<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<i8, D::Error> {
+    let preset_nr = i8::deserialize(deserializer)?;
+    if preset_nr < 1 {
+        return Err(D::Error::invalid_value(
+            Unexpected::Signed(preset_nr.into()),
+            &"a preset counted from 1",
+        ));
+    }
+
+    Ok(preset_nr)
+}
+
+/// The id of a preset counted from 1, as the camera counts them from 0.
+pub(crate) fn preset_id(preset_nr: i8) -> Result<i8, T4lError> {
+    if preset_nr < 1 {
+        return Err(T4lError::InvalidSetting);
+    }
+
+    Ok(preset_nr - 1)
+}
+
+/// Reads a TOML file, which is empty if it doesn't exist yet.
+pub(crate) fn load_toml<T: DeserializeOwned + Default>(path: &Path) -> Result<T, T4lError> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == ErrorKind::NotFound => return Ok(T::default()),
+        Err(error) => return Err(error.into()),
+    };
+
+    toml::from_str(&contents).map_err(|error| {
+        T4lError::InvalidConfig(path.display().to_string(), error.message().to_string())
+    })
+}
+
+pub(crate) fn save_toml<T: Serialize>(path: &Path, value: &T) -> Result<(), T4lError> {
+    let contents = toml::to_string(value)
+        .map_err(|error| T4lError::InvalidConfig(path.display().to_string(), error.to_string()))?;
+
+    write_atomically(path, &contents)
+}
+
+/// Writes the file through a temporary file next to it, so that it's never left half-written.
+pub(crate) fn write_atomically(path: &Path, contents: &str) -> Result<(), T4lError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let temporary = path.with_extension("toml.tmp");
+    fs::write(&temporary, contents)?;
+    fs::rename(&temporary, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    mod unit {
+        use super::super::write_atomically;
+        use std::fs;
+
+        #[test]
+        fn writes_file_and_directories() {
+            let dir = tempfile::tempdir().unwrap();
+            let path = dir.path().join("nested").join("profiles.toml");
+
+            write_atomically(&path, "[call]\n").unwrap();
+
+            assert_eq!(fs::read_to_string(&path).unwrap(), "[call]\n");
+            assert!(!path.with_extension("toml.tmp").exists());
+        }
+    }
+}
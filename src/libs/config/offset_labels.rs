@@ -0,0 +1,167 @@
+// SPDX-License-Identifier: EUPL-1.2
+
+use crate::libs::camera::{known_offset, parse_hex};
+use crate::libs::config::{config_dir, load_toml, save_toml};
+use crate::libs::errors::T4lError;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// The labels as written in the file, by selector and offset in hex.
+type LabelTable = BTreeMap<String, BTreeMap<String, String>>;
+
+/// Labels of offsets in the raw data of the selectors found while reverse-engineering,
+/// stored in `offsets.toml` in the [`config_dir`], e.g.:
+///
+/// ```toml
+/// [0x06]
+/// 0x22 = "gesture zoom"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "LabelTable", into = "LabelTable")]
+pub struct OffsetLabels {
+    labels: BTreeMap<(u8, usize), String>,
+}
+
+impl OffsetLabels {
+    pub fn path() -> PathBuf {
+        config_dir().join("offsets.toml")
+    }
+
+    /// Loads the labels from the default path, see [`OffsetLabels::path`].
+    pub fn load() -> Result<Self, T4lError> {
+        Self::load_from(&Self::path())
+    }
+
+    /// Loads the labels from the file, which has none if it doesn't exist yet.
+    pub fn load_from(path: &Path) -> Result<Self, T4lError> {
+        load_toml(path)
+    }
+
+    /// Saves the labels to the default path, see [`OffsetLabels::path`].
+    pub fn save(&self) -> Result<(), T4lError> {
+        self.save_to(&Self::path())
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<(), T4lError> {
+        save_toml(path, self)
+    }
+
+    pub fn get(&self, selector: u8, offset: usize) -> Option<&str> {
+        self.labels.get(&(selector, offset)).map(String::as_str)
+    }
+
+    /// Labels the offset, replacing its previous label.
+    pub fn insert(&mut self, selector: u8, offset: usize, label: &str) {
+        self.labels.insert((selector, offset), label.to_string());
+    }
+
+    pub fn remove(&mut self, selector: u8, offset: usize) -> Option<String> {
+        self.labels.remove(&(selector, offset))
+    }
+
+    /// The label of the offset, or what is known about it if it has none, see [`known_offset`].
+    pub fn describe(&self, selector: u8, offset: usize) -> Option<Cow<'_, str>> {
+        self.get(selector, offset)
+            .map(Cow::Borrowed)
+            .or_else(|| known_offset(selector, offset))
+    }
+
+    /// The labels by selector and offset.
+    pub fn iter(&self) -> impl Iterator<Item = (u8, usize, &str)> {
+        self.labels
+            .iter()
+            .map(|((selector, offset), label)| (*selector, *offset, label.as_str()))
+    }
+}
+
+impl TryFrom<LabelTable> for OffsetLabels {
+    type Error = String;
+
+    fn try_from(table: LabelTable) -> Result<Self, Self::Error> {
+        let mut labels = BTreeMap::new();
+
+        for (selector, offsets) in table {
+            let selector =
+                parse_hex(&selector).ok_or_else(|| format!("invalid selector `{}`", selector))?;
+            for (offset, label) in offsets {
+                let offset =
+                    parse_hex(&offset).ok_or_else(|| format!("invalid offset `{}`", offset))?;
+                labels.insert((selector, offset), label);
+            }
+        }
+
+        Ok(Self { labels })
+    }
+}
+
+impl From<OffsetLabels> for LabelTable {
+    fn from(labels: OffsetLabels) -> Self {
+        let mut table = LabelTable::new();
+
+        for ((selector, offset), label) in labels.labels {
+            table
+                .entry(format!("{:#04x}", selector))
+                .or_default()
+                .insert(format!("{:#04x}", offset), label);
+        }
+
+        table
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod unit {
+        use crate::OffsetLabels;
+
+        #[test]
+        fn parses_labels() {
+            let labels: OffsetLabels = toml::from_str(
+                r#"
+                [0x06]
+                0x22 = "gesture zoom"
+                "2a" = "battery"
+                "#,
+            )
+            .unwrap();
+
+            assert_eq!(labels.get(0x06, 0x22), Some("gesture zoom"));
+            assert_eq!(labels.get(0x06, 0x2a), Some("battery"));
+            assert_eq!(labels.get(0x02, 0x22), None);
+        }
+
+        #[test]
+        fn rejects_invalid_offset() {
+            assert!(toml::from_str::<OffsetLabels>("[0x06]\nzoom = \"zoom\"\n").is_err());
+        }
+
+        #[test]
+        fn prefers_label_over_known_offset() {
+            let mut labels = OffsetLabels::default();
+            labels.insert(0x06, 0x06, "hdr and more");
+
+            assert_eq!(labels.describe(0x06, 0x06).as_deref(), Some("hdr and more"));
+            assert_eq!(labels.describe(0x06, 0x02).as_deref(), Some("sleep mode"));
+            assert_eq!(labels.describe(0x06, 0x05), None);
+        }
+    }
+
+    mod integration {
+        use crate::OffsetLabels;
+
+        #[test]
+        fn saves_and_loads_labels() {
+            let dir = tempfile::tempdir().unwrap();
+            let path = dir.path().join("offsets.toml");
+            let mut labels = OffsetLabels::default();
+            labels.insert(0x06, 0x22, "gesture zoom");
+            labels.insert(0x02, 0x24, "appendix end");
+
+            labels.save_to(&path).unwrap();
+
+            assert_eq!(OffsetLabels::load_from(&path).unwrap(), labels);
+        }
+    }
+}
@@ -0,0 +1,92 @@
+// SPDX-License-Identifier: EUPL-1.2
+
+use crate::libs::camera::Tiny2Camera;
+use crate::libs::config::{Profile, config_dir, load_toml, save_toml};
+use crate::libs::errors::T4lError;
+use std::path::{Path, PathBuf};
+
+/// File the settings applied last are recorded in, if [`Settings::persist_settings`](crate::Settings::persist_settings) is on.
+pub fn persisted_settings_path() -> PathBuf {
+    config_dir().join("persisted.toml")
+}
+
+/// Loads the settings recorded last in the file, which are none if nothing was recorded yet.
+pub fn load_persisted_settings(path: &Path) -> Result<Profile, T4lError> {
+    load_toml(path)
+}
+
+/// Records settings that were applied in the file, keeping the ones recorded before that aren't
+/// changed.
+///
+/// Only settings the camera forgets on a reconnect are meant to be recorded, not its sleep mode
+/// or position, as these depend on the situation the camera reconnects in.
+/// Only the GUI records the settings it applies, changes made with the CLI, the daemon or on the
+/// camera itself aren't recorded.
+pub fn record_persisted_settings(
+    path: &Path,
+    update: impl FnOnce(&mut Profile),
+) -> Result<(), T4lError> {
+    let mut settings = load_persisted_settings(path)?;
+    update(&mut settings);
+    save_toml(path, &settings)
+}
+
+/// Applies the settings recorded in the file again, e.g. once the camera reconnected.
+pub fn restore_persisted_settings(path: &Path, camera: &impl Tiny2Camera) -> Result<(), T4lError> {
+    load_persisted_settings(path)?.apply(camera)
+}
+
+#[cfg(test)]
+mod tests {
+    mod integration {
+        use crate::{
+            AIMode, Camera, ExposureMode, Tiny2Camera, TrackingSpeed, load_persisted_settings,
+            record_persisted_settings, restore_persisted_settings,
+        };
+
+        #[test]
+        fn nothing_recorded_yet() {
+            let dir = tempfile::tempdir().unwrap();
+
+            let settings = load_persisted_settings(&dir.path().join("persisted.toml")).unwrap();
+
+            assert_eq!(settings, Default::default());
+        }
+
+        #[test]
+        fn keeps_settings_recorded_before() {
+            let dir = tempfile::tempdir().unwrap();
+            let path = dir.path().join("persisted.toml");
+
+            record_persisted_settings(&path, |settings| settings.hdr = Some(true)).unwrap();
+            record_persisted_settings(&path, |settings| {
+                settings.exposure_mode = Some(ExposureMode::Face)
+            })
+            .unwrap();
+
+            let settings = load_persisted_settings(&path).unwrap();
+            assert_eq!(settings.hdr, Some(true));
+            assert_eq!(settings.exposure_mode, Some(ExposureMode::Face));
+        }
+
+        #[test]
+        fn restores_recorded_settings() {
+            let dir = tempfile::tempdir().unwrap();
+            let path = dir.path().join("persisted.toml");
+            let camera = Camera::simulated();
+            record_persisted_settings(&path, |settings| {
+                settings.ai_mode = Some(AIMode::Group);
+                settings.tracking_speed = Some(TrackingSpeed::Standard);
+            })
+            .unwrap();
+
+            restore_persisted_settings(&path, &camera).unwrap();
+
+            assert_eq!(camera.get_ai_mode().unwrap(), AIMode::Group);
+            assert_eq!(
+                camera.get_tracking_speed().unwrap(),
+                TrackingSpeed::Standard
+            );
+        }
+    }
+}
@@ -0,0 +1,415 @@
+// SPDX-License-Identifier: EUPL-1.2
+
+use crate::libs::camera::{AIMode, ExposureMode, SleepMode, Tiny2Camera, TrackingSpeed};
+use crate::libs::config::{config_dir, deserialize_preset_nr, load_toml, preset_id, save_toml};
+use crate::libs::errors::T4lError;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// A named set of settings, e.g. `presentation` or `call`, that is applied in one go.
+///
+/// Settings that are left out keep their current value. The values are the stable ids also used
+/// for machine-readable output, e.g.:
+///
+/// ```toml
+/// [presentation]
+/// ai_mode = "whiteboard"
+/// hdr = false
+/// tracking_speed = "sport"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sleep_mode: Option<SleepMode>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ai_mode: Option<AIMode>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tracking_speed: Option<TrackingSpeed>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hdr: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exposure_mode: Option<ExposureMode>,
+    /// Preset position the gimbal moves to, counted from 1 as in the CLI and the GUI.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_preset",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub preset: Option<i8>,
+}
+
+impl Profile {
+    /// Reads the current settings of the camera, leaving out the ones it reports as unknown.
+    ///
+    /// The position of the gimbal isn't known as a preset and the exposure mode isn't decoded from
+    /// the status yet, so they're never included.
+    pub fn current(camera: &impl Tiny2Camera) -> Result<Self, T4lError> {
+        Ok(Self {
+            sleep_mode: Some(camera.get_sleep_mode()?).filter(|mode| *mode != SleepMode::Unknown),
+            ai_mode: Some(camera.get_ai_mode()?).filter(|mode| *mode != AIMode::Unknown),
+            tracking_speed: Some(camera.get_tracking_speed()?),
+            hdr: Some(camera.get_hdr_mode()?),
+            exposure_mode: None,
+            preset: None,
+        })
+    }
+
+    /// Applies all settings of the profile, or none of them.
+    ///
+    /// If a setting fails, the ones applied before are reverted to their previous value.
+    /// A preset the gimbal moved to can't be reverted, so it's applied after the other settings.
+    /// The exposure mode can't be read, so it's kept as applied.
+    pub fn apply(&self, camera: &impl Tiny2Camera) -> Result<(), T4lError> {
+        let previous = Self::current(camera)?;
+        let mut applied = Self::default();
+
+        if let Err(error) = self.apply_each(camera, &mut applied) {
+            let _ = previous
+                .only(&applied)
+                .apply_each(camera, &mut Self::default());
+            return Err(error);
+        }
+
+        Ok(())
+    }
+
+    /// Applies the settings one by one, noting each one that succeeded in `applied`.
+    ///
+    /// The camera is woken up first and put to sleep last, as it ignores most commands while asleep.
+    fn apply_each(&self, camera: &impl Tiny2Camera, applied: &mut Self) -> Result<(), T4lError> {
+        if let Some(mode @ SleepMode::Awake) = self.sleep_mode {
+            camera.set_sleep_mode(mode)?;
+            applied.sleep_mode = Some(mode);
+        }
+        if let Some(mode) = self.ai_mode {
+            camera.set_ai_mode(mode)?;
+            applied.ai_mode = Some(mode);
+        }
+        if let Some(speed) = self.tracking_speed {
+            camera.set_tracking_speed(speed)?;
+            applied.tracking_speed = Some(speed);
+        }
+        if let Some(hdr_on) = self.hdr {
+            camera.set_hdr_mode(hdr_on)?;
+            applied.hdr = Some(hdr_on);
+        }
+        if let Some(mode) = self.exposure_mode {
+            camera.set_exposure_mode(mode)?;
+            applied.exposure_mode = Some(mode);
+        }
+        if let Some(preset_nr) = self.preset {
+            camera.goto_preset_position(preset_id(preset_nr)?)?;
+            applied.preset = Some(preset_nr);
+        }
+        if let Some(mode @ SleepMode::Sleep) = self.sleep_mode {
+            camera.set_sleep_mode(mode)?;
+            applied.sleep_mode = Some(mode);
+        }
+        Ok(())
+    }
+
+    /// Keeps only the settings that are also set in the other profile.
+    fn only(&self, other: &Self) -> Self {
+        Self {
+            sleep_mode: self.sleep_mode.filter(|_| other.sleep_mode.is_some()),
+            ai_mode: self.ai_mode.filter(|_| other.ai_mode.is_some()),
+            tracking_speed: self
+                .tracking_speed
+                .filter(|_| other.tracking_speed.is_some()),
+            hdr: self.hdr.filter(|_| other.hdr.is_some()),
+            exposure_mode: self.exposure_mode.filter(|_| other.exposure_mode.is_some()),
+            preset: self.preset.filter(|_| other.preset.is_some()),
+        }
+    }
+}
+
+fn deserialize_preset<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<i8>, D::Error> {
+    deserialize_preset_nr(deserializer).map(Some)
+}
+
+/// The profiles stored in `profiles.toml` in the [`config_dir`], ordered by name.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Profiles {
+    profiles: BTreeMap<String, Profile>,
+}
+
+impl Profiles {
+    pub fn path() -> PathBuf {
+        config_dir().join("profiles.toml")
+    }
+
+    /// Loads the profiles from the default path, see [`Profiles::path`].
+    pub fn load() -> Result<Self, T4lError> {
+        Self::load_from(&Self::path())
+    }
+
+    /// Loads the profiles from the file, which has none if it doesn't exist yet.
+    pub fn load_from(path: &Path) -> Result<Self, T4lError> {
+        load_toml(path)
+    }
+
+    /// Saves the profiles to the default path, see [`Profiles::path`].
+    pub fn save(&self) -> Result<(), T4lError> {
+        self.save_to(&Self::path())
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<(), T4lError> {
+        save_toml(path, self)
+    }
+
+    pub fn get(&self, name: &str) -> Result<&Profile, T4lError> {
+        self.profiles
+            .get(name)
+            .ok_or_else(|| T4lError::UnknownProfile(name.to_string()))
+    }
+
+    /// Adds the profile, replacing one of the same name.
+    pub fn insert(&mut self, name: &str, profile: Profile) {
+        self.profiles.insert(name.to_string(), profile);
+    }
+
+    pub fn remove(&mut self, name: &str) -> Result<Profile, T4lError> {
+        self.profiles
+            .remove(name)
+            .ok_or_else(|| T4lError::UnknownProfile(name.to_string()))
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.profiles.keys().cloned().collect()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Profile)> {
+        self.profiles.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod unit {
+        use crate::{AIMode, ExposureMode, Profile, Profiles, SleepMode, TrackingSpeed};
+        use test_case::test_case;
+
+        fn presentation() -> Profile {
+            Profile {
+                ai_mode: Some(AIMode::Whiteboard),
+                tracking_speed: Some(TrackingSpeed::Sport),
+                hdr: Some(false),
+                ..Profile::default()
+            }
+        }
+
+        #[test]
+        fn parses_profiles() {
+            let profiles: Profiles = toml::from_str(
+                r#"
+                [presentation]
+                ai_mode = "whiteboard"
+                hdr = false
+                tracking_speed = "sport"
+
+                [call]
+                ai_mode = "upper_body"
+                exposure_mode = "face"
+
+                [away]
+                sleep_mode = "sleep"
+                "#,
+            )
+            .unwrap();
+
+            assert_eq!(profiles.names(), vec!["away", "call", "presentation"]);
+            assert_eq!(profiles.get("presentation").unwrap(), &presentation());
+            assert_eq!(
+                profiles.get("call").unwrap().exposure_mode,
+                Some(ExposureMode::Face)
+            );
+            assert_eq!(
+                profiles.get("away").unwrap().sleep_mode,
+                Some(SleepMode::Sleep)
+            );
+        }
+
+        #[test]
+        fn parses_preset() {
+            let profiles: Profiles = toml::from_str("[left]\npreset = 2\n").unwrap();
+
+            assert_eq!(profiles.get("left").unwrap().preset, Some(2));
+        }
+
+        #[test_case(0; "zero")]
+        #[test_case(-1; "negative")]
+        fn rejects_preset_not_counted_from_1(preset: i8) {
+            let error =
+                toml::from_str::<Profiles>(&format!("[left]\npreset = {}\n", preset)).unwrap_err();
+
+            assert!(error.message().contains("a preset counted from 1"));
+        }
+
+        #[test]
+        fn rejects_unknown_settings() {
+            assert!(toml::from_str::<Profiles>("[call]\nvolume = 3\n").is_err());
+        }
+
+        #[test]
+        fn unknown_profile() {
+            let error = Profiles::default().get("call").unwrap_err();
+            assert_eq!(error.code(), "unknown_profile");
+        }
+
+        #[test]
+        fn only_keeps_settings_of_other() {
+            let applied = Profile {
+                ai_mode: Some(AIMode::Group),
+                ..Profile::default()
+            };
+
+            assert_eq!(
+                presentation().only(&applied),
+                Profile {
+                    ai_mode: Some(AIMode::Whiteboard),
+                    ..Profile::default()
+                }
+            );
+        }
+    }
+
+    mod integration {
+        use crate::{
+            AIMode, Camera, ControlRange, ExposureMode, PanTilt, Profile, Profiles, SleepMode,
+            T4lError, Tiny2Camera, TrackingSpeed,
+        };
+
+        /// The simulated camera, except that setting the exposure mode fails as if it was busy.
+        struct ExposureFails(Camera);
+
+        impl Tiny2Camera for ExposureFails {
+            fn set_sleep_mode(&self, mode: SleepMode) -> Result<(), T4lError> {
+                self.0.set_sleep_mode(mode)
+            }
+            fn get_sleep_mode(&self) -> Result<SleepMode, T4lError> {
+                self.0.get_sleep_mode()
+            }
+            fn set_ai_mode(&self, mode: AIMode) -> Result<(), T4lError> {
+                self.0.set_ai_mode(mode)
+            }
+            fn get_ai_mode(&self) -> Result<AIMode, T4lError> {
+                self.0.get_ai_mode()
+            }
+            fn goto_preset_position(&self, preset_nr: i8) -> Result<(), T4lError> {
+                self.0.goto_preset_position(preset_nr)
+            }
+            #[cfg(feature = "experimental")]
+            fn save_preset_position(&self, preset_nr: i8) -> Result<(), T4lError> {
+                self.0.save_preset_position(preset_nr)
+            }
+            fn get_tracking_speed(&self) -> Result<TrackingSpeed, T4lError> {
+                self.0.get_tracking_speed()
+            }
+            fn set_tracking_speed(&self, speed: TrackingSpeed) -> Result<(), T4lError> {
+                self.0.set_tracking_speed(speed)
+            }
+            fn set_hdr_mode(&self, mode: bool) -> Result<(), T4lError> {
+                self.0.set_hdr_mode(mode)
+            }
+            fn get_hdr_mode(&self) -> Result<bool, T4lError> {
+                self.0.get_hdr_mode()
+            }
+            fn set_exposure_mode(&self, _mode: ExposureMode) -> Result<(), T4lError> {
+                Err(T4lError::CameraBusy)
+            }
+            fn get_zoom(&self) -> Result<i32, T4lError> {
+                self.0.get_zoom()
+            }
+            fn get_zoom_range(&self) -> Result<ControlRange, T4lError> {
+                self.0.get_zoom_range()
+            }
+            fn set_zoom(&self, zoom: i32) -> Result<(), T4lError> {
+                self.0.set_zoom(zoom)
+            }
+            fn get_pan_tilt(&self) -> Result<(f64, f64), T4lError> {
+                self.0.get_pan_tilt()
+            }
+            fn set_pan_tilt(&self, movement: PanTilt) -> Result<(), T4lError> {
+                self.0.set_pan_tilt(movement)
+            }
+            fn set_debugging(&mut self, debugging: bool) {
+                self.0.set_debugging(debugging)
+            }
+        }
+
+        #[test]
+        fn saves_and_loads_profiles() {
+            let dir = tempfile::tempdir().unwrap();
+            let path = dir.path().join("profiles.toml");
+            let mut profiles = Profiles::default();
+            profiles.insert(
+                "away",
+                Profile {
+                    sleep_mode: Some(SleepMode::Sleep),
+                    ..Profile::default()
+                },
+            );
+
+            profiles.save_to(&path).unwrap();
+
+            assert_eq!(Profiles::load_from(&path).unwrap(), profiles);
+        }
+
+        #[test]
+        fn missing_file_has_no_profiles() {
+            let dir = tempfile::tempdir().unwrap();
+            let profiles = Profiles::load_from(&dir.path().join("profiles.toml")).unwrap();
+
+            assert!(profiles.names().is_empty());
+        }
+
+        #[test]
+        fn invalid_file() {
+            let dir = tempfile::tempdir().unwrap();
+            let path = dir.path().join("profiles.toml");
+            std::fs::write(&path, "[call\n").unwrap();
+
+            let error = Profiles::load_from(&path).unwrap_err();
+            assert_eq!(error.code(), "invalid_config");
+        }
+
+        #[test]
+        fn applies_profile() {
+            let camera = Camera::simulated();
+            let profile = Profile {
+                ai_mode: Some(AIMode::Whiteboard),
+                tracking_speed: Some(TrackingSpeed::Standard),
+                hdr: Some(false),
+                exposure_mode: Some(ExposureMode::Face),
+                ..Profile::default()
+            };
+
+            profile.apply(&camera).unwrap();
+
+            let current = Profile::current(&camera).unwrap();
+            assert_eq!(current.ai_mode, Some(AIMode::Whiteboard));
+            assert_eq!(current.tracking_speed, Some(TrackingSpeed::Standard));
+            assert_eq!(current.hdr, Some(false));
+        }
+
+        #[test]
+        fn reverts_profile_that_fails() {
+            let camera = ExposureFails(Camera::simulated());
+            let before = Profile::current(&camera).unwrap();
+            let profile = Profile {
+                ai_mode: Some(AIMode::Whiteboard),
+                hdr: Some(!before.hdr.unwrap()),
+                exposure_mode: Some(ExposureMode::Face),
+                ..Profile::default()
+            };
+
+            assert!(matches!(profile.apply(&camera), Err(T4lError::CameraBusy)));
+
+            assert_eq!(camera.get_ai_mode().unwrap(), before.ai_mode.unwrap());
+            assert_eq!(Profile::current(&camera).unwrap(), before);
+        }
+    }
+}
@@ -0,0 +1,116 @@
+// SPDX-License-Identifier: EUPL-1.2
+
+use crate::libs::config::{HotkeyAction, config_dir, load_toml, save_toml};
+use crate::libs::errors::T4lError;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// The options of Tiny4Linux itself, stored in `config.toml` in the [`config_dir`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Settings {
+    /// Records the settings applied last and applies them again once the camera reconnects,
+    /// as it forgets some of them after a USB reset or suspend, see [`record_persisted_settings`](crate::record_persisted_settings).
+    pub persist_settings: bool,
+    /// Global keyboard shortcuts of the GUI, as triggers of the XDG shortcuts specification
+    /// the desktop is asked for, e.g.:
+    ///
+    /// ```toml
+    /// [hotkeys]
+    /// toggle_sleep = "CTRL+ALT+S"
+    /// next_ai_mode = "CTRL+ALT+Right"
+    /// ```
+    ///
+    /// The desktop may ask the user to confirm them or let them choose others.
+    pub hotkeys: BTreeMap<HotkeyAction, String>,
+}
+
+impl Settings {
+    pub fn path() -> PathBuf {
+        config_dir().join("config.toml")
+    }
+
+    /// Loads the settings from the default path, see [`Settings::path`].
+    pub fn load() -> Result<Self, T4lError> {
+        Self::load_from(&Self::path())
+    }
+
+    /// Loads the settings from the file, which has the defaults if it doesn't exist yet.
+    pub fn load_from(path: &Path) -> Result<Self, T4lError> {
+        load_toml(path)
+    }
+
+    /// Saves the settings to the default path, see [`Settings::path`].
+    pub fn save(&self) -> Result<(), T4lError> {
+        self.save_to(&Self::path())
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<(), T4lError> {
+        save_toml(path, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod unit {
+        use crate::{HotkeyAction, Settings};
+
+        #[test]
+        fn parses_hotkeys() {
+            let settings: Settings = toml::from_str(
+                r#"
+                [hotkeys]
+                toggle_sleep = "CTRL+ALT+S"
+                preset_2 = "CTRL+ALT+2"
+                "#,
+            )
+            .unwrap();
+
+            assert_eq!(
+                settings.hotkeys.get(&HotkeyAction::Preset2).unwrap(),
+                "CTRL+ALT+2"
+            );
+            assert_eq!(settings.hotkeys.len(), 2);
+        }
+
+        #[test]
+        fn rejects_unknown_hotkey() {
+            assert!(
+                toml::from_str::<Settings>(
+                    "[hotkeys]
+toggle_zoom = \"Z\"
+"
+                )
+                .is_err()
+            );
+        }
+    }
+
+    mod integration {
+        use crate::{HotkeyAction, Settings};
+        use std::collections::BTreeMap;
+
+        #[test]
+        fn defaults_without_file() {
+            let dir = tempfile::tempdir().unwrap();
+            let settings = Settings::load_from(&dir.path().join("config.toml")).unwrap();
+
+            assert!(!settings.persist_settings);
+        }
+
+        #[test]
+        fn saves_and_loads_settings() {
+            let dir = tempfile::tempdir().unwrap();
+            let path = dir.path().join("config.toml");
+            let settings = Settings {
+                persist_settings: true,
+                hotkeys: BTreeMap::from([(HotkeyAction::ToggleSleep, "CTRL+ALT+S".to_string())]),
+            };
+
+            settings.save_to(&path).unwrap();
+
+            assert_eq!(Settings::load_from(&path).unwrap(), settings);
+        }
+    }
+}
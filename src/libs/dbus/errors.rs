@@ -0,0 +1,40 @@
+// SPDX-License-Identifier: EUPL-1.2
+
+use crate::libs::errors::T4lError;
+use zbus::{DBusError, fdo};
+
+/// Errors the daemon replies with, named `io.github.openfoxes.Tiny4Linux.Error.<Variant>`.
+#[derive(DBusError, Debug)]
+#[zbus(prefix = "io.github.openfoxes.Tiny4Linux.Error")]
+pub enum DaemonError {
+    #[zbus(error)]
+    ZBus(zbus::Error),
+    /// A request to the camera failed, the message is the errno.
+    Errno(String),
+    /// The value isn't supported by the camera or unknown, e.g. an unknown tracking mode.
+    InvalidValue(String),
+    /// Any other error of the camera.
+    Failed(String),
+}
+
+impl From<T4lError> for DaemonError {
+    fn from(error: T4lError) -> Self {
+        match error {
+            T4lError::UnsupportedIntValue(_, _) | T4lError::InvalidSetting => {
+                DaemonError::InvalidValue(error.to_string())
+            }
+            _ => DaemonError::Failed(error.to_string()),
+        }
+    }
+}
+
+impl From<T4lError> for fdo::Error {
+    fn from(error: T4lError) -> Self {
+        match error {
+            T4lError::UnsupportedIntValue(_, _) | T4lError::InvalidSetting => {
+                fdo::Error::InvalidArgs(error.to_string())
+            }
+            _ => fdo::Error::Failed(error.to_string()),
+        }
+    }
+}
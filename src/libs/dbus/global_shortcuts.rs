@@ -0,0 +1,193 @@
+// SPDX-License-Identifier: EUPL-1.2
+
+use std::collections::HashMap;
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread::{self, JoinHandle};
+use zbus::blocking::Connection;
+use zbus::proxy;
+use zbus::zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, Value};
+
+/// Counts the requests to the portal, as each one needs its own token.
+static NEXT_TOKEN: AtomicUsize = AtomicUsize::new(0);
+
+#[proxy(
+    interface = "org.freedesktop.portal.GlobalShortcuts",
+    default_service = "org.freedesktop.portal.Desktop",
+    default_path = "/org/freedesktop/portal/desktop",
+    gen_async = false,
+    blocking_name = "GlobalShortcutsPortalProxy"
+)]
+trait GlobalShortcutsPortal {
+    fn create_session(&self, options: HashMap<&str, Value<'_>>) -> zbus::Result<OwnedObjectPath>;
+
+    fn bind_shortcuts(
+        &self,
+        session_handle: &ObjectPath<'_>,
+        shortcuts: &[(&str, HashMap<&str, Value<'_>>)],
+        parent_window: &str,
+        options: HashMap<&str, Value<'_>>,
+    ) -> zbus::Result<OwnedObjectPath>;
+
+    #[zbus(signal)]
+    fn activated(
+        &self,
+        session_handle: ObjectPath<'_>,
+        shortcut_id: &str,
+        timestamp: u64,
+        options: HashMap<&str, Value<'_>>,
+    ) -> zbus::Result<()>;
+}
+
+#[proxy(
+    interface = "org.freedesktop.portal.Request",
+    default_service = "org.freedesktop.portal.Desktop",
+    gen_async = false,
+    blocking_name = "PortalRequestProxy"
+)]
+trait PortalRequest {
+    #[zbus(signal)]
+    fn response(&self, response: u32, results: HashMap<String, OwnedValue>) -> zbus::Result<()>;
+}
+
+/// A shortcut to be bound with [`GlobalShortcuts::bind`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlobalShortcut {
+    pub id: String,
+    pub description: String,
+    /// Trigger as in the XDG shortcuts specification, e.g. `CTRL+ALT+S`.
+    pub preferred_trigger: Option<String>,
+}
+
+/// Keyboard shortcuts that work while another application is focused, bound through the
+/// GlobalShortcuts portal of the desktop, so that they work with Wayland as well as X11.
+pub struct GlobalShortcuts {
+    connection: Connection,
+    session: OwnedObjectPath,
+}
+
+impl GlobalShortcuts {
+    /// Binds the shortcuts for as long as the returned value lives.
+    ///
+    /// The desktop may ask the user to confirm the shortcuts or to choose other triggers first,
+    /// so this can block for a while.
+    pub fn bind(shortcuts: &[GlobalShortcut]) -> zbus::Result<Self> {
+        let connection = Connection::session()?;
+        let portal = GlobalShortcutsPortalProxy::new(&connection)?;
+
+        let response = request(&connection, |token| {
+            portal.create_session(HashMap::from([
+                ("handle_token", Value::from(token)),
+                ("session_handle_token", Value::from(token)),
+            ]))
+        })?;
+        let args = response.args()?;
+        let session = match args.results().get("session_handle").map(|value| &**value) {
+            Some(Value::Str(handle)) => OwnedObjectPath::try_from(handle.as_str())?,
+            Some(Value::ObjectPath(handle)) => handle.clone().into(),
+            _ => return Err(zbus::Error::Failure("no session handle".to_string())),
+        };
+
+        let shortcuts: Vec<(&str, HashMap<&str, Value>)> = shortcuts
+            .iter()
+            .map(|shortcut| {
+                let mut properties =
+                    HashMap::from([("description", Value::from(shortcut.description.as_str()))]);
+                if let Some(trigger) = &shortcut.preferred_trigger {
+                    properties.insert("preferred_trigger", Value::from(trigger.as_str()));
+                }
+                (shortcut.id.as_str(), properties)
+            })
+            .collect();
+        request(&connection, |token| {
+            portal.bind_shortcuts(
+                &session,
+                &shortcuts,
+                "",
+                HashMap::from([("handle_token", Value::from(token))]),
+            )
+        })?;
+
+        Ok(Self {
+            connection,
+            session,
+        })
+    }
+
+    /// Calls back with the id of each shortcut that is pressed, until the callback returns
+    /// `false` or the connection is closed.
+    pub fn spawn(
+        self,
+        mut callback: impl FnMut(&str) -> bool + Send + 'static,
+    ) -> JoinHandle<zbus::Result<()>> {
+        thread::spawn(move || {
+            let portal = GlobalShortcutsPortalProxy::new(&self.connection)?;
+
+            for signal in portal.receive_activated()? {
+                let args = signal.args()?;
+                if args.session_handle().as_str() == self.session.as_str()
+                    && !callback(args.shortcut_id())
+                {
+                    break;
+                }
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Calls a method of the portal and waits for the response it sends to the request object.
+fn request(
+    connection: &Connection,
+    call: impl FnOnce(&str) -> zbus::Result<OwnedObjectPath>,
+) -> zbus::Result<Response> {
+    let token = format!(
+        "tiny4linux_{}_{}",
+        process::id(),
+        NEXT_TOKEN.fetch_add(1, Ordering::Relaxed)
+    );
+    let sender = connection
+        .unique_name()
+        .map(|name| name.to_string())
+        .unwrap_or_default();
+
+    // the response may come before the call returns, so the predictable path is subscribed first
+    let request = PortalRequestProxy::builder(connection)
+        .path(request_path(&sender, &token))?
+        .build()?;
+    let mut responses = request.receive_response()?;
+    call(&token)?;
+
+    let response = responses
+        .next()
+        .ok_or_else(|| zbus::Error::Failure("no response from the portal".to_string()))?;
+    match response.args()?.response() {
+        0 => Ok(response),
+        1 => Err(zbus::Error::Failure("cancelled by the user".to_string())),
+        _ => Err(zbus::Error::Failure("rejected by the portal".to_string())),
+    }
+}
+
+/// Path of the request object the portal creates for a call, see `org.freedesktop.portal.Request`.
+fn request_path(sender: &str, token: &str) -> String {
+    format!(
+        "/org/freedesktop/portal/desktop/request/{}/{}",
+        sender.trim_start_matches(':').replace('.', "_"),
+        token
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    mod unit {
+        use super::super::request_path;
+
+        #[test]
+        fn request_path_of_sender() {
+            assert_eq!(
+                request_path(":1.42", "tiny4linux_1_0"),
+                "/org/freedesktop/portal/desktop/request/1_42/tiny4linux_1_0"
+            );
+        }
+    }
+}
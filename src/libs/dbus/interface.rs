@@ -0,0 +1,358 @@
+// SPDX-License-Identifier: EUPL-1.2
+
+use crate::libs::dbus::DaemonError;
+use crate::libs::usbio::{CameraDevice, UvcUsbIo};
+use crate::{
+    AIMode, CAMERA_HINT, Camera, CameraStatus, ExposureMode, PanTilt, SleepMode, T4lError,
+    Tiny2Camera, TrackingSpeed, find_camera,
+};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::sync::{Mutex, RwLock, RwLockReadGuard};
+use zbus::object_server::SignalContext;
+use zbus::{fdo, interface};
+
+/// Serves a camera on the session bus, so that several clients can share it.
+///
+/// The settings are properties, named by the stable ids also used for machine-readable output,
+/// e.g. `upper_body` for the `AiMode`. Changes are announced with `PropertiesChanged`, also if
+/// they are made by a client through the raw requests or on the camera itself.
+///
+/// Raw requests of clients are relayed one operation at a time. The daemon stamps their 0x02
+/// frames with its own sequence number, so that the frames of all clients are counted in order.
+pub struct CameraInterface {
+    camera: RwLock<Camera>,
+    device: RwLock<CameraDevice>,
+    last_state: Mutex<Option<ObservedState>>,
+}
+
+/// The state of the camera the daemon announces changes of.
+#[derive(Clone, PartialEq)]
+struct ObservedState {
+    status: CameraStatus,
+    zoom: i32,
+    pan_tilt: (f64, f64),
+}
+
+impl CameraInterface {
+    pub fn new(camera: Camera, device: CameraDevice) -> Self {
+        Self {
+            camera: RwLock::new(camera),
+            device: RwLock::new(device),
+            last_state: Mutex::new(None),
+        }
+    }
+
+    /// The camera served, which is replaced once it reconnects.
+    pub fn camera(&self) -> RwLockReadGuard<'_, Camera> {
+        self.camera.read().unwrap()
+    }
+
+    /// Opens the camera again once it's back after it was disconnected, e.g. by a USB reset or
+    /// a suspend, and announces its new path.
+    ///
+    /// It's found again by its serial or its bus, as the path may change. Returns the device if
+    /// the camera was reconnected.
+    pub async fn reconnect(&self, ctxt: &SignalContext<'_>) -> zbus::Result<Option<CameraDevice>> {
+        if !matches!(
+            self.camera().get_status(),
+            Err(T4lError::CameraDisconnected)
+        ) {
+            return Ok(None);
+        }
+
+        let selector = {
+            let device = self.device.read().unwrap();
+            device.serial.clone().unwrap_or(device.bus_info.clone())
+        };
+        let Ok(device) = find_camera(CAMERA_HINT, &selector) else {
+            return Ok(None);
+        };
+        let Ok(camera) = Camera::new_local(&device.path) else {
+            return Ok(None);
+        };
+
+        *self.camera.write().unwrap() = camera;
+        *self.device.write().unwrap() = device.clone();
+        self.device_changed(ctxt).await?;
+        Ok(Some(device))
+    }
+
+    fn observe(&self) -> Option<ObservedState> {
+        Some(ObservedState {
+            status: self.camera().get_status().ok()?,
+            zoom: self.camera().get_zoom().unwrap_or_default(),
+            pan_tilt: self.camera().get_pan_tilt().unwrap_or_default(),
+        })
+    }
+
+    /// Reads the state of the camera and emits `PropertiesChanged` for everything that differs
+    /// from the last call.
+    pub async fn announce_changes(&self, ctxt: &SignalContext<'_>) -> zbus::Result<()> {
+        let Some(state) = self.observe() else {
+            return Ok(());
+        };
+        let last = self.last_state.lock().unwrap().replace(state.clone());
+        let Some(last) = last else {
+            return Ok(());
+        };
+
+        if state.status.awake != last.status.awake {
+            self.sleep_mode_changed(ctxt).await?;
+        }
+        if state.status.ai_mode != last.status.ai_mode {
+            self.ai_mode_changed(ctxt).await?;
+        }
+        if state.status.speed != last.status.speed {
+            self.tracking_speed_changed(ctxt).await?;
+        }
+        if state.status.hdr_on != last.status.hdr_on {
+            self.hdr_changed(ctxt).await?;
+        }
+        if state.zoom != last.zoom {
+            self.zoom_changed(ctxt).await?;
+        }
+        if state.pan_tilt != last.pan_tilt {
+            self.pan_tilt_changed(ctxt).await?;
+        }
+        if state.status.raw != last.status.raw {
+            self.raw_status_changed(ctxt).await?;
+        }
+        Ok(())
+    }
+}
+
+#[interface(
+    name = "io.github.openfoxes.Tiny4Linux.Camera1",
+    proxy(
+        default_service = "io.github.openfoxes.Tiny4Linux",
+        default_path = "/io/github/openfoxes/Tiny4Linux/Camera",
+        gen_async = false,
+        blocking_name = "CameraProxy"
+    )
+)]
+impl CameraInterface {
+    /// Path of the video device, e.g. `/dev/video0`, which may change once the camera reconnects.
+    #[zbus(property)]
+    fn device(&self) -> String {
+        self.device.read().unwrap().path.clone()
+    }
+
+    #[zbus(property(emits_changed_signal = "const"))]
+    fn card(&self) -> String {
+        self.device.read().unwrap().card.clone()
+    }
+
+    #[zbus(property(emits_changed_signal = "const"))]
+    fn bus_info(&self) -> String {
+        self.device.read().unwrap().bus_info.clone()
+    }
+
+    /// `awake` or `sleep`.
+    #[zbus(property)]
+    fn sleep_mode(&self) -> fdo::Result<String> {
+        to_id(&self.camera().get_sleep_mode()?)
+    }
+
+    #[zbus(property)]
+    fn set_sleep_mode(&mut self, mode: String) -> fdo::Result<()> {
+        Ok(self.camera().set_sleep_mode(from_id::<SleepMode>(&mode)?)?)
+    }
+
+    /// The tracking mode, e.g. `no_tracking` or `upper_body`.
+    #[zbus(property)]
+    fn ai_mode(&self) -> fdo::Result<String> {
+        to_id(&self.camera().get_ai_mode()?)
+    }
+
+    #[zbus(property)]
+    fn set_ai_mode(&mut self, mode: String) -> fdo::Result<()> {
+        Ok(self.camera().set_ai_mode(from_id::<AIMode>(&mode)?)?)
+    }
+
+    /// `standard` or `sport`.
+    #[zbus(property)]
+    fn tracking_speed(&self) -> fdo::Result<String> {
+        to_id(&self.camera().get_tracking_speed()?)
+    }
+
+    #[zbus(property)]
+    fn set_tracking_speed(&mut self, speed: String) -> fdo::Result<()> {
+        Ok(self
+            .camera()
+            .set_tracking_speed(from_id::<TrackingSpeed>(&speed)?)?)
+    }
+
+    #[zbus(property)]
+    fn hdr(&self) -> fdo::Result<bool> {
+        Ok(self.camera().get_status()?.hdr_on)
+    }
+
+    #[zbus(property)]
+    fn set_hdr(&mut self, hdr_on: bool) -> fdo::Result<()> {
+        Ok(self.camera().set_hdr_mode(hdr_on)?)
+    }
+
+    /// Sets the exposure mode to `manual`, `global` or `face`.
+    ///
+    /// It's a method rather than a property, as the mode isn't decoded from the status yet.
+    fn set_exposure_mode(&self, mode: String) -> fdo::Result<()> {
+        Ok(self
+            .camera()
+            .set_exposure_mode(from_id::<ExposureMode>(&mode)?)?)
+    }
+
+    #[zbus(property)]
+    fn zoom(&self) -> fdo::Result<i32> {
+        Ok(self.camera().get_zoom()?)
+    }
+
+    #[zbus(property)]
+    fn set_zoom(&mut self, zoom: i32) -> fdo::Result<()> {
+        Ok(self.camera().set_zoom(zoom)?)
+    }
+
+    /// Pan and tilt of the gimbal in degrees.
+    #[zbus(property)]
+    fn pan_tilt(&self) -> fdo::Result<(f64, f64)> {
+        Ok(self.camera().get_pan_tilt()?)
+    }
+
+    /// The status blob of selector 0x06, see [`CameraStatus`].
+    #[zbus(property)]
+    fn raw_status(&self) -> fdo::Result<Vec<u8>> {
+        Ok(self.camera().get_status()?.raw)
+    }
+
+    /// Moves the gimbal to the preset position, counted from 0.
+    fn goto_preset(&self, preset_nr: u8) -> Result<(), DaemonError> {
+        Ok(self.camera().goto_preset_position(preset_id(preset_nr)?)?)
+    }
+
+    /// Saves the current position of the gimbal into the preset, counted from 0.
+    #[cfg(feature = "experimental")]
+    fn save_preset(&self, preset_nr: u8) -> Result<(), DaemonError> {
+        Ok(self.camera().save_preset_position(preset_id(preset_nr)?)?)
+    }
+
+    /// Moves the gimbal to the absolute position in degrees.
+    fn move_to(&self, pan: f64, tilt: f64) -> Result<(), DaemonError> {
+        Ok(self
+            .camera()
+            .set_pan_tilt(PanTilt::Absolute { pan, tilt })?)
+    }
+
+    /// Moves the gimbal by the number of steps, see [`PanTilt::Relative`].
+    fn move_by(&self, pan: i32, tilt: i32) -> Result<(), DaemonError> {
+        Ok(self
+            .camera()
+            .set_pan_tilt(PanTilt::Relative { pan, tilt })?)
+    }
+
+    /// Relays a single request to the extension unit, returning the data the camera answered with.
+    fn xu_query(
+        &self,
+        unit: u8,
+        selector: u8,
+        query: u8,
+        data: Vec<u8>,
+    ) -> Result<Vec<u8>, DaemonError> {
+        let mut data = data;
+        self.camera()
+            .relay_io(unit, selector, query, &mut data)
+            .map_err(|errno| DaemonError::Errno(errno.0.to_string()))?;
+        Ok(data)
+    }
+
+    /// Relays a request to the extension unit after asking for the length of the selector, as one
+    /// operation that requests of other clients can't come in between.
+    ///
+    /// Returns the length and the data the camera answered with. 0x02 frames are stamped with the
+    /// sequence number of the daemon.
+    fn xu_sized_query(
+        &self,
+        unit: u8,
+        selector: u8,
+        query: u8,
+        data: Vec<u8>,
+    ) -> Result<(u32, Vec<u8>), DaemonError> {
+        let mut data = data;
+        let size = self
+            .camera()
+            .relay(unit, selector, query, &mut data)
+            .map_err(|errno| DaemonError::Errno(errno.0.to_string()))?;
+        Ok((size as u32, data))
+    }
+
+    /// Relays reading a V4L2 control.
+    fn get_ctrl(&self, id: u32) -> Result<i32, DaemonError> {
+        self.camera()
+            .handle()
+            .get_ctrl(id)
+            .map_err(|errno| DaemonError::Errno(errno.0.to_string()))
+    }
+
+    /// Relays setting a V4L2 control.
+    fn set_ctrl(&self, id: u32, value: i32) -> Result<(), DaemonError> {
+        self.camera()
+            .handle()
+            .set_ctrl(id, value)
+            .map_err(|errno| DaemonError::Errno(errno.0.to_string()))
+    }
+
+    /// Relays querying the range of a V4L2 control as minimum, maximum, step and default.
+    fn query_ctrl(&self, id: u32) -> Result<(i32, i32, i32, i32), DaemonError> {
+        self.camera()
+            .handle()
+            .query_ctrl(id)
+            .map(|range| (range.minimum, range.maximum, range.step, range.default))
+            .map_err(|errno| DaemonError::Errno(errno.0.to_string()))
+    }
+}
+
+fn preset_id(preset_nr: u8) -> Result<i8, DaemonError> {
+    i8::try_from(preset_nr).map_err(|_| DaemonError::InvalidValue(preset_nr.to_string()))
+}
+
+/// The stable id of a setting's value, e.g. `upper_body`.
+fn to_id<T: Serialize>(value: &T) -> fdo::Result<String> {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(id)) => Ok(id),
+        _ => Err(fdo::Error::Failed("value has no id".to_string())),
+    }
+}
+
+fn from_id<T: DeserializeOwned>(id: &str) -> fdo::Result<T> {
+    serde_json::from_value(serde_json::Value::String(id.to_string()))
+        .map_err(|_| fdo::Error::InvalidArgs(id.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    mod unit {
+        use super::super::{from_id, to_id};
+        use crate::{AIMode, SleepMode, TrackingSpeed};
+        use test_case::test_case;
+
+        #[test_case(AIMode::UpperBody, "upper_body"; "ai mode")]
+        #[test_case(AIMode::NoTracking, "no_tracking"; "no tracking")]
+        fn ai_mode_ids(mode: AIMode, id: &str) {
+            assert_eq!(to_id(&mode).unwrap(), id);
+            assert_eq!(from_id::<AIMode>(id).unwrap(), mode);
+        }
+
+        #[test]
+        fn other_ids() {
+            assert_eq!(to_id(&SleepMode::Sleep).unwrap(), "sleep");
+            assert_eq!(
+                from_id::<TrackingSpeed>("sport").unwrap(),
+                TrackingSpeed::Sport
+            );
+        }
+
+        #[test]
+        fn unknown_id() {
+            assert!(from_id::<AIMode>("dancing").is_err());
+        }
+    }
+}
@@ -0,0 +1,16 @@
+// SPDX-License-Identifier: EUPL-1.2
+
+mod errors;
+mod global_shortcuts;
+mod interface;
+mod tray;
+
+pub use errors::DaemonError;
+pub use global_shortcuts::{GlobalShortcut, GlobalShortcuts};
+pub use interface::{CameraInterface, CameraProxy};
+pub use tray::{Tray, TrayEvent, TrayIcon, TrayMenuItem};
+
+/// Well-known name the daemon owns on the session bus.
+pub const DAEMON_BUS_NAME: &str = "io.github.openfoxes.Tiny4Linux";
+/// Object path the camera is served at.
+pub const DAEMON_OBJECT_PATH: &str = "/io/github/openfoxes/Tiny4Linux/Camera";
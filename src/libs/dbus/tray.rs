@@ -0,0 +1,655 @@
+// SPDX-License-Identifier: EUPL-1.2
+
+use serde::{Serialize, Serializer};
+use std::collections::HashMap;
+use std::process;
+use std::sync::{Arc, Mutex};
+use zbus::blocking::{Connection, connection};
+use zbus::object_server::SignalContext;
+use zbus::zvariant::{ObjectPath, OwnedValue, SerializeValue, Signature, Type, Value};
+use zbus::{fdo, interface, proxy};
+
+/// Object path the item is served at, as expected by the hosts.
+const ITEM_PATH: &str = "/StatusNotifierItem";
+/// Object path the menu of the item is served at.
+const MENU_PATH: &str = "/MenuBar";
+
+#[proxy(
+    interface = "org.kde.StatusNotifierWatcher",
+    default_service = "org.kde.StatusNotifierWatcher",
+    default_path = "/StatusNotifierWatcher",
+    gen_async = false,
+    blocking_name = "StatusNotifierWatcherProxy"
+)]
+trait StatusNotifierWatcher {
+    fn register_status_notifier_item(&self, service: &str) -> zbus::Result<()>;
+}
+
+/// Width, height and the ARGB32 pixels of an icon.
+type Pixmap = (i32, i32, Vec<u8>);
+
+/// The icon shown in the tray, either by the name of an icon of the theme or by its pixels.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TrayIcon {
+    name: String,
+    pixmaps: Vec<Pixmap>,
+}
+
+impl TrayIcon {
+    pub fn named(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            pixmaps: Vec::new(),
+        }
+    }
+
+    /// Takes the pixels row by row with 4 bytes each in RGBA order, as decoded from a PNG.
+    pub fn from_rgba(width: u32, height: u32, rgba: &[u8]) -> Self {
+        // the hosts expect ARGB32 in network byte order
+        let argb = rgba
+            .chunks_exact(4)
+            .flat_map(|pixel| [pixel[3], pixel[0], pixel[1], pixel[2]])
+            .collect();
+
+        Self {
+            name: String::new(),
+            pixmaps: vec![(width as i32, height as i32, argb)],
+        }
+    }
+}
+
+/// An entry of the context menu of the tray.
+///
+/// The ids of actions are unique within the menu. Entries keep their id in the dbusmenu protocol
+/// by their action id, or by the labels leading to a submenu, while the menu changes.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TrayMenuItem {
+    /// Reported with its id once clicked, shown as a radio button if `checked` is set.
+    Action {
+        id: String,
+        label: String,
+        checked: Option<bool>,
+    },
+    Submenu {
+        label: String,
+        items: Vec<TrayMenuItem>,
+    },
+    Separator,
+}
+
+/// What the user did with the tray.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TrayEvent {
+    /// The icon was clicked.
+    Activated,
+    /// The entry of the menu with the id was clicked.
+    MenuItem(String),
+}
+
+type EventHandler = Arc<dyn Fn(TrayEvent) + Send + Sync>;
+
+struct TrayState {
+    icon: TrayIcon,
+    menu: Vec<TrayMenuItem>,
+    ids: MenuIds,
+    revision: u32,
+}
+
+/// An icon with a context menu in the system tray of the desktop, shown for as long as the
+/// returned value lives.
+///
+/// It's served as a `StatusNotifierItem` with a `com.canonical.dbusmenu`, as supported by KDE
+/// and by GNOME with the AppIndicator extension.
+pub struct Tray {
+    connection: Connection,
+    state: Arc<Mutex<TrayState>>,
+}
+
+impl Tray {
+    /// Shows the icon with an empty menu, calling back with each [`TrayEvent`].
+    ///
+    /// Fails if no host for tray icons is running.
+    pub fn new(
+        id: &str,
+        title: &str,
+        icon: TrayIcon,
+        on_event: impl Fn(TrayEvent) + Send + Sync + 'static,
+    ) -> zbus::Result<Self> {
+        let state = Arc::new(Mutex::new(TrayState {
+            icon,
+            menu: Vec::new(),
+            ids: MenuIds::default(),
+            revision: 0,
+        }));
+        let on_event: EventHandler = Arc::new(on_event);
+
+        let name = format!("org.kde.StatusNotifierItem-{}-1", process::id());
+        let item = ItemInterface {
+            id: id.to_string(),
+            title: title.to_string(),
+            state: state.clone(),
+            on_event: on_event.clone(),
+        };
+        let menu = MenuInterface {
+            state: state.clone(),
+            on_event,
+        };
+        let connection = connection::Builder::session()?
+            .name(name.as_str())?
+            .serve_at(ITEM_PATH, item)?
+            .serve_at(MENU_PATH, menu)?
+            .build()?;
+
+        StatusNotifierWatcherProxy::new(&connection)?.register_status_notifier_item(&name)?;
+
+        Ok(Self { connection, state })
+    }
+
+    /// Changes the icon, unless it's the same already.
+    pub fn set_icon(&self, icon: TrayIcon) -> zbus::Result<()> {
+        {
+            let mut state = self.state.lock().unwrap();
+            if state.icon == icon {
+                return Ok(());
+            }
+            state.icon = icon;
+        }
+
+        let item = self
+            .connection
+            .object_server()
+            .interface::<_, ItemInterface>(ITEM_PATH)?;
+        zbus::block_on(ItemInterface::new_icon(item.signal_context()))
+    }
+
+    /// Changes the entries of the menu, unless they're the same already.
+    pub fn set_menu(&self, menu: Vec<TrayMenuItem>) -> zbus::Result<()> {
+        let revision = {
+            let mut state = self.state.lock().unwrap();
+            if state.menu == menu {
+                return Ok(());
+            }
+            state.ids.assign(&menu);
+            state.menu = menu;
+            state.revision += 1;
+            state.revision
+        };
+
+        let interface = self
+            .connection
+            .object_server()
+            .interface::<_, MenuInterface>(MENU_PATH)?;
+        zbus::block_on(MenuInterface::layout_updated(
+            interface.signal_context(),
+            revision,
+            0,
+        ))
+    }
+}
+
+struct ItemInterface {
+    id: String,
+    title: String,
+    state: Arc<Mutex<TrayState>>,
+    on_event: EventHandler,
+}
+
+#[interface(name = "org.kde.StatusNotifierItem")]
+impl ItemInterface {
+    fn activate(&self, _x: i32, _y: i32) {
+        (self.on_event)(TrayEvent::Activated);
+    }
+
+    fn secondary_activate(&self, _x: i32, _y: i32) {}
+
+    /// Nothing to do, as the host shows the menu itself.
+    fn context_menu(&self, _x: i32, _y: i32) {}
+
+    fn scroll(&self, _delta: i32, _orientation: &str) {}
+
+    #[zbus(signal)]
+    async fn new_icon(ctxt: &SignalContext<'_>) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn category(&self) -> &str {
+        "Hardware"
+    }
+
+    #[zbus(property)]
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    #[zbus(property)]
+    fn title(&self) -> &str {
+        &self.title
+    }
+
+    #[zbus(property)]
+    fn status(&self) -> &str {
+        "Active"
+    }
+
+    #[zbus(property)]
+    fn window_id(&self) -> i32 {
+        0
+    }
+
+    #[zbus(property)]
+    fn icon_name(&self) -> String {
+        self.state.lock().unwrap().icon.name.clone()
+    }
+
+    #[zbus(property)]
+    fn icon_pixmap(&self) -> Vec<Pixmap> {
+        self.state.lock().unwrap().icon.pixmaps.clone()
+    }
+
+    #[zbus(property)]
+    fn tool_tip(&self) -> (String, Vec<Pixmap>, String, String) {
+        (String::new(), Vec::new(), self.title.clone(), String::new())
+    }
+
+    #[zbus(property)]
+    fn item_is_menu(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn menu(&self) -> ObjectPath<'_> {
+        ObjectPath::from_static_str_unchecked(MENU_PATH)
+    }
+}
+
+struct MenuInterface {
+    state: Arc<Mutex<TrayState>>,
+    on_event: EventHandler,
+}
+
+impl MenuInterface {
+    fn clicked(&self, id: i32, event_id: &str) {
+        if event_id != "clicked" {
+            return;
+        }
+
+        let action = {
+            let state = self.state.lock().unwrap();
+            match menu_nodes(&state.menu, &state.ids)
+                .get(&id)
+                .and_then(|node| node.item)
+            {
+                Some(TrayMenuItem::Action { id, .. }) => Some(id.clone()),
+                _ => None,
+            }
+        };
+        if let Some(action) = action {
+            (self.on_event)(TrayEvent::MenuItem(action));
+        }
+    }
+}
+
+#[interface(name = "com.canonical.dbusmenu")]
+impl MenuInterface {
+    #[zbus(out_args("revision", "layout"))]
+    fn get_layout(
+        &self,
+        parent_id: i32,
+        recursion_depth: i32,
+        _property_names: Vec<String>,
+    ) -> fdo::Result<(u32, MenuLayout)> {
+        let state = self.state.lock().unwrap();
+        let nodes = menu_nodes(&state.menu, &state.ids);
+        if !nodes.contains_key(&parent_id) {
+            return Err(fdo::Error::InvalidArgs(format!(
+                "no menu item {}",
+                parent_id
+            )));
+        }
+
+        Ok((state.revision, layout(&nodes, parent_id, recursion_depth)))
+    }
+
+    fn get_group_properties(
+        &self,
+        ids: Vec<i32>,
+        _property_names: Vec<String>,
+    ) -> Vec<(i32, HashMap<String, Value<'static>>)> {
+        let state = self.state.lock().unwrap();
+        let nodes = menu_nodes(&state.menu, &state.ids);
+
+        // all items are meant if no ids are given
+        let ids = if ids.is_empty() {
+            let mut all: Vec<_> = nodes.keys().copied().collect();
+            all.sort();
+            all
+        } else {
+            ids
+        };
+        ids.into_iter()
+            .filter_map(|id| Some((id, properties(nodes.get(&id)?.item))))
+            .collect()
+    }
+
+    fn get_property(&self, id: i32, name: &str) -> fdo::Result<OwnedValue> {
+        let state = self.state.lock().unwrap();
+        let nodes = menu_nodes(&state.menu, &state.ids);
+
+        nodes
+            .get(&id)
+            .and_then(|node| properties(node.item).remove(name))
+            .and_then(|value| OwnedValue::try_from(value).ok())
+            .ok_or_else(|| fdo::Error::InvalidArgs(format!("no property {} of {}", name, id)))
+    }
+
+    fn event(&self, id: i32, event_id: &str, _data: Value<'_>, _timestamp: u32) {
+        self.clicked(id, event_id);
+    }
+
+    fn event_group(&self, events: Vec<(i32, String, OwnedValue, u32)>) -> Vec<i32> {
+        for (id, event_id, _, _) in events {
+            self.clicked(id, &event_id);
+        }
+        Vec::new()
+    }
+
+    fn about_to_show(&self, _id: i32) -> bool {
+        false
+    }
+
+    #[zbus(out_args("updates_needed", "id_errors"))]
+    fn about_to_show_group(&self, _ids: Vec<i32>) -> (Vec<i32>, Vec<i32>) {
+        (Vec::new(), Vec::new())
+    }
+
+    #[zbus(signal)]
+    async fn layout_updated(
+        ctxt: &SignalContext<'_>,
+        revision: u32,
+        parent: i32,
+    ) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn version(&self) -> u32 {
+        3
+    }
+
+    #[zbus(property)]
+    fn text_direction(&self) -> &str {
+        "ltr"
+    }
+
+    #[zbus(property)]
+    fn status(&self) -> &str {
+        "normal"
+    }
+
+    #[zbus(property)]
+    fn icon_theme_path(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+/// The ids of the entries in the dbusmenu protocol by their key, see [`menu_entries`].
+///
+/// Ids are never reused while the tray lives, so a click on a menu shown before a change either
+/// hits the same entry or none at all.
+#[derive(Debug, Default)]
+struct MenuIds(HashMap<String, i32>);
+
+impl MenuIds {
+    /// Allocates the next ids to the entries that weren't in any menu before, from 1 on, as 0 is
+    /// the root.
+    fn assign(&mut self, menu: &[TrayMenuItem]) {
+        for entry in menu_entries(menu) {
+            let next = self.0.len() as i32 + 1;
+            self.0.entry(entry.key).or_insert(next);
+        }
+    }
+
+    fn get(&self, key: &str) -> Option<i32> {
+        match key {
+            "" => Some(0),
+            key => self.0.get(key).copied(),
+        }
+    }
+}
+
+/// An entry of the menu with the key its id is kept by and the key of its parent, which is empty
+/// for the root.
+struct MenuEntry<'m> {
+    key: String,
+    parent: String,
+    item: &'m TrayMenuItem,
+}
+
+/// The entries of the menu in order, keyed by their action id, or by the path of labels for
+/// submenus and separators.
+fn menu_entries(menu: &[TrayMenuItem]) -> Vec<MenuEntry<'_>> {
+    let mut entries = Vec::new();
+    add_menu_entries(&mut entries, "", menu);
+    entries
+}
+
+fn add_menu_entries<'m>(entries: &mut Vec<MenuEntry<'m>>, parent: &str, items: &'m [TrayMenuItem]) {
+    let mut separators = 0;
+    for item in items {
+        let key = match item {
+            TrayMenuItem::Action { id, .. } => id.clone(),
+            TrayMenuItem::Submenu { label, .. } => format!("{}/{}", parent, label),
+            TrayMenuItem::Separator => {
+                separators += 1;
+                format!("{}/separator {}", parent, separators)
+            }
+        };
+        entries.push(MenuEntry {
+            key: key.clone(),
+            parent: parent.to_string(),
+            item,
+        });
+
+        if let TrayMenuItem::Submenu { items, .. } = item {
+            add_menu_entries(entries, &key, items);
+        }
+    }
+}
+
+/// An entry of the menu with the ids of its children, where the root has no entry.
+struct MenuNode<'m> {
+    item: Option<&'m TrayMenuItem>,
+    children: Vec<i32>,
+}
+
+/// The entries of the menu indexed by their id in the dbusmenu protocol, where 0 is the root.
+///
+/// Entries without an id, as the menu wasn't assigned to the ids, are left out.
+fn menu_nodes<'m>(menu: &'m [TrayMenuItem], ids: &MenuIds) -> HashMap<i32, MenuNode<'m>> {
+    let mut nodes = HashMap::from([(
+        0,
+        MenuNode {
+            item: None,
+            children: Vec::new(),
+        },
+    )]);
+    for entry in menu_entries(menu) {
+        let (Some(id), Some(parent)) = (ids.get(&entry.key), ids.get(&entry.parent)) else {
+            continue;
+        };
+        let Some(parent) = nodes.get_mut(&parent) else {
+            continue;
+        };
+
+        parent.children.push(id);
+        nodes.insert(
+            id,
+            MenuNode {
+                item: Some(entry.item),
+                children: Vec::new(),
+            },
+        );
+    }
+    nodes
+}
+
+fn properties(item: Option<&TrayMenuItem>) -> HashMap<String, Value<'static>> {
+    let mut properties = HashMap::new();
+    match item {
+        None => {
+            properties.insert("children-display".to_string(), Value::from("submenu"));
+        }
+        Some(TrayMenuItem::Action { label, checked, .. }) => {
+            properties.insert("label".to_string(), Value::from(label.clone()));
+            if let Some(checked) = checked {
+                properties.insert("toggle-type".to_string(), Value::from("radio"));
+                properties.insert("toggle-state".to_string(), Value::from(*checked as i32));
+            }
+        }
+        Some(TrayMenuItem::Submenu { label, .. }) => {
+            properties.insert("label".to_string(), Value::from(label.clone()));
+            properties.insert("children-display".to_string(), Value::from("submenu"));
+        }
+        Some(TrayMenuItem::Separator) => {
+            properties.insert("type".to_string(), Value::from("separator"));
+        }
+    }
+    properties
+}
+
+/// The layout of an entry of the menu and its children, as returned by `GetLayout`.
+#[derive(Debug, Serialize, Type)]
+struct MenuLayout {
+    id: i32,
+    properties: HashMap<String, Value<'static>>,
+    children: Vec<MenuChild>,
+}
+
+/// A child in a [`MenuLayout`], which the protocol wraps into a variant.
+#[derive(Debug)]
+struct MenuChild(MenuLayout);
+
+impl Type for MenuChild {
+    fn signature() -> Signature<'static> {
+        Value::signature()
+    }
+}
+
+impl Serialize for MenuChild {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeValue(&self.0).serialize(serializer)
+    }
+}
+
+/// Lays out the entry with its children up to the depth, or all of them if it's negative.
+fn layout(nodes: &HashMap<i32, MenuNode>, id: i32, depth: i32) -> MenuLayout {
+    let node = &nodes[&id];
+    let children = if depth == 0 {
+        Vec::new()
+    } else {
+        node.children
+            .iter()
+            .map(|child| MenuChild(layout(nodes, *child, depth - 1)))
+            .collect()
+    };
+
+    MenuLayout {
+        id,
+        properties: properties(node.item),
+        children,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod unit {
+        use super::super::{MenuIds, TrayIcon, TrayMenuItem, layout, menu_nodes, properties};
+        use zbus::zvariant::Value;
+
+        fn action(id: &str, checked: Option<bool>) -> TrayMenuItem {
+            TrayMenuItem::Action {
+                id: id.to_string(),
+                label: id.to_string(),
+                checked,
+            }
+        }
+
+        fn menu() -> Vec<TrayMenuItem> {
+            vec![
+                action("sleep", None),
+                TrayMenuItem::Submenu {
+                    label: "tracking".to_string(),
+                    items: vec![
+                        action("normal", Some(true)),
+                        action("close_up", Some(false)),
+                    ],
+                },
+                TrayMenuItem::Separator,
+                action("quit", None),
+            ]
+        }
+
+        fn ids(menu: &[TrayMenuItem]) -> MenuIds {
+            let mut ids = MenuIds::default();
+            ids.assign(menu);
+            ids
+        }
+
+        #[test]
+        fn numbers_items_in_order() {
+            let menu = menu();
+            let nodes = menu_nodes(&menu, &ids(&menu));
+
+            assert_eq!(nodes.len(), 7);
+            assert_eq!(nodes[&0].children, [1, 2, 5, 6]);
+            assert_eq!(nodes[&2].children, [3, 4]);
+            assert_eq!(nodes[&4].item, Some(&action("close_up", Some(false))));
+        }
+
+        #[test]
+        fn keeps_ids_of_items() {
+            let mut ids = ids(&menu());
+            let mut changed = menu();
+            changed.insert(0, action("show", None));
+            changed.remove(2);
+            ids.assign(&changed);
+            let nodes = menu_nodes(&changed, &ids);
+
+            assert_eq!(nodes[&0].children, [7, 1, 5, 6]);
+            assert_eq!(nodes[&6].item, Some(&action("quit", None)));
+            assert!(!nodes.contains_key(&4));
+        }
+
+        #[test]
+        fn skips_items_without_id() {
+            let menu = menu();
+            let nodes = menu_nodes(&menu, &MenuIds::default());
+
+            assert_eq!(nodes.len(), 1);
+            assert!(nodes[&0].children.is_empty());
+        }
+
+        #[test]
+        fn lays_out_up_to_depth() {
+            let menu = menu();
+            let nodes = menu_nodes(&menu, &ids(&menu));
+
+            assert_eq!(layout(&nodes, 0, 1).children.len(), 4);
+            assert!(layout(&nodes, 0, 1).children[1].0.children.is_empty());
+            assert_eq!(layout(&nodes, 0, -1).children[1].0.children.len(), 2);
+            assert_eq!(layout(&nodes, 2, -1).children[0].0.id, 3);
+        }
+
+        #[test]
+        fn marks_checked_items() {
+            let properties = properties(Some(&action("normal", Some(true))));
+
+            assert_eq!(properties["toggle-type"], Value::from("radio"));
+            assert_eq!(properties["toggle-state"], Value::from(1));
+        }
+
+        #[test]
+        fn converts_pixels_to_argb() {
+            let icon = TrayIcon::from_rgba(1, 2, &[1, 2, 3, 4, 5, 6, 7, 8]);
+
+            assert_eq!(icon.pixmaps, [(1, 2, vec![4, 1, 2, 3, 8, 5, 6, 7])]);
+        }
+    }
+}
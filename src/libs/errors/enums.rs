@@ -1,18 +1,181 @@
 // SPDX-License-Identifier: EUPL-1.2
 
+use errno::Errno;
+use nix::libc::{EACCES, EBUSY, ENODEV, ENXIO, EPERM};
+use rust_i18n::t;
 use std::io;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum T4lError {
-    #[error("value of {1} is not supported for {0}")]
+    #[error("{}", t!("errors.unsupported_int_value", setting = .0, value = .1))]
     UnsupportedIntValue(String, i32),
-    #[error("USB IO error: {0}")]
+    #[error("{}", t!("errors.usb_io_error", errno = .0))]
     USBIOError(i32),
-    #[error("IO error: {0}")]
+    #[error("{}", t!("errors.io_error", error = .0))]
     IOError(#[from] io::Error),
-    #[error("no camera found")]
+    #[error("{}", t!("errors.no_camera_found"))]
     NoCameraFound,
-    #[error("Invalid setting")]
+    #[error("{}", t!("errors.invalid_setting"))]
     InvalidSetting,
+    #[error("{}", t!("errors.invalid_frame", reason = .0))]
+    InvalidFrame(String),
+    #[error("{}", t!("errors.permission_denied"))]
+    PermissionDenied,
+    #[error("{}", t!("errors.camera_busy"))]
+    CameraBusy,
+    #[error("{}", t!("errors.camera_disconnected"))]
+    CameraDisconnected,
+    #[error("{}", t!("errors.invalid_config", path = .0, reason = .1))]
+    InvalidConfig(String, String),
+    #[error("{}", t!("errors.unknown_profile", name = .0))]
+    UnknownProfile(String),
+    #[error("{}", t!("errors.invalid_schedule", expression = .0, reason = .1))]
+    InvalidSchedule(String, String),
+    #[error("{}", t!("errors.unknown_schedule_rule", number = .0))]
+    UnknownScheduleRule(usize),
+    #[error("{}", t!("errors.empty_schedule"))]
+    EmptySchedule,
+    #[error("{}", t!("errors.invalid_script", line = .0, reason = .1))]
+    InvalidScript(usize, String),
+    #[error("{}", t!("errors.script_failed", line = .0, error = .1))]
+    ScriptFailed(usize, Box<T4lError>),
+    #[error("{}", t!("errors.invalid_recording", line = .0, reason = .1))]
+    InvalidRecording(usize, String),
+    #[error("{}", t!("errors.invalid_capture", reason = .0))]
+    InvalidCapture(String),
+    #[error("{}", t!("errors.replay_failed", line = .0, error = .1))]
+    ReplayFailed(usize, Box<T4lError>),
+    #[error("{}", t!("errors.data_too_long", length = .0, size = .1))]
+    DataTooLong(usize, usize),
+    #[error("{}", t!("errors.truncated_data", captured = .0, length = .1))]
+    TruncatedData(usize, usize),
+}
+
+impl From<Errno> for T4lError {
+    /// Maps the errno values with a known cause to their own variant, all others to `USBIOError`.
+    fn from(errno: Errno) -> Self {
+        match errno.0 {
+            EACCES | EPERM => T4lError::PermissionDenied,
+            EBUSY => T4lError::CameraBusy,
+            ENODEV | ENXIO => T4lError::CameraDisconnected,
+            errno => T4lError::USBIOError(errno),
+        }
+    }
+}
+
+impl T4lError {
+    /// A stable, non-localized identifier of the error, e.g. for machine-readable output.
+    pub fn code(&self) -> &'static str {
+        match self {
+            T4lError::UnsupportedIntValue(_, _) => "unsupported_value",
+            T4lError::USBIOError(_) => "usb_io_error",
+            T4lError::IOError(_) => "io_error",
+            T4lError::NoCameraFound => "no_camera_found",
+            T4lError::InvalidSetting => "invalid_setting",
+            T4lError::InvalidFrame(_) => "invalid_frame",
+            T4lError::PermissionDenied => "permission_denied",
+            T4lError::CameraBusy => "camera_busy",
+            T4lError::CameraDisconnected => "camera_disconnected",
+            T4lError::InvalidConfig(_, _) => "invalid_config",
+            T4lError::UnknownProfile(_) => "unknown_profile",
+            T4lError::InvalidSchedule(_, _) => "invalid_schedule",
+            T4lError::UnknownScheduleRule(_) => "unknown_schedule_rule",
+            T4lError::EmptySchedule => "empty_schedule",
+            T4lError::InvalidScript(_, _) => "invalid_script",
+            // the cause matters more than where in the script it occurred
+            T4lError::ScriptFailed(_, error) => error.code(),
+            T4lError::InvalidRecording(_, _) => "invalid_recording",
+            T4lError::InvalidCapture(_) => "invalid_capture",
+            T4lError::ReplayFailed(_, error) => error.code(),
+            T4lError::DataTooLong(_, _) => "data_too_long",
+            T4lError::TruncatedData(_, _) => "truncated_data",
+        }
+    }
+
+    /// The exit code of a process that ends because of this error.
+    ///
+    /// | Code | Cause                                     |
+    /// |------|-------------------------------------------|
+    /// | 1    | Communication with the camera failed      |
+    /// | 2    | Invalid command line (reserved for clap)  |
+    /// | 3    | No camera found                           |
+    /// | 4    | Permission denied on the video device     |
+    /// | 5    | Camera is busy                            |
+    /// | 6    | Camera was disconnected                   |
+    /// | 7    | Invalid or unsupported value              |
+    /// | 8    | Invalid configuration file                |
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            T4lError::USBIOError(_) | T4lError::IOError(_) | T4lError::InvalidFrame(_) => 1,
+            T4lError::NoCameraFound => 3,
+            T4lError::PermissionDenied => 4,
+            T4lError::CameraBusy => 5,
+            T4lError::CameraDisconnected => 6,
+            T4lError::UnsupportedIntValue(_, _)
+            | T4lError::InvalidSetting
+            | T4lError::UnknownProfile(_)
+            | T4lError::InvalidSchedule(_, _)
+            | T4lError::UnknownScheduleRule(_)
+            | T4lError::EmptySchedule
+            | T4lError::InvalidScript(_, _)
+            | T4lError::InvalidRecording(_, _)
+            | T4lError::InvalidCapture(_)
+            | T4lError::DataTooLong(_, _)
+            | T4lError::TruncatedData(_, _) => 7,
+            T4lError::InvalidConfig(_, _) => 8,
+            T4lError::ScriptFailed(_, error) | T4lError::ReplayFailed(_, error) => {
+                error.exit_code()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod unit {
+        use crate::libs::errors::T4lError;
+        use errno::Errno;
+        use nix::libc::{EACCES, EBUSY, EINVAL, EIO, EMSGSIZE, ENODEV, EPERM};
+        use test_case::test_case;
+
+        #[test_case(EACCES, "permission_denied"; "eacces")]
+        #[test_case(EPERM, "permission_denied"; "eperm")]
+        #[test_case(EBUSY, "camera_busy"; "ebusy")]
+        #[test_case(ENODEV, "camera_disconnected"; "enodev")]
+        #[test_case(EIO, "usb_io_error"; "eio")]
+        #[test_case(EINVAL, "usb_io_error"; "einval")]
+        #[test_case(EMSGSIZE, "usb_io_error"; "emsgsize")]
+        fn from_errno(errno: i32, expected_code: &str) {
+            assert_eq!(T4lError::from(Errno(errno)).code(), expected_code);
+        }
+
+        #[test]
+        fn keeps_unknown_errno() {
+            assert!(matches!(
+                T4lError::from(Errno(EIO)),
+                T4lError::USBIOError(EIO)
+            ));
+        }
+
+        #[test_case(T4lError::USBIOError(EIO), 1; "usb io error")]
+        #[test_case(T4lError::NoCameraFound, 3; "no camera found")]
+        #[test_case(T4lError::PermissionDenied, 4; "permission denied")]
+        #[test_case(T4lError::CameraBusy, 5; "camera busy")]
+        #[test_case(T4lError::CameraDisconnected, 6; "camera disconnected")]
+        #[test_case(T4lError::InvalidSetting, 7; "invalid setting")]
+        #[test_case(T4lError::UnknownProfile("call".to_string()), 7; "unknown profile")]
+        #[test_case(T4lError::UnknownScheduleRule(3), 7; "unknown schedule rule")]
+        #[test_case(T4lError::InvalidScript(2, "".to_string()), 7; "invalid script")]
+        #[test_case(T4lError::ScriptFailed(4, Box::new(T4lError::CameraBusy)), 5; "script failed")]
+        #[test_case(T4lError::InvalidRecording(2, "".to_string()), 7; "invalid recording")]
+        #[test_case(T4lError::InvalidCapture("".to_string()), 7; "invalid capture")]
+        #[test_case(T4lError::ReplayFailed(3, Box::new(T4lError::USBIOError(EIO))), 1; "replay failed")]
+        #[test_case(T4lError::DataTooLong(61, 60), 7; "data too long")]
+        #[test_case(T4lError::TruncatedData(32, 60), 7; "truncated data")]
+        #[test_case(T4lError::InvalidConfig("profiles.toml".to_string(), "".to_string()), 8; "invalid config")]
+        fn exit_code(error: T4lError, expected: i32) {
+            assert_eq!(error.exit_code(), expected);
+        }
+    }
 }
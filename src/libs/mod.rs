@@ -3,10 +3,34 @@ use rust_i18n::i18n;
 i18n!("src/locales", fallback = "en");
 
 mod camera;
+mod config;
+#[cfg(feature = "dbus")]
+mod dbus;
 mod errors;
 
 mod i18n;
+mod recording;
+mod schedule;
+mod script;
 mod usbio;
 
 pub use camera::*;
+pub use config::{
+    HotkeyAction, OffsetLabels, Profile, Profiles, Settings, config_dir, load_persisted_settings,
+    persisted_settings_path, record_persisted_settings, restore_persisted_settings,
+};
+#[cfg(feature = "dbus")]
+pub use dbus::*;
+pub use errors::T4lError;
 pub use i18n::*;
+pub use recording::{RecordedRequest, Recording};
+pub use schedule::{
+    CronExpression, LocalTime, SYSTEMD_UNIT, Schedule, ScheduleAction, ScheduleRule, Scheduler,
+    systemd_units,
+};
+pub use script::{CameraRequest, Script, ScriptCommand};
+pub use usbio::{
+    CameraDevice, ControlRange, HotplugEvent, HotplugWatcher, UVC_GET_CUR, UVC_GET_DEF,
+    UVC_GET_INFO, UVC_GET_LEN, UVC_GET_MAX, UVC_GET_MIN, UVC_GET_RES, UVC_SET_CUR, find_camera,
+    is_streaming, list_cameras,
+};
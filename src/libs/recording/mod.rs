@@ -0,0 +1,416 @@
+// SPDX-License-Identifier: EUPL-1.2
+
+mod recorder;
+mod usbmon;
+
+use crate::libs::camera::{Camera, parse_hex};
+use crate::libs::errors::T4lError;
+use crate::libs::usbio::{
+    UVC_GET_CUR, UVC_GET_DEF, UVC_GET_INFO, UVC_GET_LEN, UVC_GET_MAX, UVC_GET_MIN, UVC_GET_RES,
+    UVC_SET_CUR,
+};
+use std::fmt::Display;
+use std::str::FromStr;
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub(crate) use recorder::Recorder;
+
+/// The first line of a recording, naming its columns.
+const HEADER: &str = "# tiny4linux recording: seconds, request, unit, selector, data";
+
+/// The names of the UVC requests, as in the specification.
+const QUERY_NAMES: [(u8, &str); 8] = [
+    (UVC_SET_CUR, "SET_CUR"),
+    (UVC_GET_CUR, "GET_CUR"),
+    (UVC_GET_MIN, "GET_MIN"),
+    (UVC_GET_MAX, "GET_MAX"),
+    (UVC_GET_RES, "GET_RES"),
+    (UVC_GET_LEN, "GET_LEN"),
+    (UVC_GET_INFO, "GET_INFO"),
+    (UVC_GET_DEF, "GET_DEF"),
+];
+
+/// A UVC request to a unit of the camera, written on its own line of a [`Recording`], e.g.
+/// `0.000412 GET_CUR 0x02 0x06 0101…`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedRequest {
+    /// Time since the first request of the recording.
+    pub elapsed: Duration,
+    /// The UVC request code, e.g. `0x01` for SET_CUR.
+    pub query: u8,
+    pub unit: u8,
+    pub selector: u8,
+    /// The data sent with a SET request, or the answer of the camera to a GET request.
+    pub data: Vec<u8>,
+    /// The length the data had, if only its start was captured, e.g. by usbmon.
+    /// Such a request can't be replayed, written as `truncated=60`.
+    pub truncated: Option<usize>,
+    /// The error the camera answered with, if the request failed, written as `errno=32`.
+    pub errno: Option<i32>,
+}
+
+impl Display for RecordedRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let query = QUERY_NAMES
+            .iter()
+            .find(|(query, _)| *query == self.query)
+            .map(|(_, name)| name.to_string())
+            .unwrap_or_else(|| format!("{:#04x}", self.query));
+        let data = if self.data.is_empty() {
+            "-".to_string()
+        } else {
+            hex::encode(&self.data)
+        };
+
+        write!(
+            f,
+            "{:.6} {} {:#04x} {:#04x} {}",
+            self.elapsed.as_secs_f64(),
+            query,
+            self.unit,
+            self.selector,
+            data
+        )?;
+        if let Some(length) = self.truncated {
+            write!(f, " truncated={}", length)?;
+        }
+        if let Some(errno) = self.errno {
+            write!(f, " errno={}", errno)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for RecordedRequest {
+    /// The reason the request is invalid.
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let [elapsed, query, unit, selector, data, marks @ ..] = words.as_slice() else {
+            return Err(format!("expected 5 columns, got {}", words.len()));
+        };
+
+        let elapsed = elapsed
+            .parse()
+            .ok()
+            .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+            .ok_or_else(|| format!("invalid time `{}`", elapsed))?;
+        let query = QUERY_NAMES
+            .iter()
+            .find(|(_, name)| name.eq_ignore_ascii_case(query))
+            .map(|(query, _)| *query)
+            .or_else(|| parse_hex(query))
+            .ok_or_else(|| format!("unknown request `{}`", query))?;
+        let unit = parse_hex(unit).ok_or_else(|| format!("invalid unit `{}`", unit))?;
+        let selector =
+            parse_hex(selector).ok_or_else(|| format!("invalid selector `{}`", selector))?;
+        let data = match *data {
+            "-" => Vec::new(),
+            data => hex::decode(data).map_err(|error| format!("invalid data: {}", error))?,
+        };
+
+        let mut truncated = None;
+        let mut errno = None;
+        for mark in marks {
+            match mark.split_once('=') {
+                Some(("truncated", length)) => {
+                    truncated =
+                        Some(length.parse().map_err(|_| {
+                            format!("invalid length `{}` of truncated data", length)
+                        })?)
+                }
+                Some(("errno", number)) => {
+                    errno = Some(
+                        number
+                            .parse()
+                            .map_err(|_| format!("invalid errno `{}`", number))?,
+                    )
+                }
+                _ => return Err(format!("unknown column `{}`", mark)),
+            }
+        }
+
+        Ok(Self {
+            elapsed,
+            query,
+            unit,
+            selector,
+            data,
+            truncated,
+            errno,
+        })
+    }
+}
+
+/// The raw requests sent to a camera, e.g. recorded with
+/// [`Camera::start_recording`] while sniffing a new feature, one per line:
+///
+/// ```text
+/// # tiny4linux recording: seconds, request, unit, selector, data
+/// 0.000000 GET_LEN 0x02 0x02 3c00
+/// 0.000215 SET_CUR 0x02 0x02 aa2500000c00…
+/// ```
+///
+/// Empty lines and comments starting with `#` are skipped.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Recording {
+    requests: Vec<(usize, RecordedRequest)>,
+}
+
+impl Recording {
+    /// Parses the recording, failing with the number of the first invalid line.
+    pub fn parse(text: &str) -> Result<Self, T4lError> {
+        let mut requests = Vec::new();
+
+        for (number, line) in (1..).zip(text.lines()) {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let request = line
+                .parse()
+                .map_err(|reason| T4lError::InvalidRecording(number, reason))?;
+            requests.push((number, request));
+        }
+
+        Ok(Self { requests })
+    }
+
+    /// The requests with the numbers of their lines, counted from 1.
+    pub fn requests(&self) -> &[(usize, RecordedRequest)] {
+        &self.requests
+    }
+
+    /// Sends the requests to the camera again, calling back before each one with its line number.
+    ///
+    /// If `paced`, the requests are sent with the same time between them as recorded.
+    /// The answers to GET requests are discarded, and 0x02 frames keep their recorded
+    /// sequence numbers. Requests that failed when they were recorded are skipped, as they
+    /// didn't change the camera. Stops at the first request that fails, returning the error with
+    /// its line number.
+    ///
+    /// Refuses to send anything if the data of a request is truncated.
+    pub fn replay(
+        &self,
+        camera: &Camera,
+        paced: bool,
+        mut before_request: impl FnMut(usize, &RecordedRequest),
+    ) -> Result<(), T4lError> {
+        if let Some((number, request)) = self
+            .requests
+            .iter()
+            .find(|(_, request)| request.errno.is_none() && request.truncated.is_some())
+        {
+            let error = T4lError::TruncatedData(request.data.len(), request.truncated.unwrap_or(0));
+            return Err(T4lError::ReplayFailed(*number, Box::new(error)));
+        }
+
+        let started = Instant::now();
+
+        for (number, request) in &self.requests {
+            if request.errno.is_some() {
+                continue;
+            }
+            if paced && let Some(wait) = request.elapsed.checked_sub(started.elapsed()) {
+                thread::sleep(wait);
+            }
+
+            before_request(*number, request);
+            let mut data = request.data.clone();
+            camera
+                .query(request.unit, request.selector, request.query, &mut data)
+                .map_err(|error| T4lError::ReplayFailed(*number, Box::new(error)))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for Recording {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", HEADER)?;
+        for (_, request) in &self.requests {
+            writeln!(f, "{}", request)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod unit {
+        use crate::libs::recording::RecordedRequest;
+        use crate::{Recording, T4lError};
+        use std::time::Duration;
+        use test_case::test_case;
+
+        #[test]
+        fn parses_lines_with_numbers() {
+            let recording = Recording::parse(
+                "# tiny4linux recording\n0.000000 GET_LEN 0x02 0x06 3c00\n\n0.250000 set_cur 2 2 aa25 # sleep\n",
+            )
+            .unwrap();
+
+            assert_eq!(
+                recording.requests(),
+                [
+                    (
+                        2,
+                        RecordedRequest {
+                            elapsed: Duration::ZERO,
+                            query: 0x85,
+                            unit: 0x2,
+                            selector: 0x6,
+                            data: vec![0x3c, 0x00],
+                            truncated: None,
+                            errno: None,
+                        }
+                    ),
+                    (
+                        4,
+                        RecordedRequest {
+                            elapsed: Duration::from_millis(250),
+                            query: 0x01,
+                            unit: 0x2,
+                            selector: 0x2,
+                            data: vec![0xaa, 0x25],
+                            truncated: None,
+                            errno: None,
+                        }
+                    ),
+                ]
+            );
+        }
+
+        #[test_case("0.1 GET_CUR 0x02 0x06"; "missing data")]
+        #[test_case("-1 GET_CUR 0x02 0x06 00"; "negative time")]
+        #[test_case("0.1 GET_NOW 0x02 0x06 00"; "unknown request")]
+        #[test_case("0.1 GET_CUR 0x200 0x06 00"; "unit out of range")]
+        #[test_case("0.1 GET_CUR 0x02 0x06 0g"; "invalid data")]
+        #[test_case("0.1 GET_CUR 0x02 0x06 00 length=3"; "unknown column")]
+        #[test_case("0.1 SET_CUR 0x02 0x02 00 truncated=all"; "invalid truncated length")]
+        fn reports_invalid_line(line: &str) {
+            let error = Recording::parse(&format!("0 GET_LEN 2 6 3c00\n{}\n", line)).unwrap_err();
+
+            assert!(matches!(error, T4lError::InvalidRecording(2, _)));
+            assert_eq!(error.code(), "invalid_recording");
+        }
+
+        #[test]
+        fn writes_what_it_parses() {
+            let text = "# tiny4linux recording: seconds, request, unit, selector, data\n\
+                        0.000000 GET_LEN 0x02 0x06 3c00\n\
+                        1.500000 0x8a 0x02 0x06 -\n\
+                        1.600000 SET_CUR 0x02 0x02 aa25 truncated=60\n\
+                        1.700000 GET_CUR 0x02 0x06 - errno=32\n";
+
+            assert_eq!(Recording::parse(text).unwrap().to_string(), text);
+        }
+    }
+
+    mod integration {
+        use crate::{Camera, Recording, SleepCommand, SleepMode, T4lError, Tiny2Camera};
+        use std::fs;
+
+        #[test]
+        fn records_requests_sent_to_camera() {
+            let file = tempfile::NamedTempFile::new().unwrap();
+            let mut camera = Camera::simulated();
+            camera.start_recording(file.path()).unwrap();
+
+            camera.set_sleep_mode(SleepMode::Sleep).unwrap();
+
+            let recording = Recording::parse(&fs::read_to_string(file.path()).unwrap()).unwrap();
+            let requests: Vec<_> = recording
+                .requests()
+                .iter()
+                .map(|(_, request)| (request.query, request.unit, request.selector))
+                .collect();
+            assert_eq!(requests, [(0x85, 0x2, 0x2), (0x01, 0x2, 0x2)]);
+            assert_eq!(recording.requests()[0].1.data, [60, 0]);
+        }
+
+        #[test]
+        fn records_failed_requests() {
+            let file = tempfile::NamedTempFile::new().unwrap();
+            let mut camera = Camera::simulated();
+            camera.start_recording(file.path()).unwrap();
+
+            assert!(camera.get_raw(0x7, 0x1).is_err());
+
+            let recording = Recording::parse(&fs::read_to_string(file.path()).unwrap()).unwrap();
+            let (_, request) = recording.requests().last().unwrap();
+            assert_eq!((request.unit, request.selector), (0x7, 0x1));
+            assert!(request.errno.is_some());
+            assert!(request.data.is_empty());
+        }
+
+        #[test]
+        fn replays_recording() {
+            let camera = Camera::simulated();
+            let mut frame = [0u8; 60];
+            frame[..36].copy_from_slice(&SleepCommand::build(SleepMode::Sleep).unwrap());
+            let recording =
+                Recording::parse(&format!("0 SET_CUR 0x02 0x02 {}\n", hex::encode(frame))).unwrap();
+            let mut replayed = Vec::new();
+
+            recording
+                .replay(&camera, false, |number, _| replayed.push(number))
+                .unwrap();
+
+            assert_eq!(replayed, [1]);
+            assert_eq!(camera.get_sleep_mode().unwrap(), SleepMode::Sleep);
+        }
+
+        #[test]
+        fn stops_replay_at_first_failure() {
+            let camera = Camera::simulated();
+            let recording =
+                Recording::parse("0 GET_LEN 0x02 0x06 0000\n0 GET_LEN 0x07 0x01 0000\n").unwrap();
+
+            let error = recording.replay(&camera, false, |_, _| {}).unwrap_err();
+
+            assert!(matches!(error, T4lError::ReplayFailed(2, _)));
+        }
+
+        #[test]
+        fn refuses_truncated_requests() {
+            let camera = Camera::simulated();
+            let sleep = SleepCommand::build(SleepMode::Sleep).unwrap();
+            let recording = Recording::parse(&format!(
+                "0 SET_CUR 0x02 0x02 {}\n0 SET_CUR 0x02 0x02 {} truncated=60\n",
+                hex::encode([sleep.as_slice(), &[0; 24]].concat()),
+                hex::encode(&sleep[..32]),
+            ))
+            .unwrap();
+            let mut replayed = Vec::new();
+
+            let error = recording
+                .replay(&camera, false, |number, _| replayed.push(number))
+                .unwrap_err();
+
+            assert!(matches!(error, T4lError::ReplayFailed(2, _)));
+            assert_eq!(error.code(), "truncated_data");
+            assert!(replayed.is_empty(), "nothing is sent");
+            assert_eq!(camera.get_sleep_mode().unwrap(), SleepMode::Awake);
+        }
+
+        #[test]
+        fn skips_requests_that_failed() {
+            let camera = Camera::simulated();
+            let recording =
+                Recording::parse("0 GET_LEN 0x07 0x01 - errno=22\n0 GET_LEN 0x02 0x06 0000\n")
+                    .unwrap();
+            let mut replayed = Vec::new();
+
+            recording
+                .replay(&camera, false, |number, _| replayed.push(number))
+                .unwrap();
+
+            assert_eq!(replayed, [2]);
+        }
+    }
+}
@@ -0,0 +1,61 @@
+// SPDX-License-Identifier: EUPL-1.2
+
+use crate::libs::errors::T4lError;
+use crate::libs::recording::{HEADER, RecordedRequest};
+use crate::libs::usbio::UVC_SET_CUR;
+use errno::Errno;
+use std::fs::File;
+use std::io::{LineWriter, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// Writes the requests a transport sends to its camera to a file, as lines of a
+/// [`Recording`](crate::Recording).
+pub(crate) struct Recorder {
+    started: Instant,
+    file: Mutex<LineWriter<File>>,
+}
+
+impl Recorder {
+    /// Creates the file, replacing an existing one.
+    pub(crate) fn create(path: &Path) -> Result<Self, T4lError> {
+        let mut file = LineWriter::new(File::create(path)?);
+        writeln!(file, "{}", HEADER)?;
+
+        Ok(Self {
+            started: Instant::now(),
+            file: Mutex::new(file),
+        })
+    }
+
+    /// Records the request with the data sent or answered, or with its error if it failed.
+    pub(crate) fn record(
+        &self,
+        query: u8,
+        unit: u8,
+        selector: u8,
+        data: &[u8],
+        error: Option<Errno>,
+    ) {
+        // a failed GET request wasn't answered
+        let data = match error {
+            Some(_) if query != UVC_SET_CUR => Vec::new(),
+            _ => data.to_vec(),
+        };
+        let request = RecordedRequest {
+            elapsed: self.started.elapsed(),
+            query,
+            unit,
+            selector,
+            data,
+            truncated: None,
+            errno: error.map(|error| error.0),
+        };
+
+        // a recording that can't be written mustn't keep the camera from being controlled
+        if let Ok(mut file) = self.file.lock() {
+            let _ = writeln!(file, "{}", request);
+        }
+    }
+}
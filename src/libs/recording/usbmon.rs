@@ -0,0 +1,731 @@
+// SPDX-License-Identifier: EUPL-1.2
+
+use crate::libs::errors::T4lError;
+use crate::libs::recording::{QUERY_NAMES, RecordedRequest, Recording};
+use crate::libs::usbio::UVC_SET_CUR;
+use std::collections::HashMap;
+use std::str;
+use std::time::Duration;
+
+/// The timestamps of usbmon are microseconds that start over every 4096 seconds.
+const TIMESTAMP_PERIOD: u64 = 4096 * 1_000_000;
+
+/// Request types of class requests to an interface, as UVC sends them to its units.
+const CLASS_INTERFACE_OUT: u64 = 0x21;
+const CLASS_INTERFACE_IN: u64 = 0xa1;
+
+/// The magic numbers of pcap files with timestamps in microseconds or nanoseconds.
+const PCAP_MAGIC: u32 = 0xa1b2c3d4;
+const PCAP_MAGIC_NANOSECONDS: u32 = 0xa1b23c4d;
+const PCAP_HEADER_SIZE: usize = 24;
+const PCAP_RECORD_HEADER_SIZE: usize = 16;
+
+/// The link types of usbmon packets, with the header of `struct usbmon_packet` and with the
+/// longer one of its memory-mapped interface, which tcpdump and Wireshark capture.
+const LINKTYPE_USB_LINUX: u32 = 189;
+const LINKTYPE_USB_LINUX_MMAPPED: u32 = 220;
+const USBMON_HEADER_SIZE: usize = 48;
+const USBMON_MMAPPED_HEADER_SIZE: usize = 64;
+
+/// The transfer type of control transfers in a usbmon packet.
+const XFER_TYPE_CONTROL: u8 = 2;
+/// The flags of a usbmon packet that has a setup packet or data, otherwise they're a character.
+const SETUP_PRESENT: u8 = 0;
+const DATA_PRESENT: u8 = 0;
+
+/// A control request submitted to the device, waiting for its completion.
+struct Submission {
+    /// The line or packet of the capture.
+    number: usize,
+    timestamp: u64,
+    query: u8,
+    unit: u8,
+    selector: u8,
+    length: usize,
+    data: Vec<u8>,
+}
+
+impl Recording {
+    /// Imports the UVC requests of a capture of usbmon, as pcap or in its text format,
+    /// see [`Recording::from_pcap`] and [`Recording::from_usbmon`].
+    pub fn from_capture(bytes: &[u8]) -> Result<(Self, usize), T4lError> {
+        if bytes.len() >= 4 && PcapByteOrder::of(bytes[..4].try_into().unwrap()).is_some() {
+            return Self::from_pcap(bytes);
+        }
+
+        let text = str::from_utf8(bytes)
+            .map_err(|_| T4lError::InvalidCapture("neither pcap nor text".to_string()))?;
+        Self::from_usbmon(text)
+    }
+
+    /// Imports the UVC requests of a capture in the text format of usbmon, e.g. read from
+    /// `/sys/kernel/debug/usb/usbmon/1u` while the OBSBOT app runs in a virtual machine.
+    ///
+    /// The class requests to units are kept with the numbers of their lines in the capture,
+    /// failed ones with their errno. As usbmon cuts off the data after 32 bytes, longer data is
+    /// marked as truncated, and the number of requests this happened to is returned with the
+    /// recording. Captures of the full data are read by [`Recording::from_pcap`].
+    pub fn from_usbmon(text: &str) -> Result<(Self, usize), T4lError> {
+        let mut importer = Importer::new(Some(TIMESTAMP_PERIOD));
+
+        for (number, line) in (1..).zip(text.lines()) {
+            let invalid = |reason: String| T4lError::InvalidRecording(number, reason);
+            let words: Vec<&str> = line.split_whitespace().collect();
+
+            match words.as_slice() {
+                [
+                    tag,
+                    timestamp,
+                    "S",
+                    address,
+                    "s",
+                    request_type,
+                    request,
+                    value,
+                    index,
+                    length,
+                    rest @ ..,
+                ] if address.starts_with("Ci:") || address.starts_with("Co:") => {
+                    let request_type = parse_hex(request_type, "request type").map_err(invalid)?;
+                    let query = parse_hex(request, "request").map_err(invalid)?;
+                    if !is_uvc_request(request_type, query) {
+                        continue;
+                    }
+
+                    let value = parse_hex(value, "value").map_err(invalid)?;
+                    let index = parse_hex(index, "index").map_err(invalid)?;
+                    importer.submissions.insert(
+                        parse_hex(tag, "tag").map_err(invalid)?,
+                        Submission {
+                            number,
+                            timestamp: parse_timestamp(timestamp).map_err(invalid)?,
+                            query: query as u8,
+                            unit: (index >> 8) as u8,
+                            selector: (value >> 8) as u8,
+                            length: parse_hex(length, "length").map_err(invalid)? as usize,
+                            data: parse_data(rest).map_err(invalid)?,
+                        },
+                    );
+                }
+                [tag, _, "C", _, status, length, rest @ ..] => {
+                    let tag = parse_hex(tag, "tag").map_err(invalid)?;
+                    let Some(submission) = importer.submissions.remove(&tag) else {
+                        continue;
+                    };
+                    let status = status
+                        .parse()
+                        .map_err(|_| invalid(format!("invalid status `{}`", status)))?;
+                    let length = length
+                        .parse()
+                        .map_err(|_| invalid(format!("invalid length `{}`", length)))?;
+
+                    importer.complete(
+                        submission,
+                        status,
+                        length,
+                        parse_data(rest).map_err(invalid)?,
+                    );
+                }
+                _ => continue,
+            }
+        }
+
+        Ok(importer.finish())
+    }
+
+    /// Imports the UVC requests of a pcap capture of the binary usbmon packets, e.g. written by
+    /// `tcpdump -i usbmon1 -w obsbot.pcap`, which keeps the full data of the requests.
+    ///
+    /// The requests are kept with the numbers of their packets in the capture, see
+    /// [`Recording::from_usbmon`]. Data is only marked as truncated if the capture cut it off,
+    /// e.g. because of its snapshot length.
+    pub fn from_pcap(bytes: &[u8]) -> Result<(Self, usize), T4lError> {
+        let invalid = |reason: String| T4lError::InvalidCapture(reason);
+        let header = bytes
+            .first_chunk::<PCAP_HEADER_SIZE>()
+            .ok_or_else(|| invalid("incomplete pcap header".to_string()))?;
+        let order = PcapByteOrder::of(header[..4].try_into().unwrap())
+            .ok_or_else(|| invalid("not a pcap file".to_string()))?;
+        let header_size = match order.u32(&header[20..24]) {
+            LINKTYPE_USB_LINUX => USBMON_HEADER_SIZE,
+            LINKTYPE_USB_LINUX_MMAPPED => USBMON_MMAPPED_HEADER_SIZE,
+            link_type => return Err(invalid(format!("link type {} isn't usbmon", link_type))),
+        };
+
+        let mut importer = Importer::new(None);
+        let mut records = &bytes[PCAP_HEADER_SIZE..];
+
+        for number in 1.. {
+            if records.is_empty() {
+                break;
+            }
+            let invalid = |reason: &str| invalid(format!("packet {}: {}", number, reason));
+            let record = records
+                .first_chunk::<PCAP_RECORD_HEADER_SIZE>()
+                .ok_or_else(|| invalid("incomplete record header"))?;
+            let end = PCAP_RECORD_HEADER_SIZE + order.u32(&record[8..12]) as usize;
+            let packet = records
+                .get(PCAP_RECORD_HEADER_SIZE..end)
+                .ok_or_else(|| invalid("incomplete packet"))?;
+            records = &records[end..];
+
+            if packet.len() < header_size {
+                return Err(invalid("incomplete usbmon header"));
+            }
+            let (urb, data) = packet.split_at(header_size);
+            if urb[9] != XFER_TYPE_CONTROL {
+                continue;
+            }
+            let id = order.u64(&urb[0..8]);
+            let timestamp = order.u64(&urb[16..24]) * 1_000_000 + order.u32(&urb[24..28]) as u64;
+            let status = order.u32(&urb[28..32]) as i32;
+            let length = order.u32(&urb[32..36]) as usize;
+            // the data may have been cut off by the capture, but not extended
+            let captured = data.len().min(order.u32(&urb[36..40]) as usize);
+            let data = match urb[15] {
+                DATA_PRESENT => data[..captured].to_vec(),
+                _ => Vec::new(),
+            };
+
+            match urb[8] {
+                b'S' if urb[14] == SETUP_PRESENT => {
+                    let setup = &urb[40..48];
+                    if !is_uvc_request(setup[0].into(), setup[1].into()) {
+                        continue;
+                    }
+
+                    importer.submissions.insert(
+                        id,
+                        Submission {
+                            number,
+                            timestamp,
+                            query: setup[1],
+                            unit: setup[5],
+                            selector: setup[3],
+                            length: order.u16(&setup[6..8]).into(),
+                            data,
+                        },
+                    );
+                }
+                b'C' => {
+                    if let Some(submission) = importer.submissions.remove(&id) {
+                        importer.complete(submission, status, length, data);
+                    }
+                }
+                _ => continue,
+            }
+        }
+
+        Ok(importer.finish())
+    }
+}
+
+/// Whether the request is a class request to a unit, with a request code of UVC.
+fn is_uvc_request(request_type: u64, query: u64) -> bool {
+    (request_type == CLASS_INTERFACE_OUT || request_type == CLASS_INTERFACE_IN)
+        && QUERY_NAMES.iter().any(|(known, _)| *known as u64 == query)
+}
+
+/// Pairs the control requests submitted to the device with their completions.
+struct Importer {
+    submissions: HashMap<u64, Submission>,
+    requests: Vec<(usize, RecordedRequest)>,
+    truncated: usize,
+    /// The microseconds after which the timestamps start over, if they do.
+    period: Option<u64>,
+    /// The timestamp of the last request and the microseconds since the first.
+    clock: Option<(u64, u64)>,
+}
+
+impl Importer {
+    fn new(period: Option<u64>) -> Self {
+        Self {
+            submissions: HashMap::new(),
+            requests: Vec::new(),
+            truncated: 0,
+            period,
+            clock: None,
+        }
+    }
+
+    /// Records the submitted request with the data it was sent with, or the answer in the
+    /// completion, for a failed request with its errno.
+    fn complete(&mut self, submission: Submission, status: i32, length: usize, data: Vec<u8>) {
+        let errno = (status != 0).then_some(-status);
+
+        let (data, length) = if submission.query == UVC_SET_CUR {
+            (submission.data, submission.length)
+        } else if errno.is_some() {
+            // a failed GET request wasn't answered
+            (Vec::new(), 0)
+        } else {
+            (data, length)
+        };
+        let cut_off = data.len() < length;
+        if cut_off {
+            self.truncated += 1;
+        }
+
+        // the time since the first request, counting the timestamps starting over
+        let elapsed = match self.clock {
+            None => 0,
+            Some((previous, elapsed)) => match self.period {
+                Some(period) => elapsed + (submission.timestamp + period - previous) % period,
+                None => elapsed + submission.timestamp.saturating_sub(previous),
+            },
+        };
+        self.clock = Some((submission.timestamp, elapsed));
+
+        self.requests.push((
+            submission.number,
+            RecordedRequest {
+                elapsed: Duration::from_micros(elapsed),
+                query: submission.query,
+                unit: submission.unit,
+                selector: submission.selector,
+                data,
+                truncated: cut_off.then_some(length),
+                errno,
+            },
+        ));
+    }
+
+    /// The recording of the completed requests and the number of them with truncated data.
+    fn finish(self) -> (Recording, usize) {
+        (
+            Recording {
+                requests: self.requests,
+            },
+            self.truncated,
+        )
+    }
+}
+
+/// The byte order of a pcap file, which is the one of the machine that captured it.
+#[derive(Clone, Copy)]
+struct PcapByteOrder {
+    big_endian: bool,
+}
+
+impl PcapByteOrder {
+    /// Tells the byte order from the magic number at the start of the file, if it's pcap.
+    fn of(magic: [u8; 4]) -> Option<Self> {
+        let little_endian = u32::from_le_bytes(magic);
+        let big_endian = u32::from_be_bytes(magic);
+
+        match (little_endian, big_endian) {
+            (PCAP_MAGIC | PCAP_MAGIC_NANOSECONDS, _) => Some(Self { big_endian: false }),
+            (_, PCAP_MAGIC | PCAP_MAGIC_NANOSECONDS) => Some(Self { big_endian: true }),
+            _ => None,
+        }
+    }
+
+    fn u16(self, bytes: &[u8]) -> u16 {
+        let bytes = bytes.try_into().unwrap();
+        match self.big_endian {
+            true => u16::from_be_bytes(bytes),
+            false => u16::from_le_bytes(bytes),
+        }
+    }
+
+    fn u32(self, bytes: &[u8]) -> u32 {
+        let bytes = bytes.try_into().unwrap();
+        match self.big_endian {
+            true => u32::from_be_bytes(bytes),
+            false => u32::from_le_bytes(bytes),
+        }
+    }
+
+    fn u64(self, bytes: &[u8]) -> u64 {
+        let bytes = bytes.try_into().unwrap();
+        match self.big_endian {
+            true => u64::from_be_bytes(bytes),
+            false => u64::from_le_bytes(bytes),
+        }
+    }
+}
+
+fn parse_hex(text: &str, name: &str) -> Result<u64, String> {
+    u64::from_str_radix(text, 16).map_err(|_| format!("invalid {} `{}`", name, text))
+}
+
+fn parse_timestamp(text: &str) -> Result<u64, String> {
+    text.parse()
+        .map_err(|_| format!("invalid timestamp `{}`", text))
+}
+
+/// Decodes the data words following `=`, if the line has any.
+fn parse_data(words: &[&str]) -> Result<Vec<u8>, String> {
+    match words.iter().position(|word| *word == "=") {
+        Some(position) => hex::decode(words[position + 1..].concat())
+            .map_err(|error| format!("invalid data: {}", error)),
+        None => Ok(Vec::new()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod unit {
+        use super::{bulk_packet, packet, pcap, record, setup};
+        use crate::{Recording, T4lError};
+        use std::time::Duration;
+
+        const CAPTURE: &str = "\
+ffff8881 3000000 S Ci:1:005:0 s a1 85 0200 0200 0002 2 <
+ffff8881 3000150 C Ci:1:005:0 0 2 = 3c00
+ffff8882 3000400 S Co:1:005:0 s 21 01 0200 0200 003c 60 = aa250000 0c00aaa1 0a04c40c 010067fe 02000000 00000000 00000000 00000000
+ffff8882 3000900 C Co:1:005:0 0 60 >
+ffff8883 3001000 S Bi:1:005:1 -115 512 <
+ffff8884 3002000 S Ci:1:005:0 s 80 06 0100 0000 0012 18 <
+ffff8884 3002100 C Ci:1:005:0 0 18 = 12010002 ef020140
+ffff8885 4095999000 S Ci:1:005:0 s a1 81 0600 0200 003c 60 <
+ffff8885 0000500 C Ci:1:005:0 -32 0
+ffff8886 0001000 S Ci:1:005:0 s a1 81 0600 0200 0004 4 <
+ffff8886 0001200 C Ci:1:005:0 0 4 = 01020304
+";
+
+        #[test]
+        fn imports_uvc_requests() {
+            let (recording, truncated) = Recording::from_usbmon(CAPTURE).unwrap();
+
+            let requests: Vec<_> = recording
+                .requests()
+                .iter()
+                .map(|(number, request)| {
+                    (
+                        *number,
+                        request.query,
+                        request.unit,
+                        request.selector,
+                        request.data.len(),
+                        request.truncated,
+                        request.errno,
+                    )
+                })
+                .collect();
+            assert_eq!(
+                requests,
+                [
+                    (1, 0x85, 0x2, 0x2, 2, None, None),
+                    (3, 0x01, 0x2, 0x2, 32, Some(60), None),
+                    (8, 0x81, 0x2, 0x6, 0, None, Some(32)),
+                    (10, 0x81, 0x2, 0x6, 4, None, None),
+                ]
+            );
+            assert_eq!(truncated, 1, "only the data of SET_CUR was cut off");
+            assert_eq!(recording.requests()[0].1.data, [0x3c, 0x00]);
+            assert_eq!(
+                recording.requests()[1].1.data[..4],
+                [0xaa, 0x25, 0x00, 0x00]
+            );
+        }
+
+        #[test]
+        fn counts_time_since_first_request() {
+            let (recording, _) = Recording::from_usbmon(CAPTURE).unwrap();
+
+            assert_eq!(recording.requests()[0].1.elapsed, Duration::ZERO);
+            assert_eq!(
+                recording.requests()[1].1.elapsed,
+                Duration::from_micros(400)
+            );
+            assert_eq!(
+                recording.requests()[2].1.elapsed,
+                Duration::from_micros(4_092_999_000)
+            );
+            // the timestamps started over in between
+            assert_eq!(
+                recording.requests()[3].1.elapsed,
+                Duration::from_micros(4_093_001_000)
+            );
+        }
+
+        #[test]
+        fn reports_invalid_line() {
+            let error = Recording::from_usbmon(
+                "ffff8881 3000000 S Ci:1:005:0 s a1 85 0200 0200 0002 2 <\n\
+                 ffff8881 3000150 C Ci:1:005:0 0 2 = 3c0\n",
+            )
+            .unwrap_err();
+
+            assert!(matches!(error, T4lError::InvalidRecording(2, _)));
+        }
+
+        #[test]
+        fn imports_full_data_of_pcap() {
+            let frame = [0xaa; 60];
+            let status = [0x27; 60];
+            let capture = pcap(&[
+                packet(
+                    1,
+                    b'S',
+                    3_000_000,
+                    0,
+                    Some(setup(0x21, 0x01, 0x02, 0x02, 60)),
+                    60,
+                    &frame,
+                ),
+                packet(1, b'C', 3_000_500, 0, None, 60, &[]),
+                // a bulk transfer and a standard request of the same device
+                bulk_packet(2, b'S', 3_000_600),
+                packet(
+                    3,
+                    b'S',
+                    3_000_700,
+                    0,
+                    Some(setup(0x80, 0x06, 0x01, 0x00, 18)),
+                    18,
+                    &[],
+                ),
+                packet(3, b'C', 3_000_800, 0, None, 18, &[0x12; 18]),
+                packet(
+                    4,
+                    b'S',
+                    3_001_000,
+                    0,
+                    Some(setup(0xa1, 0x81, 0x06, 0x02, 60)),
+                    60,
+                    &[],
+                ),
+                packet(4, b'C', 3_001_200, 0, None, 60, &status),
+                packet(
+                    5,
+                    b'S',
+                    3_002_000,
+                    0,
+                    Some(setup(0xa1, 0x81, 0x06, 0x02, 60)),
+                    60,
+                    &[],
+                ),
+                packet(5, b'C', 3_002_100, -32, None, 0, &[]),
+            ]);
+
+            let (recording, truncated) = Recording::from_pcap(&capture).unwrap();
+
+            let requests: Vec<_> = recording
+                .requests()
+                .iter()
+                .map(|(number, request)| {
+                    (
+                        *number,
+                        request.query,
+                        request.unit,
+                        request.selector,
+                        request.data.len(),
+                        request.truncated,
+                        request.errno,
+                    )
+                })
+                .collect();
+            assert_eq!(
+                requests,
+                [
+                    (1, 0x01, 0x2, 0x2, 60, None, None),
+                    (6, 0x81, 0x2, 0x6, 60, None, None),
+                    (8, 0x81, 0x2, 0x6, 0, None, Some(32)),
+                ]
+            );
+            assert_eq!(truncated, 0);
+            assert_eq!(recording.requests()[0].1.data, frame);
+            assert_eq!(recording.requests()[1].1.data, status);
+            assert_eq!(
+                recording.requests()[1].1.elapsed,
+                Duration::from_micros(1_000)
+            );
+        }
+
+        #[test]
+        fn marks_data_cut_off_by_pcap() {
+            let submission = packet(
+                1,
+                b'S',
+                0,
+                0,
+                Some(setup(0x21, 0x01, 0x02, 0x02, 60)),
+                60,
+                &[0xaa; 60],
+            );
+            // a snapshot length of 80 bytes keeps 16 bytes of the data
+            let mut cut_off = record(&submission[..80]);
+            cut_off[12..16].copy_from_slice(&(submission.len() as u32).to_le_bytes());
+            let mut capture = pcap(&[]);
+            capture.extend(cut_off);
+            capture.extend(record(&packet(1, b'C', 100, 0, None, 60, &[])));
+
+            let (recording, truncated) = Recording::from_pcap(&capture).unwrap();
+
+            assert_eq!(truncated, 1);
+            assert_eq!(recording.requests()[0].1.data.len(), 16);
+            assert_eq!(recording.requests()[0].1.truncated, Some(60));
+        }
+
+        #[test]
+        fn rejects_other_link_types() {
+            let mut capture = pcap(&[]);
+            capture[20..24].copy_from_slice(&1u32.to_le_bytes());
+
+            let error = Recording::from_pcap(&capture).unwrap_err();
+
+            assert!(matches!(error, T4lError::InvalidCapture(_)));
+        }
+
+        #[test]
+        fn rejects_incomplete_packet() {
+            let mut capture = pcap(&[packet(1, b'S', 0, 0, None, 0, &[])]);
+            capture.truncate(capture.len() - 1);
+
+            let error = Recording::from_pcap(&capture).unwrap_err();
+
+            assert!(
+                matches!(error, T4lError::InvalidCapture(reason) if reason.starts_with("packet 1"))
+            );
+        }
+
+        #[test]
+        fn reads_big_endian_pcap() {
+            let mut capture = pcap(&[]);
+            capture[0..4].copy_from_slice(&0xa1b2c3d4u32.to_be_bytes());
+            capture[20..24].copy_from_slice(&220u32.to_be_bytes());
+
+            assert!(
+                Recording::from_pcap(&capture)
+                    .unwrap()
+                    .0
+                    .requests()
+                    .is_empty()
+            );
+        }
+
+        #[test]
+        fn imports_capture_in_either_format() {
+            let capture = pcap(&[
+                packet(
+                    1,
+                    b'S',
+                    0,
+                    0,
+                    Some(setup(0xa1, 0x85, 0x02, 0x02, 2)),
+                    2,
+                    &[],
+                ),
+                packet(1, b'C', 150, 0, None, 2, &[0x3c, 0x00]),
+            ]);
+
+            let (from_pcap, _) = Recording::from_capture(&capture).unwrap();
+            let (from_text, _) = Recording::from_capture(CAPTURE.as_bytes()).unwrap();
+
+            assert_eq!(from_pcap.requests()[0].1.data, [0x3c, 0x00]);
+            assert_eq!(from_text.requests().len(), 4);
+            assert!(matches!(
+                Recording::from_capture(&[0xff, 0xfe, 0x00, 0x80]),
+                Err(T4lError::InvalidCapture(_))
+            ));
+        }
+    }
+
+    mod integration {
+        use super::{packet, pcap, setup};
+        use crate::{Camera, Recording, SleepCommand, SleepMode, Tiny2Camera};
+
+        #[test]
+        fn replays_frame_imported_from_pcap() {
+            let camera = Camera::simulated();
+            let mut frame = [0u8; 60];
+            frame[..36].copy_from_slice(&SleepCommand::build(SleepMode::Sleep).unwrap());
+            let capture = pcap(&[
+                packet(
+                    1,
+                    b'S',
+                    0,
+                    0,
+                    Some(setup(0x21, 0x01, 0x02, 0x02, 60)),
+                    60,
+                    &frame,
+                ),
+                packet(1, b'C', 400, 0, None, 60, &[]),
+            ]);
+
+            let (recording, truncated) = Recording::from_capture(&capture).unwrap();
+            assert_eq!(truncated, 0);
+            // as written by `recording import` and read by `recording replay`
+            let recording = Recording::parse(&recording.to_string()).unwrap();
+            recording.replay(&camera, false, |_, _| {}).unwrap();
+
+            assert_eq!(camera.get_sleep_mode().unwrap(), SleepMode::Sleep);
+        }
+    }
+
+    /// The setup packet of a control request.
+    fn setup(request_type: u8, request: u8, selector: u8, unit: u8, length: u16) -> [u8; 8] {
+        let length = length.to_le_bytes();
+        [
+            request_type,
+            request,
+            0x00,
+            selector,
+            0x00,
+            unit,
+            length[0],
+            length[1],
+        ]
+    }
+
+    /// A usbmon packet of a control transfer, as captured on the memory-mapped interface.
+    fn packet(
+        id: u64,
+        event: u8,
+        timestamp: u64,
+        status: i32,
+        setup: Option<[u8; 8]>,
+        length: u32,
+        data: &[u8],
+    ) -> Vec<u8> {
+        let mut packet = vec![0u8; super::USBMON_MMAPPED_HEADER_SIZE];
+        packet[0..8].copy_from_slice(&id.to_le_bytes());
+        packet[8] = event;
+        packet[9] = super::XFER_TYPE_CONTROL;
+        packet[11] = 5;
+        packet[12..14].copy_from_slice(&1u16.to_le_bytes());
+        packet[14] = if setup.is_some() { 0 } else { b'-' };
+        packet[15] = if data.is_empty() { b'<' } else { 0 };
+        packet[16..24].copy_from_slice(&(timestamp / 1_000_000).to_le_bytes());
+        packet[24..28].copy_from_slice(&((timestamp % 1_000_000) as u32).to_le_bytes());
+        packet[28..32].copy_from_slice(&status.to_le_bytes());
+        packet[32..36].copy_from_slice(&length.to_le_bytes());
+        packet[36..40].copy_from_slice(&(data.len() as u32).to_le_bytes());
+        packet[40..48].copy_from_slice(&setup.unwrap_or_default());
+        packet.extend_from_slice(data);
+        packet
+    }
+
+    /// A usbmon packet of a bulk transfer, which isn't imported.
+    fn bulk_packet(id: u64, event: u8, timestamp: u64) -> Vec<u8> {
+        let mut packet = packet(id, event, timestamp, 0, None, 512, &[]);
+        packet[9] = 3;
+        packet
+    }
+
+    /// The packet with the header of its pcap record.
+    fn record(packet: &[u8]) -> Vec<u8> {
+        let mut record = vec![0u8; 8];
+        record.extend_from_slice(&(packet.len() as u32).to_le_bytes());
+        record.extend_from_slice(&(packet.len() as u32).to_le_bytes());
+        record.extend_from_slice(packet);
+        record
+    }
+
+    /// A little-endian pcap file of the packets, with the link type of the memory-mapped interface.
+    fn pcap(packets: &[Vec<u8>]) -> Vec<u8> {
+        let mut pcap = Vec::new();
+        pcap.extend_from_slice(&super::PCAP_MAGIC.to_le_bytes());
+        pcap.extend_from_slice(&2u16.to_le_bytes());
+        pcap.extend_from_slice(&4u16.to_le_bytes());
+        pcap.extend_from_slice(&[0u8; 8]);
+        pcap.extend_from_slice(&65535u32.to_le_bytes());
+        pcap.extend_from_slice(&super::LINKTYPE_USB_LINUX_MMAPPED.to_le_bytes());
+        for packet in packets {
+            pcap.extend(record(packet));
+        }
+        pcap
+    }
+}
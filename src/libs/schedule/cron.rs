@@ -0,0 +1,396 @@
+// SPDX-License-Identifier: EUPL-1.2
+
+use crate::libs::errors::T4lError;
+use nix::libc;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::mem::MaybeUninit;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const MONTH_NAMES: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+const WEEKDAY_NAMES: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+const SYSTEMD_WEEKDAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+
+/// A point in the local time zone, precise to the minute as the cron expressions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LocalTime {
+    pub minute: u32,
+    pub hour: u32,
+    /// Day of the month, from 1.
+    pub day: u32,
+    /// Month, from 1 for January.
+    pub month: u32,
+    /// Day of the week, from 0 for Sunday.
+    pub weekday: u32,
+}
+
+impl LocalTime {
+    pub fn now() -> Self {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs() as i64)
+            .unwrap_or_default();
+
+        Self::at(seconds)
+    }
+
+    /// Converts seconds since the Unix epoch to the local time zone.
+    ///
+    /// Falls back to UTC if the time zone can't be applied, e.g. for a timestamp out of its range.
+    pub fn at(timestamp: i64) -> Self {
+        let Some(tm) = local_tm(timestamp) else {
+            return Self::utc(timestamp);
+        };
+
+        Self {
+            minute: tm.tm_min as u32,
+            hour: tm.tm_hour as u32,
+            day: tm.tm_mday as u32,
+            month: tm.tm_mon as u32 + 1,
+            weekday: tm.tm_wday as u32,
+        }
+    }
+
+    /// Converts seconds since the Unix epoch to UTC, with the days counted as in the proleptic
+    /// Gregorian calendar, see <https://howardhinnant.github.io/date_algorithms.html#civil_from_days>.
+    fn utc(timestamp: i64) -> Self {
+        let days = timestamp.div_euclid(86_400);
+        let seconds = timestamp.rem_euclid(86_400);
+
+        // the days are counted from the 1st of March, so that the leap day is the last one
+        let day_of_era = (days + 719_468).rem_euclid(146_097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+
+        Self {
+            minute: (seconds / 60 % 60) as u32,
+            hour: (seconds / 3_600) as u32,
+            day: (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32,
+            month: if shifted_month < 10 {
+                shifted_month + 3
+            } else {
+                shifted_month - 9
+            } as u32,
+            // the epoch was a Thursday
+            weekday: (days + 4).rem_euclid(7) as u32,
+        }
+    }
+}
+
+/// Splits the timestamp into the fields of the local time zone with `localtime_r`, the thread-safe
+/// variant of `localtime`, as the standard library has no access to the time zone.
+fn local_tm(timestamp: i64) -> Option<libc::tm> {
+    let time = timestamp as libc::time_t;
+    let mut tm = MaybeUninit::<libc::tm>::uninit();
+
+    // SAFETY: both pointers are valid for the duration of the call and not kept afterwards.
+    // localtime_r only writes to `tm` and, unlike localtime, doesn't share a static buffer
+    // between threads. It returns null if it fails, and fills all fields of `tm` otherwise.
+    let result = unsafe { libc::localtime_r(&time, tm.as_mut_ptr()) };
+    if result.is_null() {
+        return None;
+    }
+
+    // SAFETY: localtime_r succeeded, so it initialized `tm`
+    Some(unsafe { tm.assume_init() })
+}
+
+/// The times a rule of the schedule runs at, as a cron expression of the five fields
+/// minute, hour, day of the month, month and day of the week, e.g. `30 8 * * mon-fri`.
+///
+/// A field is either `*`, a value, a range like `1-5` or a list of these, each optionally with a
+/// step like `*/15`. Months and days of the week can also be given by their English abbreviation,
+/// Sunday is both 0 and 7. As in cron, a time matches if either the day of the month or the day of
+/// the week matches while both are restricted. The shortcuts `@hourly`, `@daily`, `@weekly`,
+/// `@monthly` and `@yearly` are supported as well.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct CronExpression {
+    expression: String,
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    any_day: bool,
+    any_weekday: bool,
+}
+
+impl CronExpression {
+    pub fn matches(&self, time: &LocalTime) -> bool {
+        let day = has_bit(self.days, time.day);
+        let weekday = has_bit(self.weekdays, time.weekday);
+        let day_matches = if self.any_day || self.any_weekday {
+            day && weekday
+        } else {
+            day || weekday
+        };
+
+        has_bit(self.minutes, time.minute)
+            && has_bit(self.hours, time.hour)
+            && has_bit(self.months, time.month)
+            && day_matches
+    }
+
+    /// The same times as calendar events of systemd, see `systemd.time(7)`.
+    ///
+    /// Systemd requires both the day of the month and the day of the week to match,
+    /// so an expression restricting both becomes two events.
+    pub fn to_systemd_calendar(&self) -> Vec<String> {
+        let time = format!(
+            "{}:{}:00",
+            values(self.hours, 0, 23, |hour| format!("{:02}", hour)),
+            values(self.minutes, 0, 59, |minute| format!("{:02}", minute)),
+        );
+        let months = values(self.months, 1, 12, |month| format!("{:02}", month));
+        let days = values(self.days, 1, 31, |day| format!("{:02}", day));
+        let weekdays = values(self.weekdays, 0, 6, |weekday| {
+            SYSTEMD_WEEKDAYS[weekday as usize].to_string()
+        });
+
+        let with_days = format!("*-{}-{} {}", months, days, time);
+        let with_weekdays = format!("{} *-{}-* {}", weekdays, months, time);
+
+        match (self.any_day, self.any_weekday) {
+            (false, false) => vec![with_days, with_weekdays],
+            (_, false) => vec![format!("{} *-{}-{} {}", weekdays, months, days, time)],
+            (_, true) => vec![with_days],
+        }
+    }
+}
+
+impl FromStr for CronExpression {
+    type Err = T4lError;
+
+    fn from_str(expression: &str) -> Result<Self, Self::Err> {
+        let expression = expression.trim();
+        let invalid = |reason: String| T4lError::InvalidSchedule(expression.to_string(), reason);
+
+        let fields: Vec<&str> = match expression {
+            "@hourly" => vec!["0", "*", "*", "*", "*"],
+            "@daily" | "@midnight" => vec!["0", "0", "*", "*", "*"],
+            "@weekly" => vec!["0", "0", "*", "*", "0"],
+            "@monthly" => vec!["0", "0", "1", "*", "*"],
+            "@yearly" | "@annually" => vec!["0", "0", "1", "1", "*"],
+            _ => expression.split_whitespace().collect(),
+        };
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(invalid(format!(
+                "expected 5 fields, found {}",
+                fields.len()
+            )));
+        };
+
+        let mut weekdays = parse_field(weekday, 0, 7, &WEEKDAY_NAMES, 0).map_err(invalid)?;
+        // Sunday is both 0 and 7
+        if has_bit(weekdays, 7) {
+            weekdays = (weekdays & !(1 << 7)) | 1;
+        }
+
+        Ok(Self {
+            expression: expression.to_string(),
+            minutes: parse_field(minute, 0, 59, &[], 0).map_err(invalid)?,
+            hours: parse_field(hour, 0, 23, &[], 0).map_err(invalid)?,
+            days: parse_field(day, 1, 31, &[], 0).map_err(invalid)?,
+            months: parse_field(month, 1, 12, &MONTH_NAMES, 1).map_err(invalid)?,
+            weekdays,
+            any_day: day.starts_with('*'),
+            any_weekday: weekday.starts_with('*'),
+        })
+    }
+}
+
+impl TryFrom<String> for CronExpression {
+    type Error = T4lError;
+
+    fn try_from(expression: String) -> Result<Self, Self::Error> {
+        expression.parse()
+    }
+}
+
+impl From<CronExpression> for String {
+    fn from(expression: CronExpression) -> Self {
+        expression.expression
+    }
+}
+
+impl Display for CronExpression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.expression)
+    }
+}
+
+fn has_bit(mask: u64, value: u32) -> bool {
+    value < 64 && mask & (1 << value) != 0
+}
+
+/// Parses one field into a bit mask of the values it allows.
+///
+/// The names are counted from `first_name`, e.g. 1 for January.
+fn parse_field(
+    field: &str,
+    min: u32,
+    max: u32,
+    names: &[&str],
+    first_name: u32,
+) -> Result<u64, String> {
+    let mut mask = 0;
+
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => match step.parse::<usize>() {
+                Ok(step) if step > 0 => (range, Some(step)),
+                _ => return Err(format!("invalid step `{}`", step)),
+            },
+            None => (part, None),
+        };
+
+        let (start, end) = match range.split_once('-') {
+            _ if range == "*" => (min, max),
+            Some((start, end)) => (
+                parse_value(start, names, first_name)?,
+                parse_value(end, names, first_name)?,
+            ),
+            // a single value with a step counts up to the maximum, as in cron
+            None => {
+                let start = parse_value(range, names, first_name)?;
+                (start, if step.is_some() { max } else { start })
+            }
+        };
+
+        if start < min || end > max || start > end {
+            return Err(format!("`{}` is out of the range {}-{}", range, min, max));
+        }
+
+        for value in (start..=end).step_by(step.unwrap_or(1)) {
+            mask |= 1 << value;
+        }
+    }
+
+    Ok(mask)
+}
+
+fn parse_value(value: &str, names: &[&str], first_name: u32) -> Result<u32, String> {
+    if let Ok(number) = value.parse() {
+        return Ok(number);
+    }
+
+    names
+        .iter()
+        .position(|name| name.eq_ignore_ascii_case(value))
+        .map(|index| index as u32 + first_name)
+        .ok_or_else(|| format!("invalid value `{}`", value))
+}
+
+/// Lists the values of the mask for systemd, or `*` if it allows all of them.
+fn values(mask: u64, min: u32, max: u32, format: impl Fn(u32) -> String) -> String {
+    if (min..=max).all(|value| has_bit(mask, value)) {
+        return "*".to_string();
+    }
+
+    (min..=max)
+        .filter(|value| has_bit(mask, *value))
+        .map(format)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    mod unit {
+        use crate::{CronExpression, LocalTime};
+        use test_case::test_case;
+
+        fn time(hour: u32, minute: u32, day: u32, weekday: u32) -> LocalTime {
+            LocalTime {
+                minute,
+                hour,
+                day,
+                month: 3,
+                weekday,
+            }
+        }
+
+        fn cron(expression: &str) -> CronExpression {
+            expression.parse().unwrap()
+        }
+
+        #[test_case("30 8 * * 1-5", time(8, 30, 10, 1), true; "weekday morning")]
+        #[test_case("30 8 * * 1-5", time(8, 30, 15, 6), false; "saturday")]
+        #[test_case("30 8 * * 1-5", time(8, 31, 10, 1), false; "other minute")]
+        #[test_case("0 18 * * mon-fri", time(18, 0, 10, 5), true; "weekday names")]
+        #[test_case("*/15 * * * *", time(12, 45, 1, 0), true; "step")]
+        #[test_case("*/15 * * * *", time(12, 50, 1, 0), false; "between steps")]
+        #[test_case("0 9,17 * * *", time(17, 0, 1, 0), true; "list")]
+        #[test_case("0 0 * * 7", time(0, 0, 1, 0), true; "sunday as 7")]
+        #[test_case("0 0 1 mar *", time(0, 0, 1, 4), true; "month name")]
+        #[test_case("0 0 1 * 1", time(0, 0, 5, 1), true; "day or weekday")]
+        #[test_case("0 0 1 * 1", time(0, 0, 5, 2), false; "neither day nor weekday")]
+        #[test_case("0 0 */2 * 1", time(0, 0, 3, 2), false; "restricted star needs both")]
+        #[test_case("@daily", time(0, 0, 5, 2), true; "shortcut")]
+        fn matches(expression: &str, time: LocalTime, expected: bool) {
+            assert_eq!(cron(expression).matches(&time), expected);
+        }
+
+        #[test_case("30 8 * *"; "missing field")]
+        #[test_case("60 8 * * *"; "minute out of range")]
+        #[test_case("0 18-8 * * *"; "reversed range")]
+        #[test_case("*/0 * * * *"; "zero step")]
+        #[test_case("0 8 * * someday"; "unknown name")]
+        fn rejects_invalid(expression: &str) {
+            let error = expression.parse::<CronExpression>().unwrap_err();
+            assert_eq!(error.code(), "invalid_schedule");
+        }
+
+        #[test_case("30 8 * * 1-5", vec!["Mon,Tue,Wed,Thu,Fri *-*-* 08:30:00"]; "weekdays")]
+        #[test_case("*/20 * * * *", vec!["*-*-* *:00,20,40:00"]; "every 20 minutes")]
+        #[test_case("0 0 1,15 jan *", vec!["*-01-01,15 00:00:00"]; "days")]
+        #[test_case("0 0 1 * sun", vec!["*-*-01 00:00:00", "Sun *-*-* 00:00:00"]; "day or weekday")]
+        fn to_systemd_calendar(expression: &str, expected: Vec<&str>) {
+            assert_eq!(cron(expression).to_systemd_calendar(), expected);
+        }
+
+        #[test]
+        fn keeps_expression() {
+            let json = serde_json::to_value(cron(" 0 18 * * mon-fri ")).unwrap();
+            assert_eq!(json, "0 18 * * mon-fri");
+        }
+
+        #[test]
+        fn utc_time() {
+            // 2023-11-14 22:13:20 UTC was a Tuesday
+            assert_eq!(
+                LocalTime::utc(1_700_000_000),
+                LocalTime {
+                    minute: 13,
+                    hour: 22,
+                    day: 14,
+                    month: 11,
+                    weekday: 2,
+                }
+            );
+        }
+
+        #[test]
+        fn utc_time_of_leap_day() {
+            // 2024-02-29 00:00:00 UTC was a Thursday
+            let leap_day = LocalTime::utc(1_709_164_800);
+
+            assert_eq!((leap_day.day, leap_day.month, leap_day.weekday), (29, 2, 4));
+        }
+
+        #[test]
+        fn local_time_of_next_week() {
+            let now = LocalTime::at(1_700_000_000);
+            let next_week = LocalTime::at(1_700_000_000 + 7 * 24 * 60 * 60);
+
+            assert_eq!(now.weekday, next_week.weekday);
+            assert_eq!((now.hour, now.minute), (next_week.hour, next_week.minute));
+        }
+    }
+}
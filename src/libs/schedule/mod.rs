@@ -0,0 +1,286 @@
+// SPDX-License-Identifier: EUPL-1.2
+
+mod cron;
+mod scheduler;
+mod systemd;
+
+use crate::libs::camera::{AIMode, SleepMode, Tiny2Camera};
+use crate::libs::config::{
+    Profiles, config_dir, deserialize_preset_nr, load_toml, preset_id, save_toml,
+};
+use crate::libs::errors::T4lError;
+use rust_i18n::t;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+pub use cron::{CronExpression, LocalTime};
+pub use scheduler::Scheduler;
+pub use systemd::{SYSTEMD_UNIT, systemd_units};
+
+/// What a rule of the schedule does with the camera.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum ScheduleAction {
+    Sleep,
+    Wake,
+    AiMode {
+        ai_mode: AIMode,
+    },
+    /// Preset position the gimbal moves to, counted from 1 as in the CLI and the GUI.
+    Preset {
+        #[serde(deserialize_with = "deserialize_preset_nr")]
+        preset: i8,
+    },
+    /// Applies one of the [`Profiles`], which is looked up when the rule runs.
+    Profile {
+        profile: String,
+    },
+}
+
+impl ScheduleAction {
+    pub fn run(&self, camera: &impl Tiny2Camera) -> Result<(), T4lError> {
+        match self {
+            ScheduleAction::Sleep => camera.set_sleep_mode(SleepMode::Sleep),
+            ScheduleAction::Wake => camera.set_sleep_mode(SleepMode::Awake),
+            ScheduleAction::AiMode { ai_mode } => camera.set_ai_mode(*ai_mode),
+            ScheduleAction::Preset { preset } => camera.goto_preset_position(preset_id(*preset)?),
+            ScheduleAction::Profile { profile } => Profiles::load()?.get(profile)?.apply(camera),
+        }
+    }
+}
+
+impl Display for ScheduleAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScheduleAction::Sleep => write!(f, "{}", t!("display.schedule_action.sleep")),
+            ScheduleAction::Wake => write!(f, "{}", t!("display.schedule_action.wake")),
+            ScheduleAction::AiMode { ai_mode } => write!(
+                f,
+                "{}",
+                t!("display.schedule_action.ai_mode", mode = ai_mode)
+            ),
+            ScheduleAction::Preset { preset } => write!(
+                f,
+                "{}",
+                t!("display.schedule_action.preset", preset = preset)
+            ),
+            ScheduleAction::Profile { profile } => write!(
+                f,
+                "{}",
+                t!("display.schedule_action.profile", name = profile)
+            ),
+        }
+    }
+}
+
+/// Runs the action at the times of the cron expression.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduleRule {
+    pub when: CronExpression,
+    #[serde(flatten)]
+    pub action: ScheduleAction,
+}
+
+/// The rules stored in `schedule.toml` in the [`config_dir`], numbered from 1 in their order, e.g.:
+///
+/// ```toml
+/// [[rule]]
+/// when = "0 18 * * mon-fri"
+/// action = "sleep"
+///
+/// [[rule]]
+/// when = "30 8 * * mon-fri"
+/// action = "profile"
+/// profile = "office"
+/// ```
+///
+/// The rules are run by the daemon with `--schedule`, by `t4l schedule run` or by the systemd
+/// timer generated with [`systemd_units`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Schedule {
+    #[serde(default, rename = "rule")]
+    rules: Vec<ScheduleRule>,
+}
+
+impl Schedule {
+    pub fn path() -> PathBuf {
+        config_dir().join("schedule.toml")
+    }
+
+    /// Loads the schedule from the default path, see [`Schedule::path`].
+    pub fn load() -> Result<Self, T4lError> {
+        Self::load_from(&Self::path())
+    }
+
+    /// Loads the schedule from the file, which has no rules if it doesn't exist yet.
+    pub fn load_from(path: &Path) -> Result<Self, T4lError> {
+        load_toml(path)
+    }
+
+    /// Saves the schedule to the default path, see [`Schedule::path`].
+    pub fn save(&self) -> Result<(), T4lError> {
+        self.save_to(&Self::path())
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<(), T4lError> {
+        save_toml(path, self)
+    }
+
+    pub fn rules(&self) -> &[ScheduleRule] {
+        &self.rules
+    }
+
+    /// Adds the rule after the others, returning its number.
+    pub fn add(&mut self, rule: ScheduleRule) -> usize {
+        self.rules.push(rule);
+        self.rules.len()
+    }
+
+    /// Removes the rule with the number, counted from 1.
+    pub fn remove(&mut self, number: usize) -> Result<ScheduleRule, T4lError> {
+        if number == 0 || number > self.rules.len() {
+            return Err(T4lError::UnknownScheduleRule(number));
+        }
+
+        Ok(self.rules.remove(number - 1))
+    }
+
+    /// The rules that run at the time.
+    pub fn due(&self, time: &LocalTime) -> Vec<&ScheduleRule> {
+        self.rules
+            .iter()
+            .filter(|rule| rule.when.matches(time))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod unit {
+        use crate::{AIMode, LocalTime, Schedule, ScheduleAction, ScheduleRule};
+
+        fn rule(when: &str, action: ScheduleAction) -> ScheduleRule {
+            ScheduleRule {
+                when: when.parse().unwrap(),
+                action,
+            }
+        }
+
+        #[test]
+        fn parses_schedule() {
+            let schedule: Schedule = toml::from_str(
+                r#"
+                [[rule]]
+                when = "0 18 * * mon-fri"
+                action = "sleep"
+
+                [[rule]]
+                when = "30 8 * * mon-fri"
+                action = "ai_mode"
+                ai_mode = "upper_body"
+
+                [[rule]]
+                when = "0 12 * * *"
+                action = "preset"
+                preset = 2
+                "#,
+            )
+            .unwrap();
+
+            assert_eq!(
+                schedule.rules(),
+                [
+                    rule("0 18 * * mon-fri", ScheduleAction::Sleep),
+                    rule(
+                        "30 8 * * mon-fri",
+                        ScheduleAction::AiMode {
+                            ai_mode: AIMode::UpperBody
+                        }
+                    ),
+                    rule("0 12 * * *", ScheduleAction::Preset { preset: 2 }),
+                ]
+            );
+        }
+
+        #[test]
+        fn rejects_preset_0() {
+            let result = toml::from_str::<Schedule>(
+                "[[rule]]\nwhen = \"0 12 * * *\"\naction = \"preset\"\npreset = 0\n",
+            );
+            assert!(result.is_err());
+        }
+
+        #[test]
+        fn rejects_invalid_expression() {
+            let result =
+                toml::from_str::<Schedule>("[[rule]]\nwhen = \"0 25 * * *\"\naction = \"wake\"\n");
+            assert!(result.is_err());
+        }
+
+        #[test]
+        fn removes_rule_by_number() {
+            let mut schedule = Schedule::default();
+            schedule.add(rule("0 18 * * *", ScheduleAction::Sleep));
+            let number = schedule.add(rule("30 8 * * *", ScheduleAction::Wake));
+
+            assert_eq!(number, 2);
+            assert_eq!(schedule.remove(1).unwrap().action, ScheduleAction::Sleep);
+            assert_eq!(
+                schedule.remove(2).unwrap_err().code(),
+                "unknown_schedule_rule"
+            );
+            assert_eq!(schedule.rules().len(), 1);
+        }
+
+        #[test]
+        fn due_rules() {
+            let mut schedule = Schedule::default();
+            schedule.add(rule("0 18 * * 1-5", ScheduleAction::Sleep));
+            schedule.add(rule("30 8 * * 1-5", ScheduleAction::Wake));
+            let friday_evening = LocalTime {
+                minute: 0,
+                hour: 18,
+                day: 17,
+                month: 10,
+                weekday: 5,
+            };
+
+            let due = schedule.due(&friday_evening);
+
+            assert_eq!(due.len(), 1);
+            assert_eq!(due[0].action, ScheduleAction::Sleep);
+        }
+    }
+
+    mod integration {
+        use crate::{Camera, Schedule, ScheduleAction, ScheduleRule, SleepMode, Tiny2Camera};
+
+        #[test]
+        fn saves_and_loads_schedule() {
+            let dir = tempfile::tempdir().unwrap();
+            let path = dir.path().join("schedule.toml");
+            let mut schedule = Schedule::default();
+            schedule.add(ScheduleRule {
+                when: "30 8 * * mon-fri".parse().unwrap(),
+                action: ScheduleAction::Profile {
+                    profile: "office".to_string(),
+                },
+            });
+
+            schedule.save_to(&path).unwrap();
+
+            assert_eq!(Schedule::load_from(&path).unwrap(), schedule);
+        }
+
+        #[test]
+        fn runs_action() {
+            let camera = Camera::simulated();
+
+            ScheduleAction::Sleep.run(&camera).unwrap();
+
+            assert_eq!(camera.get_sleep_mode().unwrap(), SleepMode::Sleep);
+        }
+    }
+}
@@ -0,0 +1,138 @@
+// SPDX-License-Identifier: EUPL-1.2
+
+use crate::libs::camera::Tiny2Camera;
+use crate::libs::errors::T4lError;
+use crate::libs::schedule::{LocalTime, Schedule, ScheduleRule};
+use std::ops::Range;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Minutes missed between two polls that are caught up on, e.g. while the computer was busy.
+/// Rules missed for longer, e.g. during a suspend, are skipped, as they're likely outdated.
+const MAX_CATCH_UP_MINUTES: i64 = 5;
+
+/// A rule that ran, with its outcome.
+type RuleOutcome = (ScheduleRule, Result<(), T4lError>);
+
+/// Runs the rules of a [`Schedule`] once they're due, if polled at least once a minute.
+///
+/// The schedule is read again whenever a minute passed, so changes apply without a restart.
+pub struct Scheduler {
+    path: PathBuf,
+    last_minute: Option<i64>,
+}
+
+impl Scheduler {
+    /// Runs the rules stored at the default path, see [`Schedule::path`].
+    pub fn new() -> Self {
+        Self::with_path(Schedule::path())
+    }
+
+    pub fn with_path(path: PathBuf) -> Self {
+        Self {
+            path,
+            last_minute: None,
+        }
+    }
+
+    /// Runs the rules that became due since the last poll, returning each with its outcome.
+    ///
+    /// The first poll only runs the rules due in the current minute.
+    pub fn poll(&mut self, camera: &impl Tiny2Camera) -> Result<Vec<RuleOutcome>, T4lError> {
+        let minutes = self.pending_minutes(current_minute());
+        if minutes.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let schedule = Schedule::load_from(&self.path)?;
+        let times: Vec<LocalTime> = minutes.map(|minute| LocalTime::at(minute * 60)).collect();
+
+        Ok(schedule
+            .rules()
+            .iter()
+            .filter(|rule| times.iter().any(|time| rule.when.matches(time)))
+            .map(|rule| (rule.clone(), rule.action.run(camera)))
+            .collect())
+    }
+
+    /// The minutes since the Unix epoch that weren't checked yet, up to the current one.
+    fn pending_minutes(&mut self, minute: i64) -> Range<i64> {
+        let first = match self.last_minute {
+            Some(last) => (last + 1).max(minute + 1 - MAX_CATCH_UP_MINUTES),
+            None => minute,
+        };
+        self.last_minute = Some(minute);
+
+        first..minute + 1
+    }
+}
+
+impl Default for Scheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn current_minute() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as i64 / 60)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    mod unit {
+        use crate::Scheduler;
+        use std::path::PathBuf;
+
+        fn scheduler() -> Scheduler {
+            Scheduler::with_path(PathBuf::from("schedule.toml"))
+        }
+
+        #[test]
+        fn first_poll_checks_current_minute() {
+            assert_eq!(scheduler().pending_minutes(100), 100..101);
+        }
+
+        #[test]
+        fn checks_each_minute_once() {
+            let mut scheduler = scheduler();
+            scheduler.pending_minutes(100);
+
+            assert!(scheduler.pending_minutes(100).is_empty());
+            assert_eq!(scheduler.pending_minutes(102), 101..103);
+        }
+
+        #[test]
+        fn skips_minutes_missed_for_long() {
+            let mut scheduler = scheduler();
+            scheduler.pending_minutes(100);
+
+            assert_eq!(scheduler.pending_minutes(200), 196..201);
+        }
+    }
+
+    mod integration {
+        use crate::{Camera, Schedule, ScheduleAction, ScheduleRule, Scheduler};
+
+        #[test]
+        fn runs_due_rules() {
+            let dir = tempfile::tempdir().unwrap();
+            let path = dir.path().join("schedule.toml");
+            let mut schedule = Schedule::default();
+            schedule.add(ScheduleRule {
+                when: "* * * * *".parse().unwrap(),
+                action: ScheduleAction::Wake,
+            });
+            schedule.save_to(&path).unwrap();
+            let camera = Camera::simulated();
+            let mut scheduler = Scheduler::with_path(path);
+
+            let ran = scheduler.poll(&camera).unwrap();
+
+            assert_eq!(ran.len(), 1);
+            assert!(ran[0].1.is_ok());
+        }
+    }
+}
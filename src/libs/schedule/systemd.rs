@@ -0,0 +1,88 @@
+// SPDX-License-Identifier: EUPL-1.2
+
+use crate::libs::errors::T4lError;
+use crate::libs::schedule::Schedule;
+
+/// Name of the timer and the service running the schedule, without their suffix.
+pub const SYSTEMD_UNIT: &str = "tiny4linux-schedule";
+
+/// Generates a systemd user timer that elapses at all times of the schedule, and the service it
+/// starts, which runs the rules due with the command, e.g. `t4l schedule run --due`.
+///
+/// Returns the file names of the units with their contents. As the times are written into the
+/// timer, the units have to be generated again once the schedule changed. A schedule without rules
+/// is refused, as a timer without a time never elapses.
+pub fn systemd_units(
+    schedule: &Schedule,
+    command: &str,
+) -> Result<Vec<(String, String)>, T4lError> {
+    if schedule.rules().is_empty() {
+        return Err(T4lError::EmptySchedule);
+    }
+
+    let calendars: String = schedule
+        .rules()
+        .iter()
+        .flat_map(|rule| rule.when.to_systemd_calendar())
+        .map(|calendar| format!("OnCalendar={}\n", calendar))
+        .collect();
+
+    // the timer has to elapse within the minute of the rule, not just somewhen after it
+    let timer = format!(
+        "[Unit]\nDescription=Tiny4Linux schedule\n\n\
+         [Timer]\n{}AccuracySec=1s\n\n\
+         [Install]\nWantedBy=timers.target\n",
+        calendars
+    );
+    let service = format!(
+        "[Unit]\nDescription=Tiny4Linux schedule\n\n\
+         [Service]\nType=oneshot\nExecStart={}\n",
+        command
+    );
+
+    Ok(vec![
+        (format!("{}.timer", SYSTEMD_UNIT), timer),
+        (format!("{}.service", SYSTEMD_UNIT), service),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    mod unit {
+        use crate::{Schedule, ScheduleAction, ScheduleRule, systemd_units};
+
+        #[test]
+        fn generates_timer_and_service() {
+            let mut schedule = Schedule::default();
+            schedule.add(ScheduleRule {
+                when: "0 18 * * 1-5".parse().unwrap(),
+                action: ScheduleAction::Sleep,
+            });
+            schedule.add(ScheduleRule {
+                when: "30 8 * * 1-5".parse().unwrap(),
+                action: ScheduleAction::Wake,
+            });
+
+            let units = systemd_units(&schedule, "/usr/bin/t4l schedule run --due").unwrap();
+
+            assert_eq!(units[0].0, "tiny4linux-schedule.timer");
+            assert!(units[0].1.contains(
+                "OnCalendar=Mon,Tue,Wed,Thu,Fri *-*-* 18:00:00\n\
+                 OnCalendar=Mon,Tue,Wed,Thu,Fri *-*-* 08:30:00\n"
+            ));
+            assert_eq!(units[1].0, "tiny4linux-schedule.service");
+            assert!(
+                units[1]
+                    .1
+                    .contains("ExecStart=/usr/bin/t4l schedule run --due\n")
+            );
+        }
+
+        #[test]
+        fn refuses_empty_schedule() {
+            let error = systemd_units(&Schedule::default(), "t4l schedule run --due").unwrap_err();
+
+            assert_eq!(error.code(), "empty_schedule");
+        }
+    }
+}
@@ -0,0 +1,274 @@
+// SPDX-License-Identifier: EUPL-1.2
+
+use crate::libs::camera::{
+    AIMode, AIModeCommand, Camera, ExposureMode, ExposureModeCommand, ExposureModeTypeCommand,
+    GotoPresetPositionCommand, HdrModeCommand, SleepCommand, SleepMode, Tiny2Camera, TrackingSpeed,
+    TrackingSpeedCommand,
+};
+use crate::libs::config::preset_id;
+use crate::libs::errors::T4lError;
+use crate::libs::usbio::V4L2_CID_ZOOM_ABSOLUTE;
+use serde::Serialize;
+use std::fmt::Display;
+use std::str::FromStr;
+use std::thread;
+use std::time::Duration;
+
+/// A command of a [`Script`](crate::Script), written on its own line, e.g. `tracking whiteboard`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScriptCommand {
+    Sleep,
+    Wake,
+    Tracking(AIMode),
+    Speed(TrackingSpeed),
+    Hdr(bool),
+    Exposure(ExposureMode),
+    /// Moves to the preset position, counted from 1 as in the CLI, stopping the tracking first.
+    Preset(i8),
+    Zoom(i32),
+    /// Waits before the next command, e.g. until the gimbal arrived, written as `500ms` or `2s`.
+    Wait(Duration),
+}
+
+/// A request a command sends to the camera.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "request", rename_all = "snake_case")]
+pub enum CameraRequest {
+    /// A 0x02 frame as built, before the transport stamps its sequence number.
+    Frame02 {
+        #[serde(serialize_with = "hex::serde::serialize")]
+        frame: [u8; 36],
+    },
+    /// A payload for selector 0x06, which is sent padded with zeros.
+    Payload06 {
+        #[serde(with = "hex::serde")]
+        payload: Vec<u8>,
+    },
+    /// A standard V4L2 control, e.g. the zoom.
+    Control { id: u32, value: i32 },
+}
+
+impl Display for CameraRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CameraRequest::Frame02 { frame } => write!(f, "0x02 frame   {}", hex::encode(frame)),
+            CameraRequest::Payload06 { payload } => {
+                write!(f, "0x06 payload {}", hex::encode(payload))
+            }
+            CameraRequest::Control { id, value } => write!(f, "control {:#010x} {}", id, value),
+        }
+    }
+}
+
+impl CameraRequest {
+    /// Sends the request to the camera, where 0x02 frames are stamped with its next sequence number.
+    pub fn send(&self, camera: &Camera) -> Result<(), T4lError> {
+        match self {
+            CameraRequest::Frame02 { frame } => camera.send_cmd02(frame),
+            CameraRequest::Payload06 { payload } => camera.send_cmd(0x2, 0x6, payload),
+            // checked against the range of the camera first
+            CameraRequest::Control {
+                id: V4L2_CID_ZOOM_ABSOLUTE,
+                value,
+            } => camera.set_zoom(*value),
+            CameraRequest::Control { id, value } => camera.set_ctrl(*id, *value),
+        }
+    }
+}
+
+impl ScriptCommand {
+    /// Sends the [`ScriptCommand::requests`] of the command to the camera, or waits.
+    pub fn run(&self, camera: &Camera) -> Result<(), T4lError> {
+        if let ScriptCommand::Wait(duration) = self {
+            thread::sleep(*duration);
+        }
+
+        for request in self.requests()? {
+            request.send(camera)?;
+        }
+        Ok(())
+    }
+
+    /// The requests the command sends to the camera, built without sending them, e.g. for a dry run.
+    pub fn requests(&self) -> Result<Vec<CameraRequest>, T4lError> {
+        let frame = |frame: [u8; 36]| CameraRequest::Frame02 { frame };
+        let payload = |payload: &[u8]| CameraRequest::Payload06 {
+            payload: payload.to_vec(),
+        };
+
+        Ok(match self {
+            ScriptCommand::Sleep => vec![frame(SleepCommand::build(SleepMode::Sleep)?)],
+            ScriptCommand::Wake => vec![frame(SleepCommand::build(SleepMode::Awake)?)],
+            ScriptCommand::Tracking(mode) => vec![payload(&AIModeCommand::build(*mode)?)],
+            ScriptCommand::Speed(speed) => vec![frame(TrackingSpeedCommand::build(*speed)?)],
+            ScriptCommand::Hdr(hdr) => vec![payload(&HdrModeCommand::build(*hdr))],
+            ScriptCommand::Exposure(mode) => {
+                let mut requests = vec![frame(ExposureModeTypeCommand::build(*mode))];
+                requests.extend(ExposureModeCommand::build(*mode).map(|cmd| payload(&cmd)));
+                requests
+            }
+            // the tracking would move the gimbal away from the preset again
+            ScriptCommand::Preset(preset) => vec![
+                payload(&AIModeCommand::build(AIMode::NoTracking)?),
+                frame(GotoPresetPositionCommand::build(preset_id(*preset)?)?),
+            ],
+            ScriptCommand::Zoom(zoom) => vec![CameraRequest::Control {
+                id: V4L2_CID_ZOOM_ABSOLUTE,
+                value: *zoom,
+            }],
+            ScriptCommand::Wait(_) => Vec::new(),
+        })
+    }
+}
+
+impl Display for ScriptCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScriptCommand::Sleep => write!(f, "sleep"),
+            ScriptCommand::Wake => write!(f, "wake"),
+            ScriptCommand::Tracking(mode) => write!(f, "tracking {}", mode.name()),
+            ScriptCommand::Speed(TrackingSpeed::Standard) => write!(f, "speed standard"),
+            ScriptCommand::Speed(TrackingSpeed::Sport) => write!(f, "speed fast"),
+            ScriptCommand::Hdr(hdr) => write!(f, "hdr {}", if *hdr { "on" } else { "off" }),
+            ScriptCommand::Exposure(ExposureMode::Manual) => write!(f, "exposure manual"),
+            ScriptCommand::Exposure(ExposureMode::Global) => write!(f, "exposure global"),
+            ScriptCommand::Exposure(ExposureMode::Face) => write!(f, "exposure face"),
+            ScriptCommand::Preset(preset) => write!(f, "preset {}", preset),
+            ScriptCommand::Zoom(zoom) => write!(f, "zoom {}", zoom),
+            ScriptCommand::Wait(duration) if duration.subsec_millis() == 0 => {
+                write!(f, "wait {}s", duration.as_secs())
+            }
+            ScriptCommand::Wait(duration) => write!(f, "wait {}ms", duration.as_millis()),
+        }
+    }
+}
+
+impl FromStr for ScriptCommand {
+    /// The reason the command is invalid.
+    type Err = String;
+
+    /// Parses a command with the names and aliases of the CLI, e.g. `track close` or `hdr on`.
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let line = line.to_lowercase().replace('_', "-");
+        let words: Vec<&str> = line.split_whitespace().collect();
+
+        let command = match words.as_slice() {
+            ["sleep"] => ScriptCommand::Sleep,
+            ["wake"] => ScriptCommand::Wake,
+            ["tracking" | "track", mode] => ScriptCommand::Tracking(mode.parse()?),
+            ["speed" | "tracking-speed", speed] => match *speed {
+                "standard" | "normal" | "default" | "slow" | "low" => {
+                    ScriptCommand::Speed(TrackingSpeed::Standard)
+                }
+                "fast" | "sport" | "high" => ScriptCommand::Speed(TrackingSpeed::Sport),
+                _ => return Err(format!("unknown tracking speed `{}`", speed)),
+            },
+            ["hdr", "on"] => ScriptCommand::Hdr(true),
+            ["hdr", "off"] => ScriptCommand::Hdr(false),
+            ["exposure", "manual"] => ScriptCommand::Exposure(ExposureMode::Manual),
+            ["exposure", "global"] => ScriptCommand::Exposure(ExposureMode::Global),
+            ["exposure", "face"] => ScriptCommand::Exposure(ExposureMode::Face),
+            ["preset" | "position", preset] => ScriptCommand::Preset(
+                preset
+                    .parse()
+                    .ok()
+                    .filter(|preset| *preset >= 1)
+                    .ok_or_else(|| format!("invalid preset position `{}`", preset))?,
+            ),
+            ["zoom", zoom] => ScriptCommand::Zoom(
+                zoom.parse()
+                    .map_err(|_| format!("invalid zoom `{}`", zoom))?,
+            ),
+            ["wait", duration] => ScriptCommand::Wait(parse_duration(duration)?),
+            [] => return Err("empty command".to_string()),
+            [command, ..] => return Err(format!("unknown command or arguments of `{}`", command)),
+        };
+
+        Ok(command)
+    }
+}
+
+/// Parses a duration in milliseconds or seconds, e.g. `500ms` or `2s`.
+fn parse_duration(duration: &str) -> Result<Duration, String> {
+    let invalid = || format!("invalid duration `{}`, e.g. `500ms` or `2s`", duration);
+
+    if let Some(millis) = duration.strip_suffix("ms") {
+        millis
+            .parse()
+            .map(Duration::from_millis)
+            .map_err(|_| invalid())
+    } else if let Some(seconds) = duration.strip_suffix('s') {
+        seconds
+            .parse()
+            .map(Duration::from_secs)
+            .map_err(|_| invalid())
+    } else {
+        Err(invalid())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod unit {
+        use crate::{AIMode, CameraRequest, ExposureMode, ScriptCommand, TrackingSpeed};
+        use std::time::Duration;
+        use test_case::test_case;
+
+        #[test_case("sleep", ScriptCommand::Sleep; "sleep")]
+        #[test_case("tracking whiteboard", ScriptCommand::Tracking(AIMode::Whiteboard); "tracking")]
+        #[test_case("track close", ScriptCommand::Tracking(AIMode::CloseUp); "tracking alias")]
+        #[test_case("tracking upper_body", ScriptCommand::Tracking(AIMode::UpperBody); "tracking id")]
+        #[test_case("speed fast", ScriptCommand::Speed(TrackingSpeed::Sport); "speed")]
+        #[test_case("HDR Off", ScriptCommand::Hdr(false); "any case")]
+        #[test_case("exposure face", ScriptCommand::Exposure(ExposureMode::Face); "exposure")]
+        #[test_case("preset 2", ScriptCommand::Preset(2); "preset")]
+        #[test_case("wait 500ms", ScriptCommand::Wait(Duration::from_millis(500)); "wait millis")]
+        #[test_case("  wait   2s ", ScriptCommand::Wait(Duration::from_secs(2)); "wait seconds")]
+        fn parses_command(line: &str, expected: ScriptCommand) {
+            assert_eq!(line.parse::<ScriptCommand>(), Ok(expected));
+        }
+
+        #[test_case("tracking fast"; "unknown mode")]
+        #[test_case("hdr"; "missing argument")]
+        #[test_case("wait 5"; "duration without unit")]
+        #[test_case("preset two"; "invalid number")]
+        #[test_case("preset 0"; "preset not counted from 1")]
+        #[test_case("reboot"; "unknown command")]
+        fn rejects_invalid_command(line: &str) {
+            assert!(line.parse::<ScriptCommand>().is_err());
+        }
+
+        #[test_case("tracking close-up"; "tracking")]
+        #[test_case("speed standard"; "speed")]
+        #[test_case("exposure global"; "exposure")]
+        #[test_case("wait 1500ms"; "wait millis")]
+        #[test_case("wait 3s"; "wait seconds")]
+        fn displays_parseable_command(line: &str) {
+            assert_eq!(line.parse::<ScriptCommand>().unwrap().to_string(), line);
+        }
+
+        #[test]
+        fn preset_stops_tracking_first() {
+            let requests = ScriptCommand::Preset(1).requests().unwrap();
+
+            assert_eq!(requests.len(), 2);
+            assert!(matches!(requests[0], CameraRequest::Payload06 { .. }));
+            assert!(matches!(requests[1], CameraRequest::Frame02 { .. }));
+        }
+
+        #[test]
+        fn rejects_preset_not_counted_from_1() {
+            assert!(ScriptCommand::Preset(0).requests().is_err());
+        }
+
+        #[test]
+        fn wait_sends_nothing() {
+            assert!(
+                ScriptCommand::Wait(Duration::from_secs(1))
+                    .requests()
+                    .unwrap()
+                    .is_empty()
+            );
+        }
+    }
+}
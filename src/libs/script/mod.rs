@@ -0,0 +1,131 @@
+// SPDX-License-Identifier: EUPL-1.2
+
+mod command;
+
+use crate::libs::camera::Camera;
+use crate::libs::errors::T4lError;
+
+pub use command::{CameraRequest, ScriptCommand};
+
+/// Commands run one after another against a single camera, one per line, e.g.:
+///
+/// ```text
+/// # before the talk
+/// tracking whiteboard
+/// speed fast
+/// hdr off
+/// wait 500ms
+/// preset 2
+/// ```
+///
+/// Empty lines and comments starting with `#` are skipped.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Script {
+    commands: Vec<(usize, ScriptCommand)>,
+}
+
+impl Script {
+    /// Parses the script, failing with the number of the first invalid line.
+    pub fn parse(text: &str) -> Result<Self, T4lError> {
+        let mut commands = Vec::new();
+
+        for (number, line) in (1..).zip(text.lines()) {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let command = line
+                .parse()
+                .map_err(|reason| T4lError::InvalidScript(number, reason))?;
+            commands.push((number, command));
+        }
+
+        Ok(Self { commands })
+    }
+
+    /// The commands with the numbers of their lines, counted from 1.
+    pub fn commands(&self) -> &[(usize, ScriptCommand)] {
+        &self.commands
+    }
+
+    /// Runs the commands in order, calling back before each one with its line number.
+    ///
+    /// Stops at the first command that fails, returning the error with its line number.
+    pub fn run(
+        &self,
+        camera: &Camera,
+        mut before_command: impl FnMut(usize, &ScriptCommand),
+    ) -> Result<(), T4lError> {
+        for (number, command) in &self.commands {
+            before_command(*number, command);
+            command
+                .run(camera)
+                .map_err(|error| T4lError::ScriptFailed(*number, Box::new(error)))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod unit {
+        use crate::{AIMode, Script, ScriptCommand, T4lError};
+
+        #[test]
+        fn parses_lines_with_numbers() {
+            let script =
+                Script::parse("# setup\ntracking whiteboard\n\nhdr off # no flicker\n").unwrap();
+
+            assert_eq!(
+                script.commands(),
+                [
+                    (2, ScriptCommand::Tracking(AIMode::Whiteboard)),
+                    (4, ScriptCommand::Hdr(false)),
+                ]
+            );
+        }
+
+        #[test]
+        fn reports_invalid_line() {
+            let error = Script::parse("sleep\nwake\nspeed turbo\n").unwrap_err();
+
+            assert!(matches!(error, T4lError::InvalidScript(3, _)));
+            assert_eq!(error.code(), "invalid_script");
+        }
+    }
+
+    mod integration {
+        use crate::{Camera, Script, SleepMode, T4lError, Tiny2Camera};
+
+        #[test]
+        fn runs_commands_in_order() {
+            let camera = Camera::simulated();
+            let script = Script::parse("hdr off\nsleep\n").unwrap();
+            let mut ran = Vec::new();
+
+            script
+                .run(&camera, |number, command| {
+                    ran.push((number, command.clone()))
+                })
+                .unwrap();
+
+            assert_eq!(ran.len(), 2);
+            assert_eq!(camera.get_sleep_mode().unwrap(), SleepMode::Sleep);
+            assert!(!camera.get_hdr_mode().unwrap());
+        }
+
+        #[test]
+        fn stops_at_first_failure() {
+            let camera = Camera::simulated();
+            let script = Script::parse("hdr off\nzoom 1000\nsleep\n").unwrap();
+
+            let error = script.run(&camera, |_, _| {}).unwrap_err();
+
+            assert!(matches!(error, T4lError::ScriptFailed(2, _)));
+            assert_eq!(error.exit_code(), 7);
+            assert_eq!(camera.get_sleep_mode().unwrap(), SleepMode::Awake);
+        }
+    }
+}
@@ -0,0 +1,111 @@
+// SPDX-License-Identifier: EUPL-1.2
+
+use crate::libs::dbus::{CameraProxy, DaemonError};
+use crate::libs::usbio::{CameraInfo, ControlRange, UvcUsbIo};
+use errno::Errno;
+use nix::libc::EIO;
+use std::fmt::{Debug, Formatter};
+use std::sync::Mutex;
+use zbus::blocking::Connection;
+
+/// Accesses the camera through the daemon, which relays the requests to the device.
+pub struct DaemonHandle {
+    proxy: Mutex<CameraProxy<'static>>,
+}
+
+impl Debug for DaemonHandle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DaemonHandle").finish_non_exhaustive()
+    }
+}
+
+impl DaemonHandle {
+    /// Connects to the daemon if it's running and serves a camera matching the hint,
+    /// which is either the path of the device or a part of its card name or bus.
+    pub fn connect(hint: &str) -> Option<Self> {
+        let proxy = Self::proxy()?;
+
+        let serves_hint = proxy.device().ok()? == hint
+            || proxy.card().ok()?.contains(hint)
+            || proxy.bus_info().ok()?.contains(hint);
+
+        serves_hint.then_some(Self {
+            proxy: Mutex::new(proxy),
+        })
+    }
+
+    fn proxy() -> Option<CameraProxy<'static>> {
+        let connection = Connection::session().ok()?;
+        CameraProxy::new(&connection).ok()
+    }
+
+    /// Calls the daemon, connecting to it again once if the call failed on the bus, e.g. because
+    /// the daemon was restarted in between.
+    fn call<T>(
+        &self,
+        request: impl Fn(&CameraProxy<'static>) -> Result<T, DaemonError>,
+    ) -> Result<T, Errno> {
+        let mut proxy = self.proxy.lock().map_err(|_| Errno(EIO))?;
+
+        match request(&proxy) {
+            Err(DaemonError::ZBus(_)) => {
+                *proxy = Self::proxy().ok_or(Errno(EIO))?;
+                request(&proxy).map_err(to_errno)
+            }
+            result => result.map_err(to_errno),
+        }
+    }
+}
+
+/// Restores the errno the daemon failed with, so that it's reported like a local failure.
+fn to_errno(error: DaemonError) -> Errno {
+    match error {
+        DaemonError::Errno(errno) => Errno(errno.parse().unwrap_or(EIO)),
+        _ => Errno(EIO),
+    }
+}
+
+impl UvcUsbIo for DaemonHandle {
+    fn info(&self) -> Result<CameraInfo, Errno> {
+        Ok(CameraInfo {
+            card: self.call(|proxy| Ok(proxy.card()?))?,
+            bus_info: self.call(|proxy| Ok(proxy.bus_info()?))?,
+        })
+    }
+
+    fn io(&self, unit: u8, selector: u8, query: u8, data: &mut [u8]) -> Result<(), Errno> {
+        let answer = self.call(|proxy| proxy.xu_query(unit, selector, query, data.to_vec()))?;
+
+        let len = answer.len().min(data.len());
+        data[..len].copy_from_slice(&answer[..len]);
+        Ok(())
+    }
+
+    fn sized_io(&self, unit: u8, selector: u8, query: u8, data: &mut [u8]) -> Result<usize, Errno> {
+        let (size, answer) =
+            self.call(|proxy| proxy.xu_sized_query(unit, selector, query, data.to_vec()))?;
+
+        let len = answer.len().min(data.len());
+        data[..len].copy_from_slice(&answer[..len]);
+        Ok(size as usize)
+    }
+
+    fn get_ctrl(&self, id: u32) -> Result<i32, Errno> {
+        self.call(|proxy| proxy.get_ctrl(id))
+    }
+
+    fn set_ctrl(&self, id: u32, value: i32) -> Result<(), Errno> {
+        self.call(|proxy| proxy.set_ctrl(id, value))
+    }
+
+    fn query_ctrl(&self, id: u32) -> Result<ControlRange, Errno> {
+        let (minimum, maximum, step, default) = self.call(|proxy| proxy.query_ctrl(id))?;
+
+        Ok(ControlRange {
+            minimum,
+            maximum,
+            step,
+            default,
+        })
+    }
+}
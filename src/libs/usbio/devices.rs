@@ -0,0 +1,166 @@
+// SPDX-License-Identifier: EUPL-1.2
+
+use crate::libs::errors::T4lError;
+use crate::libs::usbio::{SIMULATED_CAMERA_ENV, v4l2_capability};
+use glob::{MatchOptions, glob_with};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::fs::File;
+use std::path::Path;
+
+/// Path reported for the simulated camera, see [`SIMULATED_CAMERA_ENV`].
+pub const SIMULATED_DEVICE_PATH: &str = "simulated";
+
+/// A video device of a camera, as found by [`list_cameras`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CameraDevice {
+    /// Path of the video device, e.g. `/dev/video0`.
+    pub path: String,
+    /// Name of the camera reported by the driver, e.g. `OBSBOT Tiny 2`.
+    pub card: String,
+    /// Location of the camera on the bus, e.g. `usb-0000:00:14.0-2`.
+    pub bus_info: String,
+    /// Serial number of the USB device, if it reports one.
+    pub serial: Option<String>,
+}
+
+impl Display for CameraDevice {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.card, self.path)
+    }
+}
+
+impl CameraDevice {
+    /// Checks whether the device is meant by the selector, which is its path, bus or serial.
+    pub fn matches(&self, selector: &str) -> bool {
+        self.path == selector
+            || self.path == format!("/dev/{}", selector)
+            || self.bus_info == selector
+            || self.serial.as_deref() == Some(selector)
+    }
+}
+
+/// Lists the video devices of all cameras whose card name or bus contains the hint.
+///
+/// Metadata devices are skipped, so every camera is listed once, ordered by path.
+/// Devices that can't be opened, e.g. for lack of permissions, aren't listed.
+pub fn list_cameras(hint: &str) -> Vec<CameraDevice> {
+    if std::env::var_os(SIMULATED_CAMERA_ENV).is_some() {
+        return vec![CameraDevice {
+            path: SIMULATED_DEVICE_PATH.to_string(),
+            card: format!("{} (simulated)", hint),
+            bus_info: SIMULATED_DEVICE_PATH.to_string(),
+            serial: None,
+        }];
+    }
+
+    let options = MatchOptions {
+        case_sensitive: true,
+        require_literal_separator: true,
+        require_literal_leading_dot: true,
+    };
+    let mut paths: Vec<_> = glob_with("/dev/video*", options)
+        .unwrap()
+        .flatten()
+        .collect();
+    paths.sort_by_key(|path| video_number(path));
+
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let device = File::open(&path).ok()?;
+            let video_info = v4l2_capability::new(&device).ok()?;
+
+            if !video_info.matches(hint) {
+                return None;
+            }
+
+            Some(CameraDevice {
+                path: path.to_string_lossy().to_string(),
+                card: video_info.card(),
+                bus_info: video_info.bus_info(),
+                serial: read_serial(&path),
+            })
+        })
+        .collect()
+}
+
+/// Finds the camera meant by the selector, which is either its index in [`list_cameras`],
+/// its path, its bus or its serial.
+pub fn find_camera(hint: &str, selector: &str) -> Result<CameraDevice, T4lError> {
+    let mut cameras = list_cameras(hint).into_iter();
+
+    match selector.parse::<usize>() {
+        Ok(index) => cameras.nth(index),
+        Err(_) => cameras.find(|camera| camera.matches(selector)),
+    }
+    .ok_or(T4lError::NoCameraFound)
+}
+
+/// Orders `/dev/video10` after `/dev/video9`.
+fn video_number(path: &Path) -> u32 {
+    path.to_string_lossy()
+        .trim_start_matches("/dev/video")
+        .parse()
+        .unwrap_or(u32::MAX)
+}
+
+/// Reads the card name of the video device from sysfs, which is readable without access to it.
+pub(super) fn read_card(path: &Path) -> Option<String> {
+    let name = path.file_name()?.to_str()?;
+    let card = fs::read_to_string(format!("/sys/class/video4linux/{}/name", name)).ok()?;
+
+    Some(card.trim().to_string())
+}
+
+/// Reads the serial of the USB device the video device belongs to from sysfs.
+///
+/// `/sys/class/video4linux/videoN/device` points to the USB interface, its parent is the device.
+fn read_serial(path: &Path) -> Option<String> {
+    let name = path.file_name()?.to_str()?;
+    let interface = fs::canonicalize(format!("/sys/class/video4linux/{}/device", name)).ok()?;
+    let serial = fs::read_to_string(interface.parent()?.join("serial")).ok()?;
+
+    Some(serial.trim().to_string()).filter(|serial| !serial.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    mod unit {
+        use crate::CameraDevice;
+        use std::path::Path;
+        use test_case::test_case;
+
+        fn device() -> CameraDevice {
+            CameraDevice {
+                path: "/dev/video2".to_string(),
+                card: "OBSBOT Tiny 2".to_string(),
+                bus_info: "usb-0000:00:14.0-2".to_string(),
+                serial: Some("ABC123".to_string()),
+            }
+        }
+
+        #[test_case("/dev/video2", true; "path")]
+        #[test_case("video2", true; "path without dev")]
+        #[test_case("usb-0000:00:14.0-2", true; "bus")]
+        #[test_case("ABC123", true; "serial")]
+        #[test_case("/dev/video0", false; "other path")]
+        #[test_case("usb-0000:00:14.0", false; "part of bus")]
+        fn matches(selector: &str, expected: bool) {
+            assert_eq!(device().matches(selector), expected);
+        }
+
+        #[test]
+        fn display() {
+            assert_eq!(device().to_string(), "OBSBOT Tiny 2 (/dev/video2)");
+        }
+
+        #[test_case("/dev/video0", 0; "single digit")]
+        #[test_case("/dev/video10", 10; "two digits")]
+        #[test_case("/dev/videoX", u32::MAX; "no number")]
+        fn video_number(path: &str, expected: u32) {
+            assert_eq!(super::super::video_number(Path::new(path)), expected);
+        }
+    }
+}
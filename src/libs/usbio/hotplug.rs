@@ -0,0 +1,141 @@
+// SPDX-License-Identifier: EUPL-1.2
+
+use crate::libs::errors::T4lError;
+use errno::Errno;
+use nix::sys::inotify::{AddWatchFlags, InitFlags, Inotify, InotifyEvent};
+use std::ffi::OsStr;
+use std::thread;
+use std::thread::JoinHandle;
+
+/// Directory the video devices are created in.
+const DEVICE_DIR: &str = "/dev";
+
+/// A video device that appeared or vanished, e.g. because a camera was plugged in or out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HotplugEvent {
+    /// The device was created or its permissions changed, so it may be opened now.
+    Added(String),
+    /// The device was removed.
+    Removed(String),
+}
+
+impl HotplugEvent {
+    /// Path of the video device, e.g. `/dev/video0`.
+    pub fn path(&self) -> &str {
+        match self {
+            HotplugEvent::Added(path) | HotplugEvent::Removed(path) => path,
+        }
+    }
+}
+
+/// Watches `/dev` for video devices being added or removed.
+///
+/// udev creates the device node first and adjusts its permissions afterward, so a change of the
+/// attributes is reported as [`HotplugEvent::Added`] too.
+pub struct HotplugWatcher {
+    inotify: Inotify,
+}
+
+impl HotplugWatcher {
+    pub fn new() -> Result<Self, T4lError> {
+        let inotify = Inotify::init(InitFlags::IN_CLOEXEC).map_err(to_error)?;
+        inotify
+            .add_watch(
+                DEVICE_DIR,
+                AddWatchFlags::IN_CREATE | AddWatchFlags::IN_DELETE | AddWatchFlags::IN_ATTRIB,
+            )
+            .map_err(to_error)?;
+
+        Ok(Self { inotify })
+    }
+
+    /// Blocks until video devices were added or removed and returns these changes.
+    pub fn wait(&self) -> Result<Vec<HotplugEvent>, T4lError> {
+        loop {
+            let events: Vec<_> = self
+                .inotify
+                .read_events()
+                .map_err(to_error)?
+                .iter()
+                .filter_map(to_hotplug_event)
+                .collect();
+
+            if !events.is_empty() {
+                return Ok(events);
+            }
+        }
+    }
+
+    /// Calls the callback on a background thread for every video device added or removed.
+    ///
+    /// The thread ends when the watcher fails or the callback returns `false`.
+    pub fn spawn<F>(self, mut callback: F) -> JoinHandle<Result<(), T4lError>>
+    where
+        F: FnMut(HotplugEvent) -> bool + Send + 'static,
+    {
+        thread::spawn(move || {
+            loop {
+                for event in self.wait()? {
+                    if !callback(event) {
+                        return Ok(());
+                    }
+                }
+            }
+        })
+    }
+}
+
+fn to_error(error: nix::Error) -> T4lError {
+    T4lError::from(Errno(error as i32))
+}
+
+fn to_hotplug_event(event: &InotifyEvent) -> Option<HotplugEvent> {
+    hotplug_event(event.mask, event.name.as_deref())
+}
+
+/// Maps an inotify event in `/dev` to a change of a video device, ignoring all other devices.
+fn hotplug_event(mask: AddWatchFlags, name: Option<&OsStr>) -> Option<HotplugEvent> {
+    let name = name?.to_str()?;
+    if !name.starts_with("video") {
+        return None;
+    }
+
+    let path = format!("{}/{}", DEVICE_DIR, name);
+    if mask.contains(AddWatchFlags::IN_DELETE) {
+        Some(HotplugEvent::Removed(path))
+    } else if mask.intersects(AddWatchFlags::IN_CREATE | AddWatchFlags::IN_ATTRIB) {
+        Some(HotplugEvent::Added(path))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod unit {
+        use super::super::{HotplugEvent, hotplug_event};
+        use nix::sys::inotify::AddWatchFlags;
+        use std::ffi::OsStr;
+        use test_case::test_case;
+
+        #[test_case(AddWatchFlags::IN_CREATE, "video0", Some(HotplugEvent::Added("/dev/video0".to_string())); "created")]
+        #[test_case(AddWatchFlags::IN_ATTRIB, "video2", Some(HotplugEvent::Added("/dev/video2".to_string())); "permissions changed")]
+        #[test_case(AddWatchFlags::IN_DELETE, "video0", Some(HotplugEvent::Removed("/dev/video0".to_string())); "deleted")]
+        #[test_case(AddWatchFlags::IN_CREATE, "ttyUSB0", None; "other device")]
+        #[test_case(AddWatchFlags::IN_MODIFY, "video0", None; "other event")]
+        fn maps_events(mask: AddWatchFlags, name: &str, expected: Option<HotplugEvent>) {
+            assert_eq!(hotplug_event(mask, Some(OsStr::new(name))), expected);
+        }
+
+        #[test]
+        fn ignores_events_without_name() {
+            assert_eq!(hotplug_event(AddWatchFlags::IN_CREATE, None), None);
+        }
+
+        #[test]
+        fn path() {
+            let event = HotplugEvent::Removed("/dev/video4".to_string());
+            assert_eq!(event.path(), "/dev/video4");
+        }
+    }
+}
@@ -0,0 +1,409 @@
+// SPDX-License-Identifier: EUPL-1.2
+
+#[cfg(feature = "dbus")]
+mod daemon;
+mod devices;
+mod hotplug;
+mod simulated;
+mod streaming;
+
+use crate::libs::errors::T4lError;
+use enum_dispatch::enum_dispatch;
+use errno::Errno;
+use glob::MatchOptions;
+use glob::glob_with;
+use nix::libc::EMSGSIZE;
+use nix::{Error, ioctl_read_buf, ioctl_readwrite, ioctl_readwrite_buf};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::ErrorKind;
+use std::os::unix::io::AsRawFd;
+use std::path::Path;
+use std::str;
+
+#[cfg(feature = "dbus")]
+pub use daemon::DaemonHandle;
+use devices::read_card;
+pub use devices::{CameraDevice, find_camera, list_cameras};
+pub use hotplug::{HotplugEvent, HotplugWatcher};
+pub use simulated::SimulatedCameraHandle;
+pub use streaming::is_streaming;
+
+/// Environment variable that replaces the real camera with a [`SimulatedCameraHandle`].
+pub const SIMULATED_CAMERA_ENV: &str = "T4L_SIMULATED_CAMERA";
+
+/// Control id of the absolute zoom, defined in linux/v4l2-controls.h
+pub const V4L2_CID_ZOOM_ABSOLUTE: u32 = 0x009a090d;
+/// Control id of the absolute pan in arc seconds, defined in linux/v4l2-controls.h
+pub const V4L2_CID_PAN_ABSOLUTE: u32 = 0x009a0908;
+/// Control id of the absolute tilt in arc seconds, defined in linux/v4l2-controls.h
+pub const V4L2_CID_TILT_ABSOLUTE: u32 = 0x009a0909;
+
+#[enum_dispatch(CameraHandleType)]
+pub trait UvcUsbIo {
+    fn info(&self) -> Result<CameraInfo, Errno>;
+    fn io(&self, unit: u8, selector: u8, query: u8, data: &mut [u8]) -> Result<(), Errno>;
+    fn get_ctrl(&self, id: u32) -> Result<i32, Errno>;
+    fn set_ctrl(&self, id: u32, value: i32) -> Result<(), Errno>;
+    fn query_ctrl(&self, id: u32) -> Result<ControlRange, Errno>;
+
+    /// Asks the selector for its length with `UVC_GET_LEN` and sends the request if the data
+    /// fits it, i.e. if a GET request has room for the answer and a SET request isn't longer.
+    /// Otherwise, it fails with `EMSGSIZE`.
+    ///
+    /// The daemon runs both requests as one operation, so that the requests of other clients
+    /// can't come in between. Returns the length of the selector.
+    fn sized_io(&self, unit: u8, selector: u8, query: u8, data: &mut [u8]) -> Result<usize, Errno> {
+        let mut length = [0u8; 2];
+        self.io(unit, selector, UVC_GET_LEN, &mut length)?;
+        let size = u16::from_le_bytes(length).into();
+
+        let fits = match query {
+            UVC_SET_CUR => data.len() <= size,
+            _ => data.len() >= size,
+        };
+        if !fits {
+            return Err(Errno(EMSGSIZE));
+        }
+
+        self.io(unit, selector, query, data)?;
+        Ok(size)
+    }
+}
+
+/// Name and bus of the camera behind a handle, as reported by [`UvcUsbIo::info`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CameraInfo {
+    pub card: String,
+    pub bus_info: String,
+}
+
+/// The range of values a V4L2 control accepts, as reported by the device.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ControlRange {
+    pub minimum: i32,
+    pub maximum: i32,
+    pub step: i32,
+    pub default: i32,
+}
+
+impl ControlRange {
+    pub fn contains(&self, value: i32) -> bool {
+        self.minimum <= value && value <= self.maximum
+    }
+}
+
+#[enum_dispatch]
+#[derive(Debug)]
+#[allow(clippy::enum_variant_names)] // the variants are named after the handle types
+pub enum CameraHandleType {
+    CameraHandle,
+    SimulatedCameraHandle,
+    #[cfg(feature = "dbus")]
+    DaemonHandle,
+}
+
+#[derive(Debug)]
+pub struct CameraHandle(File);
+
+impl From<File> for CameraHandle {
+    fn from(file: File) -> Self {
+        CameraHandle(file)
+    }
+}
+
+impl UvcUsbIo for CameraHandle {
+    fn info(&self) -> Result<CameraInfo, Errno> {
+        let video_info = v4l2_capability::new(&self.0)?;
+
+        Ok(CameraInfo {
+            card: video_info.card(),
+            bus_info: video_info.bus_info(),
+        })
+    }
+
+    fn io(&self, unit: u8, selector: u8, query: u8, data: &mut [u8]) -> Result<(), Errno> {
+        let dev = &self.0;
+
+        let query = uvc_xu_control_query {
+            unit,
+            selector,
+            query,
+            size: data.len() as u16,
+            data: data.as_mut_ptr(),
+        };
+
+        unsafe {
+            match uvcioc_ctrl_query(dev.as_raw_fd(), &mut [query]) {
+                Ok(_) => Ok(()),
+                _ => Err(Errno(Error::last_raw())),
+            }
+        }
+    }
+
+    fn get_ctrl(&self, id: u32) -> Result<i32, Errno> {
+        let mut control = v4l2_control { id, value: 0 };
+
+        unsafe {
+            match ioctl_videoc_g_ctrl(self.0.as_raw_fd(), &mut control) {
+                Ok(_) => Ok(control.value),
+                _ => Err(Errno(Error::last_raw())),
+            }
+        }
+    }
+
+    fn set_ctrl(&self, id: u32, value: i32) -> Result<(), Errno> {
+        let mut control = v4l2_control { id, value };
+
+        unsafe {
+            match ioctl_videoc_s_ctrl(self.0.as_raw_fd(), &mut control) {
+                Ok(_) => Ok(()),
+                _ => Err(Errno(Error::last_raw())),
+            }
+        }
+    }
+
+    fn query_ctrl(&self, id: u32) -> Result<ControlRange, Errno> {
+        let mut query = v4l2_queryctrl {
+            id,
+            ..Default::default()
+        };
+
+        unsafe {
+            match ioctl_videoc_queryctrl(self.0.as_raw_fd(), &mut query) {
+                Ok(_) => Ok(ControlRange {
+                    minimum: query.minimum,
+                    maximum: query.maximum,
+                    step: query.step,
+                    default: query.default_value,
+                }),
+                _ => Err(Errno(Error::last_raw())),
+            }
+        }
+    }
+}
+
+/// Opens the camera matching the hint, through the daemon if it's running and `via_daemon` is set.
+pub(crate) fn open_camera(hint: &str, via_daemon: bool) -> Result<CameraHandleType, T4lError> {
+    if std::env::var_os(SIMULATED_CAMERA_ENV).is_some() {
+        return Ok(SimulatedCameraHandle::default().into());
+    }
+
+    #[cfg(feature = "dbus")]
+    if via_daemon && let Some(handle) = DaemonHandle::connect(hint) {
+        return Ok(handle.into());
+    }
+    #[cfg(not(feature = "dbus"))]
+    let _ = via_daemon;
+
+    // a camera that can't be opened for lack of permissions is reported instead of being skipped
+    let mut permission_denied = false;
+
+    for path in [Path::new(hint).to_path_buf(), Path::new("/dev/").join(hint)] {
+        match File::open(path) {
+            Ok(file) => return Ok(CameraHandle::from(file).into()),
+            Err(error) => permission_denied |= error.kind() == ErrorKind::PermissionDenied,
+        }
+    }
+
+    // enumerate all cameras and check for match
+    let options = MatchOptions {
+        case_sensitive: true,
+        require_literal_separator: true,
+        require_literal_leading_dot: true,
+    };
+    for path in glob_with("/dev/video*", options).unwrap().flatten() {
+        match File::open(&path) {
+            Ok(device) => {
+                if v4l2_capability::new(&device).is_ok_and(|video_info| video_info.matches(hint)) {
+                    return Ok(CameraHandle::from(device).into());
+                }
+            }
+            // inaccessible devices of other cameras don't matter, so their name is checked in sysfs
+            Err(error) if error.kind() == ErrorKind::PermissionDenied => {
+                permission_denied |= read_card(&path).is_some_and(|card| card.contains(hint));
+            }
+            Err(_) => {}
+        }
+    }
+
+    if permission_denied {
+        return Err(T4lError::PermissionDenied);
+    }
+    Err(T4lError::NoCameraFound)
+}
+
+#[repr(C)]
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, Default, Debug)]
+pub struct v4l2_capability {
+    driver: [u8; 16],
+    card: [u8; 32],
+    bus_info: [u8; 32],
+    version: u32,
+    capabilities: u32,
+    device_caps: u32,
+    reserved: [u32; 3],
+}
+
+impl v4l2_capability {
+    fn new(dev: &File) -> Result<Self, Errno> {
+        let mut query = [v4l2_capability {
+            ..Default::default()
+        }];
+
+        unsafe {
+            match ioctl_videoc_querycap(dev.as_raw_fd(), &mut query) {
+                Ok(_) => Ok(query[0]),
+                _ => Err(Errno(Error::last_raw())),
+            }
+        }
+    }
+
+    fn card(&self) -> String {
+        c_string(&self.card)
+    }
+
+    fn bus_info(&self) -> String {
+        c_string(&self.bus_info)
+    }
+
+    /// Checks whether the card name or bus contains the hint, skipping metadata devices.
+    fn matches(&self, hint: &str) -> bool {
+        (self.card().contains(hint) || self.bus_info().contains(hint))
+            && (self.device_caps & V4L2_CAP_META_CAPTURE == 0)
+    }
+}
+
+/// Capability of the metadata devices, which are created next to each video device.
+const V4L2_CAP_META_CAPTURE: u32 = 0x800000;
+
+/// Converts a NUL-padded string of the kernel.
+fn c_string(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).to_string()
+}
+
+const VIDIOC_QUERYCAP_MAGIC: u8 = b'V';
+const VIDIOC_QUERYCAP_QUERY_MESSAGE: u8 = 0x00;
+ioctl_read_buf!(
+    ioctl_videoc_querycap,
+    VIDIOC_QUERYCAP_MAGIC,
+    VIDIOC_QUERYCAP_QUERY_MESSAGE,
+    v4l2_capability
+);
+
+#[repr(C)]
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, Default, Debug)]
+pub struct v4l2_control {
+    id: u32,
+    value: i32,
+}
+
+#[repr(C)]
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, Default, Debug)]
+pub struct v4l2_queryctrl {
+    id: u32,
+    control_type: u32,
+    name: [u8; 32],
+    minimum: i32,
+    maximum: i32,
+    step: i32,
+    default_value: i32,
+    flags: u32,
+    reserved: [u32; 2],
+}
+
+const VIDIOC_G_CTRL_MESSAGE: u8 = 27; // Defined in linux/videodev2.h
+const VIDIOC_S_CTRL_MESSAGE: u8 = 28; // Defined in linux/videodev2.h
+const VIDIOC_QUERYCTRL_MESSAGE: u8 = 36; // Defined in linux/videodev2.h
+ioctl_readwrite!(
+    ioctl_videoc_g_ctrl,
+    VIDIOC_QUERYCAP_MAGIC,
+    VIDIOC_G_CTRL_MESSAGE,
+    v4l2_control
+);
+ioctl_readwrite!(
+    ioctl_videoc_s_ctrl,
+    VIDIOC_QUERYCAP_MAGIC,
+    VIDIOC_S_CTRL_MESSAGE,
+    v4l2_control
+);
+ioctl_readwrite!(
+    ioctl_videoc_queryctrl,
+    VIDIOC_QUERYCAP_MAGIC,
+    VIDIOC_QUERYCTRL_MESSAGE,
+    v4l2_queryctrl
+);
+
+#[allow(non_camel_case_types)]
+#[repr(C)]
+pub struct uvc_xu_control_query {
+    unit: u8,
+    selector: u8,
+    query: u8, /* Video Class-Specific Request Code, */
+    /* defined in linux/usb/video.h A.8.  */
+    size: u16,
+    data: *mut u8,
+}
+
+#[allow(non_camel_case_types)]
+#[repr(C)]
+#[allow(dead_code)]
+pub struct uvc_menu_info {
+    name: [u8; 32],
+    value: u32,
+}
+
+#[allow(non_camel_case_types)]
+#[repr(C)]
+#[allow(dead_code)]
+pub struct uvc_xu_control_mapping {
+    id: u32,
+    name: [u8; 32],
+    entity: u8,
+    selector: u8,
+    size: u16,
+    offset: u16,
+    v4l2_type: u32,
+    data_type: u32,
+    uvc_menu_info: u32,
+    uvc_menu_count: u32,
+}
+
+const UVCIOC_CTRL_MAGIC: u8 = b'u'; // Defined in linux/uvcvideo.h
+const UVCIOC_CTRL_QUERY_MESSAGE: u8 = 0x21; // Defined in linux/uvcvideo.h
+ioctl_readwrite_buf!(
+    uvcioc_ctrl_query,
+    UVCIOC_CTRL_MAGIC,
+    UVCIOC_CTRL_QUERY_MESSAGE,
+    uvc_xu_control_query
+);
+ioctl_read_buf!(
+    uvcioc_ctrl_query_read,
+    UVCIOC_CTRL_MAGIC,
+    UVCIOC_CTRL_QUERY_MESSAGE,
+    uvc_xu_control_query
+);
+
+/* A.8. Video Class-Specific Request Codes */
+#[allow(dead_code)]
+const UVC_RC_UNDEFINED: u8 = 0x00;
+#[allow(dead_code)]
+pub const UVC_SET_CUR: u8 = 0x01;
+#[allow(dead_code)]
+pub const UVC_GET_CUR: u8 = 0x81;
+#[allow(dead_code)]
+pub const UVC_GET_MIN: u8 = 0x82;
+#[allow(dead_code)]
+pub const UVC_GET_MAX: u8 = 0x83;
+#[allow(dead_code)]
+pub const UVC_GET_RES: u8 = 0x84;
+#[allow(dead_code)]
+pub const UVC_GET_LEN: u8 = 0x85;
+#[allow(dead_code)]
+pub const UVC_GET_INFO: u8 = 0x86;
+#[allow(dead_code)]
+pub const UVC_GET_DEF: u8 = 0x87;
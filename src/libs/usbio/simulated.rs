@@ -0,0 +1,455 @@
+// SPDX-License-Identifier: EUPL-1.2
+
+use crate::libs::camera::{
+    FUNCTION_GROUP_PRESETS, FUNCTION_GROUP_SAVE_PRESET, FUNCTION_GROUP_SLEEP,
+    FUNCTION_GROUP_TRACKING_SPEED,
+};
+use crate::libs::usbio::devices::SIMULATED_DEVICE_PATH;
+use crate::libs::usbio::{
+    CameraInfo, ControlRange, UVC_GET_CUR, UVC_GET_INFO, UVC_GET_LEN, UVC_SET_CUR, UvcUsbIo,
+    V4L2_CID_PAN_ABSOLUTE, V4L2_CID_TILT_ABSOLUTE, V4L2_CID_ZOOM_ABSOLUTE,
+};
+use errno::Errno;
+use nix::libc::{EINVAL, EIO, ERANGE};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Size of the extension unit controls on selector 0x02 and 0x06.
+const XU_CONTROL_SIZE: usize = 60;
+
+/// Status blob reported by a real OBSBOT Tiny 2 while awake, with HDR on,
+/// upper body tracking and sport tracking speed.
+const INITIAL_STATUS: [u8; XU_CONTROL_SIZE] = [
+    0x27, 0x00, 0x00, 0x01, 0x42, 0x00, 0x01, 0x01, 0x01, 0x01, 0x88, 0xff, 0x00, 0x00, 0x01, 0x00,
+    0x00, 0x03, 0x00, 0x00, 0x01, 0x00, 0x21, 0x00, 0x02, 0x01, 0x03, 0x00, 0x01, 0x00, 0x00, 0x1e,
+    0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+];
+
+/// V4L2 controls of a real OBSBOT Tiny 2 with their ranges and initial values.
+const INITIAL_CONTROLS: [(u32, ControlRange, i32); 3] = [
+    (
+        V4L2_CID_ZOOM_ABSOLUTE,
+        ControlRange {
+            minimum: 0,
+            maximum: 100,
+            step: 1,
+            default: 0,
+        },
+        0,
+    ),
+    (
+        V4L2_CID_PAN_ABSOLUTE,
+        ControlRange {
+            minimum: -468000,
+            maximum: 468000,
+            step: 3600,
+            default: 0,
+        },
+        0,
+    ),
+    (
+        V4L2_CID_TILT_ABSOLUTE,
+        ControlRange {
+            minimum: -324000,
+            maximum: 324000,
+            step: 3600,
+            default: 0,
+        },
+        0,
+    ),
+];
+
+/// An in-memory stand-in for an OBSBOT Tiny 2.
+///
+/// It answers `UVC_GET_LEN` and `UVC_GET_CUR` on unit 0x2, selectors 0x02 and 0x06,
+/// like the real camera does and applies `UVC_SET_CUR` frames to its internal state.
+/// The standard V4L2 controls are kept in memory as well.
+/// This allows running the library and both binaries without a camera being plugged in,
+/// e.g. by setting the `T4L_SIMULATED_CAMERA` environment variable.
+#[derive(Debug)]
+pub struct SimulatedCameraHandle {
+    state: Box<Mutex<SimulatedState>>,
+}
+
+#[derive(Debug)]
+struct SimulatedState {
+    status: [u8; XU_CONTROL_SIZE],
+    last_frame: [u8; XU_CONTROL_SIZE],
+    controls: HashMap<u32, (ControlRange, i32)>,
+    presets: HashMap<u8, (i32, i32)>,
+}
+
+impl Default for SimulatedCameraHandle {
+    fn default() -> Self {
+        SimulatedCameraHandle {
+            state: Box::new(Mutex::new(SimulatedState {
+                status: INITIAL_STATUS,
+                last_frame: [0u8; XU_CONTROL_SIZE],
+                controls: INITIAL_CONTROLS
+                    .iter()
+                    .map(|(id, range, value)| (*id, (*range, *value)))
+                    .collect(),
+                presets: HashMap::new(),
+            })),
+        }
+    }
+}
+
+impl UvcUsbIo for SimulatedCameraHandle {
+    fn info(&self) -> Result<CameraInfo, Errno> {
+        Ok(CameraInfo {
+            card: "OBSBOT Tiny 2 (simulated)".to_string(),
+            bus_info: SIMULATED_DEVICE_PATH.to_string(),
+        })
+    }
+
+    fn io(&self, unit: u8, selector: u8, query: u8, data: &mut [u8]) -> Result<(), Errno> {
+        if unit != 0x2 || (selector != 0x2 && selector != 0x6) {
+            return Err(Errno(EINVAL));
+        }
+
+        let mut state = self.state.lock().map_err(|_| Errno(EIO))?;
+
+        match query {
+            UVC_GET_LEN => {
+                if data.len() != 2 {
+                    return Err(Errno(EINVAL));
+                }
+                data.copy_from_slice(&(XU_CONTROL_SIZE as u16).to_le_bytes());
+                Ok(())
+            }
+            // supports GET and SET requests
+            UVC_GET_INFO => {
+                if data.len() != 1 {
+                    return Err(Errno(EINVAL));
+                }
+                data[0] = 0x03;
+                Ok(())
+            }
+            UVC_GET_CUR => {
+                let source = if selector == 0x6 {
+                    &state.status
+                } else {
+                    &state.last_frame
+                };
+                let size = data.len().min(XU_CONTROL_SIZE);
+                data[..size].copy_from_slice(&source[..size]);
+                Ok(())
+            }
+            UVC_SET_CUR => {
+                if selector == 0x6 {
+                    state.apply_status_command(data);
+                } else {
+                    state.apply_command02(data);
+                }
+                Ok(())
+            }
+            _ => Err(Errno(EINVAL)),
+        }
+    }
+
+    fn get_ctrl(&self, id: u32) -> Result<i32, Errno> {
+        let state = self.state.lock().map_err(|_| Errno(EIO))?;
+
+        match state.controls.get(&id) {
+            Some((_, value)) => Ok(*value),
+            None => Err(Errno(EINVAL)),
+        }
+    }
+
+    fn set_ctrl(&self, id: u32, value: i32) -> Result<(), Errno> {
+        let mut state = self.state.lock().map_err(|_| Errno(EIO))?;
+
+        match state.controls.get_mut(&id) {
+            Some((range, _)) if !range.contains(value) => return Err(Errno(ERANGE)),
+            Some((_, current)) => *current = value,
+            None => return Err(Errno(EINVAL)),
+        }
+
+        Ok(())
+    }
+
+    fn query_ctrl(&self, id: u32) -> Result<ControlRange, Errno> {
+        let state = self.state.lock().map_err(|_| Errno(EIO))?;
+
+        match state.controls.get(&id) {
+            Some((range, _)) => Ok(*range),
+            None => Err(Errno(EINVAL)),
+        }
+    }
+}
+
+impl SimulatedState {
+    fn apply_status_command(&mut self, data: &[u8]) {
+        match data {
+            // The camera reports hand tracking as 0x06, although it is requested with 0x03
+            [0x16, 0x02, 0x03, secondary, ..] => {
+                self.status[0x18] = 0x06;
+                self.status[0x1c] = *secondary;
+            }
+            [0x16, 0x02, primary, secondary, ..] => {
+                self.status[0x18] = *primary;
+                self.status[0x1c] = *secondary;
+            }
+            [0x01, 0x01, hdr, ..] => self.status[0x06] = *hdr,
+            _ => {}
+        }
+    }
+
+    fn apply_command02(&mut self, data: &[u8]) {
+        let size = data.len().min(XU_CONTROL_SIZE);
+        self.last_frame = [0u8; XU_CONTROL_SIZE];
+        self.last_frame[..size].copy_from_slice(&data[..size]);
+
+        if size < 20 || data[0..2] != [0xaa, 0x25] {
+            return;
+        }
+
+        let function_group: [u8; 6] = data[8..14].try_into().unwrap();
+        let value = data[16];
+
+        match function_group {
+            FUNCTION_GROUP_SLEEP => self.status[0x02] = value,
+            FUNCTION_GROUP_TRACKING_SPEED => self.status[0x21] = value,
+            FUNCTION_GROUP_SAVE_PRESET => {
+                let position = (
+                    self.control_value(V4L2_CID_PAN_ABSOLUTE),
+                    self.control_value(V4L2_CID_TILT_ABSOLUTE),
+                );
+                self.presets.insert(value, position);
+            }
+            FUNCTION_GROUP_PRESETS => {
+                if let Some((pan, tilt)) = self.presets.get(&value).copied() {
+                    self.set_control_value(V4L2_CID_PAN_ABSOLUTE, pan);
+                    self.set_control_value(V4L2_CID_TILT_ABSOLUTE, tilt);
+                }
+            }
+            // The offsets of the exposure mode in the status aren't confirmed, so it isn't reported
+            _ => {}
+        }
+    }
+
+    fn control_value(&self, id: u32) -> i32 {
+        self.controls.get(&id).map_or(0, |(_, value)| *value)
+    }
+
+    fn set_control_value(&mut self, id: u32, value: i32) {
+        if let Some((_, current)) = self.controls.get_mut(&id) {
+            *current = value;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod unit {
+        use crate::libs::usbio::{
+            SimulatedCameraHandle, UVC_GET_CUR, UVC_GET_LEN, UVC_SET_CUR, UvcUsbIo,
+            V4L2_CID_ZOOM_ABSOLUTE,
+        };
+        use assertables::assert_err;
+        use test_case::test_case;
+
+        #[test]
+        fn get_len() {
+            let handle = SimulatedCameraHandle::default();
+            let mut data = [0u8; 2];
+
+            handle.io(0x2, 0x6, UVC_GET_LEN, &mut data).unwrap();
+
+            assert_eq!(u16::from_le_bytes(data), 60, "controls should be 60 bytes");
+        }
+
+        #[test]
+        fn get_cur_returns_initial_status() {
+            let handle = SimulatedCameraHandle::default();
+            let mut data = [0u8; 60];
+
+            handle.io(0x2, 0x6, UVC_GET_CUR, &mut data).unwrap();
+
+            assert_eq!(data[0x00], 0x27, "status should start like a real one");
+            assert_eq!(data[0x18], 0x02, "status should contain tracking");
+        }
+
+        #[test]
+        fn set_cur_applies_status_command() {
+            let handle = SimulatedCameraHandle::default();
+            let mut command = [0u8; 60];
+            command[..3].copy_from_slice(&[0x01, 0x01, 0x00]);
+            let mut data = [0u8; 60];
+
+            handle.io(0x2, 0x6, UVC_SET_CUR, &mut command).unwrap();
+            handle.io(0x2, 0x6, UVC_GET_CUR, &mut data).unwrap();
+
+            assert_eq!(data[0x06], 0x00, "HDR should be turned off");
+        }
+
+        #[test]
+        fn set_cur_echoes_command02() {
+            let handle = SimulatedCameraHandle::default();
+            let mut command = [0u8; 60];
+            command[..4].copy_from_slice(&[0xaa, 0x25, 0x01, 0x00]);
+            let mut data = [0u8; 60];
+
+            handle.io(0x2, 0x2, UVC_SET_CUR, &mut command).unwrap();
+            handle.io(0x2, 0x2, UVC_GET_CUR, &mut data).unwrap();
+
+            assert_eq!(data, command, "selector 0x02 should echo the last frame");
+        }
+
+        #[test]
+        fn controls() {
+            let handle = SimulatedCameraHandle::default();
+
+            handle.set_ctrl(V4L2_CID_ZOOM_ABSOLUTE, 42).unwrap();
+
+            assert_eq!(handle.get_ctrl(V4L2_CID_ZOOM_ABSOLUTE).unwrap(), 42);
+            assert_err!(
+                handle.set_ctrl(V4L2_CID_ZOOM_ABSOLUTE, 101),
+                "values out of range should be rejected"
+            );
+            assert_err!(handle.get_ctrl(0x00), "unknown controls should be rejected");
+        }
+
+        #[test_case(0x1, 0x6; "unknown unit")]
+        #[test_case(0x2, 0x3; "unknown selector")]
+        fn invalid_control(unit: u8, selector: u8) {
+            let handle = SimulatedCameraHandle::default();
+            let mut data = [0u8; 2];
+
+            assert_err!(
+                handle.io(unit, selector, UVC_GET_LEN, &mut data),
+                "unknown controls should be rejected"
+            );
+        }
+    }
+
+    mod integration {
+        mod camera {
+            use crate::{
+                AIMode, Camera, ExposureMode, PanTilt, SleepMode, Tiny2Camera, TrackingSpeed,
+            };
+            use assertables::{assert_err, assert_ok};
+            use test_case::test_case;
+
+            #[test]
+            fn info() {
+                let info = Camera::simulated().info().unwrap();
+
+                assert_eq!(info.card, "OBSBOT Tiny 2 (simulated)");
+                assert_eq!(info.bus_info, "simulated");
+            }
+
+            #[test_case(SleepMode::Sleep; "sleep")]
+            #[test_case(SleepMode::Awake; "awake")]
+            fn sleep_mode(mode: SleepMode) {
+                let camera = Camera::simulated();
+
+                camera.set_sleep_mode(mode).unwrap();
+
+                assert_eq!(camera.get_sleep_mode().unwrap(), mode);
+            }
+
+            #[test_case(AIMode::NoTracking; "no tracking")]
+            #[test_case(AIMode::NormalTracking; "normal tracking")]
+            #[test_case(AIMode::CloseUp; "close up")]
+            #[test_case(AIMode::Whiteboard; "whiteboard")]
+            #[test_case(AIMode::Hand; "hand")]
+            #[test_case(AIMode::Group; "group")]
+            fn ai_mode(mode: AIMode) {
+                let camera = Camera::simulated();
+
+                camera.set_ai_mode(mode).unwrap();
+
+                assert_eq!(camera.get_ai_mode().unwrap(), mode);
+            }
+
+            #[test_case(TrackingSpeed::Standard; "standard")]
+            #[test_case(TrackingSpeed::Sport; "sport")]
+            fn tracking_speed(speed: TrackingSpeed) {
+                let camera = Camera::simulated();
+
+                camera.set_tracking_speed(speed).unwrap();
+
+                assert_eq!(camera.get_tracking_speed().unwrap(), speed);
+            }
+
+            #[test_case(true; "on")]
+            #[test_case(false; "off")]
+            fn hdr_mode(mode: bool) {
+                let camera = Camera::simulated();
+
+                camera.set_hdr_mode(mode).unwrap();
+
+                assert_eq!(camera.get_status().unwrap().hdr_on, mode);
+            }
+
+            #[test_case(ExposureMode::Manual; "manual")]
+            #[test_case(ExposureMode::Global; "global")]
+            #[test_case(ExposureMode::Face; "face")]
+            fn exposure_mode(mode: ExposureMode) {
+                let camera = Camera::simulated();
+
+                assert_ok!(camera.set_exposure_mode(mode));
+            }
+
+            #[test]
+            fn zoom() {
+                let camera = Camera::simulated();
+
+                camera.set_zoom(50).unwrap();
+
+                assert_eq!(camera.get_zoom().unwrap(), 50);
+                assert_eq!(camera.get_zoom_range().unwrap().maximum, 100);
+                assert_err!(camera.set_zoom(-1), "zoom out of range should be rejected");
+            }
+
+            #[test_case(PanTilt::Absolute { pan: 10.0, tilt: -20.0 }, (10.0, -20.0); "absolute")]
+            #[test_case(PanTilt::Relative { pan: -2, tilt: 1 }, (-10.0, 5.0); "relative")]
+            #[test_case(PanTilt::Relative { pan: 100, tilt: -100 }, (130.0, -90.0); "relative beyond the limits")]
+            fn pan_tilt(movement: PanTilt, expected: (f64, f64)) {
+                let camera = Camera::simulated();
+
+                camera.set_pan_tilt(movement).unwrap();
+
+                assert_eq!(camera.get_pan_tilt().unwrap(), expected);
+            }
+
+            #[test]
+            #[cfg(feature = "experimental")]
+            fn save_preset_position() {
+                let camera = Camera::simulated();
+                let position = PanTilt::Absolute {
+                    pan: 30.0,
+                    tilt: 15.0,
+                };
+
+                camera.set_pan_tilt(position).unwrap();
+                camera.save_preset_position(1).unwrap();
+                camera
+                    .set_pan_tilt(PanTilt::Absolute {
+                        pan: 0.0,
+                        tilt: 0.0,
+                    })
+                    .unwrap();
+                camera.goto_preset_position(1).unwrap();
+
+                assert_eq!(camera.get_pan_tilt().unwrap(), (30.0, 15.0));
+            }
+
+            #[test]
+            fn pan_tilt_out_of_range() {
+                let camera = Camera::simulated();
+
+                assert_err!(
+                    camera.set_pan_tilt(PanTilt::Absolute {
+                        pan: 180.0,
+                        tilt: 0.0
+                    }),
+                    "positions out of range should be rejected"
+                );
+            }
+        }
+    }
+}
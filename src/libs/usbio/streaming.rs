@@ -0,0 +1,74 @@
+// SPDX-License-Identifier: EUPL-1.2
+
+use crate::libs::errors::T4lError;
+use crate::libs::usbio::VIDIOC_QUERYCAP_MAGIC;
+use crate::libs::usbio::devices::SIMULATED_DEVICE_PATH;
+use errno::Errno;
+use nix::ioctl_readwrite;
+use std::fs::OpenOptions;
+use std::os::unix::io::AsRawFd;
+
+const V4L2_BUF_TYPE_VIDEO_CAPTURE: u32 = 1; // Defined in linux/videodev2.h
+const V4L2_MEMORY_MMAP: u32 = 1; // Defined in linux/videodev2.h
+
+#[repr(C)]
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, Default, Debug)]
+pub struct v4l2_requestbuffers {
+    count: u32,
+    buffer_type: u32,
+    memory: u32,
+    capabilities: u32,
+    flags: u8,
+    reserved: [u8; 3],
+}
+
+const VIDIOC_REQBUFS_MESSAGE: u8 = 8; // Defined in linux/videodev2.h
+ioctl_readwrite!(
+    ioctl_videoc_reqbufs,
+    VIDIOC_QUERYCAP_MAGIC,
+    VIDIOC_REQBUFS_MESSAGE,
+    v4l2_requestbuffers
+);
+
+/// Checks whether another application is streaming from the video device, e.g. a meeting tool.
+///
+/// Only one application can own the buffers of a device. Requesting no buffers fails with
+/// `EBUSY` while another one owns them and otherwise doesn't change anything, so it's used as probe.
+/// The simulated camera is never streaming.
+pub fn is_streaming(path: &str) -> Result<bool, T4lError> {
+    if path == SIMULATED_DEVICE_PATH {
+        return Ok(false);
+    }
+
+    let device = OpenOptions::new().read(true).write(true).open(path)?;
+    let mut request = v4l2_requestbuffers {
+        count: 0,
+        buffer_type: V4L2_BUF_TYPE_VIDEO_CAPTURE,
+        memory: V4L2_MEMORY_MMAP,
+        ..Default::default()
+    };
+
+    match unsafe { ioctl_videoc_reqbufs(device.as_raw_fd(), &mut request) } {
+        Ok(_) => Ok(false),
+        Err(nix::Error::EBUSY) => Ok(true),
+        Err(error) => Err(T4lError::from(Errno(error as i32))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod unit {
+        use crate::is_streaming;
+
+        #[test]
+        fn simulated_camera_isnt_streaming() {
+            assert!(!is_streaming("simulated").unwrap());
+        }
+
+        #[test]
+        fn missing_device() {
+            assert!(is_streaming("/dev/video-missing").is_err());
+        }
+    }
+}